@@ -1,4 +1,4 @@
-/// Minimum collateral in lamports (0.01 SOL)
+/// Default minimum collateral in lamports (0.01 SOL)
 pub const MIN_COLLATERAL: u64 = 10_000_000;
 
 /// Minimum escrow duration in seconds (1 minute — devnet testing)
@@ -7,24 +7,134 @@ pub const MIN_DURATION: i64 = 60;
 /// Maximum escrow duration in seconds (7 days)
 pub const MAX_DURATION: i64 = 604_800;
 
-/// Protocol fee in basis points (0% — folded into trade fee split)
+/// Default protocol fee in basis points (0% — folded into trade fee split)
 pub const PROTOCOL_FEE_BPS: u64 = 0;
 
-/// Trade fee in basis points (1% = 100 bps, split 50/50: deployer + treasury)
+/// Default trade fee in basis points (1% = 100 bps, split 50/50: deployer + treasury)
 pub const TRADE_FEE_BPS: u64 = 100;
 
-/// Deployer fee share in basis points (50 = 0.5% of trade volume)
+/// Default deployer fee share in basis points (50 = 0.5% of trade volume)
 pub const DEPLOYER_FEE_BPS: u64 = 50;
 
-/// Deploy fee in lamports (0.02 SOL)
+/// Default deploy fee in lamports (0.02 SOL)
 pub const DEPLOY_FEE: u64 = 20_000_000;
 
-/// Operations wallet share of treasury fees in basis points (40% = 4000 bps)
+/// Default operations wallet share of treasury fees in basis points (40% = 4000 bps)
 pub const OPS_SHARE_BPS: u64 = 4000;
 
+/// Fee schedule ceiling: trade fee (5%)
+pub const MAX_TRADE_FEE_BPS: u64 = 500;
+
+/// Fee schedule ceiling: protocol fee on top of the trade fee (2%)
+pub const MAX_PROTOCOL_FEE_BPS: u64 = 200;
+
+/// Fee schedule ceiling: trade fee and protocol fee combined (6%)
+pub const MAX_TOTAL_FEE_BPS: u64 = 600;
+
+/// Fee schedule ceiling: deploy fee (1 SOL)
+pub const MAX_DEPLOY_FEE: u64 = 1_000_000_000;
+
+/// Fee schedule ceiling: minimum collateral requirement (10 SOL)
+pub const MAX_MIN_COLLATERAL: u64 = 10_000_000_000;
+
+/// Fixed-point scale for curve prices (lamports per base token unit × 1e12)
+pub const PRICE_SCALE: u128 = 1_000_000_000_000;
+
+/// Fixed-point scale for exponential curve growth (1e18 = 1.0)
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+
+/// Maximum number of price levels in a step curve
+pub const MAX_CURVE_STEPS: usize = 8;
+
+/// Default curve bound: lowest marginal price (PRICE_SCALE units)
+pub const CURVE_MIN_PRICE: u64 = 1_000_000;
+
+/// Default curve bound: highest marginal price (PRICE_SCALE units)
+pub const CURVE_MAX_PRICE: u64 = 1_000_000_000_000;
+
+/// Default curve bound: exponential growth exponent over the curve (5.0 WAD)
+pub const CURVE_MAX_GROWTH_WAD: u64 = 5_000_000_000_000_000_000;
+
+/// Curve bound ceiling: marginal price (keeps u128 pricing math in range)
+pub const MAX_CURVE_PRICE: u64 = 1_000_000_000_000_000;
+
+/// Curve bound ceiling: exponential growth exponent (10.0 WAD)
+pub const MAX_CURVE_GROWTH_WAD: u64 = 10_000_000_000_000_000_000;
+
+/// Curve bound floor: exponential growth exponent (0.001 WAD — use linear below this)
+pub const MIN_CURVE_GROWTH_WAD: u64 = 1_000_000_000_000_000;
+
+/// Default launch protection window in seconds (60s after launch)
+pub const PROTECTION_WINDOW_SECONDS: i64 = 60;
+
+/// Default launch protection cap: gross SOL per wallet during the window (1 SOL)
+pub const PROTECTION_MAX_SOL_PER_WALLET: u64 = 1_000_000_000;
+
+/// Launch protection ceiling: window length (1 hour)
+pub const MAX_PROTECTION_WINDOW: i64 = 3_600;
+
+/// Sniper tax ceiling: total buy fee at launch, protocol fee included (50%)
+pub const MAX_SNIPER_FEE_BPS: u64 = 5_000;
+
+/// Sniper tax ceiling: decay duration (1 hour)
+pub const MAX_SNIPER_TAX_DURATION: i64 = 3_600;
+
+/// ln(2) in WAD — converts a sniper tax half-life into an exponent
+pub const LN2_WAD: u128 = 693_147_180_559_945_309;
+
+/// Maximum delay between launch and a scheduled trading open time (7 days)
+pub const MAX_OPEN_DELAY: i64 = 604_800;
+
+/// Maximum presale length before public trading opens (3 days)
+pub const MAX_PRESALE_DURATION: i64 = 259_200;
+
+/// Maximum batch-auction opening window (1 day)
+pub const MAX_OPENING_DURATION: i64 = 86_400;
+
+/// Smallest non-zero per-wallet holding cap (0.5% of supply)
+pub const MIN_MAX_WALLET_BPS: u64 = 50;
+
+/// Default post-graduation safety period before any collateral unlocks (24 hours)
+pub const COLLATERAL_LOCK_SECONDS: i64 = 86_400;
+
+/// Default collateral unlock duration after the safety period (7 days)
+pub const COLLATERAL_UNLOCK_SECONDS: i64 = 604_800;
+
+/// Collateral lock ceiling: safety period (30 days)
+pub const MAX_COLLATERAL_LOCK: i64 = 2_592_000;
+
+/// Collateral lock ceiling: unlock duration (90 days)
+pub const MAX_COLLATERAL_UNLOCK: i64 = 7_776_000;
+
+/// Collateral lock ceiling: tranches in a stepped unlock
+pub const MAX_UNLOCK_STEPS: u8 = 52;
+
+/// Default share of the tokens they acquired that a deployer may sell while
+/// their collateral is locked (50%); selling more makes the escrow slashable
+pub const DUMP_THRESHOLD_BPS: u64 = 5_000;
+
+/// Most holders a single refund snapshot can cover (claimed bitmap = 8 KiB)
+pub const MAX_SNAPSHOT_LEAVES: u32 = 65_536;
+
+/// How long holders can claim snapshot refunds before the snapshot can be
+/// closed and its unclaimed lamports swept (30 days)
+pub const SNAPSHOT_CLAIM_SECONDS: i64 = 2_592_000;
+
 /// Escrow PDA seed
 pub const ESCROW_SEED: &[u8] = b"escrow";
 
+/// Deploy fee waiver PDA seed
+pub const FEE_WAIVER_SEED: &[u8] = b"fee_waiver";
+
+/// Wallet position PDA seed
+pub const POSITION_SEED: &[u8] = b"position";
+
+/// Refund snapshot PDA seed
+pub const REFUND_SNAPSHOT_SEED: &[u8] = b"refund_snapshot";
+
+/// Batch-opening commit PDA seed
+pub const COMMIT_SEED: &[u8] = b"commit";
+
 /// Bonding curve PDA seed
 pub const CURVE_SEED: &[u8] = b"curve";
 
@@ -37,21 +147,45 @@ pub const PROTOCOL_SEED: &[u8] = b"protocol";
 /// Graduation threshold in lamports (5 SOL — devnet testing)
 pub const GRADUATION_THRESHOLD: u64 = 5_000_000_000;
 
-/// Token decimals for SPL tokens
+/// Default token decimals for SPL tokens
 pub const TOKEN_DECIMALS: u8 = 6;
 
-/// Initial virtual token reserves (pump.fun style, TOKEN_DECIMALS=6)
+/// Reference virtual token reserves (pump.fun style, TOKEN_DECIMALS=6).
+/// Launches keep the INITIAL_VIRTUAL_TOKEN_RESERVES : INITIAL_REAL_TOKEN_RESERVES
+/// ratio when deriving virtual token reserves from their curve allocation.
 pub const INITIAL_VIRTUAL_TOKEN_RESERVES: u64 = 1_073_000_000_000_000;
 
-/// Initial virtual SOL reserves in lamports (30 SOL)
+/// Default initial virtual SOL reserves in lamports (30 SOL)
 pub const INITIAL_VIRTUAL_SOL_RESERVES: u64 = 30_000_000_000;
 
-/// Initial real token reserves (tokens available for bonding curve sale)
+/// Reference real token reserves (tokens available for bonding curve sale)
 pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
 
-/// Total token supply (1 billion with 6 decimals)
+/// Default total token supply (1 billion with 6 decimals)
 pub const TOKEN_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
 
+/// Default share of supply sold on the curve in basis points (rest reserved for DEX liquidity)
+pub const CURVE_TOKEN_BPS: u64 = 7931;
+
+/// Default launch bound: total supply in whole tokens (1 million – 1 trillion)
+pub const LAUNCH_MIN_TOTAL_SUPPLY: u64 = 1_000_000;
+pub const LAUNCH_MAX_TOTAL_SUPPLY: u64 = 1_000_000_000_000;
+
+/// Default launch bound: token decimals
+pub const LAUNCH_MIN_DECIMALS: u8 = 0;
+pub const LAUNCH_MAX_DECIMALS: u8 = 9;
+
+/// Default launch bound: initial virtual SOL reserves (1 – 1,000 SOL)
+pub const LAUNCH_MIN_VIRTUAL_SOL: u64 = 1_000_000_000;
+pub const LAUNCH_MAX_VIRTUAL_SOL: u64 = 1_000_000_000_000;
+
+/// Default launch bound: curve share of supply in basis points (50% – 95%)
+pub const LAUNCH_MIN_CURVE_TOKEN_BPS: u64 = 5_000;
+pub const LAUNCH_MAX_CURVE_TOKEN_BPS: u64 = 9_500;
+
+/// Launch bound ceiling: token decimals
+pub const MAX_TOKEN_DECIMALS: u8 = 9;
+
 /// Wrapped SOL mint address
 pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
 
@@ -0,0 +1,537 @@
+//! Bonding curve pricing shapes.
+//!
+//! `CurveKind::ConstantProduct` prices off the curve's virtual reserves
+//! (pump.fun-style x*y=k). The other shapes define a marginal price p(s) over
+//! the sold supply s and price every trade from its integral F(s), so the
+//! reserve always covers F(sold). Inverses use binary search and round in the
+//! curve's favour.
+
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::FyrstError;
+use crate::state::CurveKind;
+
+/// e^n in WAD for n = 0..=11 (integer part of the exponential curve exponent)
+const EXP_WAD_TABLE: [u128; 12] = [
+    1_000_000_000_000_000_000,
+    2_718_281_828_459_045_235,
+    7_389_056_098_930_650_227,
+    20_085_536_923_187_667_740,
+    54_598_150_033_144_239_078,
+    148_413_159_102_576_603_421,
+    403_428_793_492_735_122_608,
+    1_096_633_158_428_458_599_263,
+    2_980_957_987_041_728_274_743,
+    8_103_083_927_575_384_007_709,
+    22_026_465_794_806_716_516_957,
+    59_874_141_715_197_818_455_326,
+];
+
+/// Curve state a pricing shape quotes against
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CurvePosition {
+    /// Virtual token reserves (constant product only)
+    pub virtual_token_reserves: u64,
+    /// Virtual SOL reserves (constant product only)
+    pub virtual_sol_reserves: u64,
+    /// Tokens sold via the curve so far
+    pub sold: u64,
+    /// Tokens the curve can sell in total (sold + remaining real reserves)
+    pub allocation: u64,
+}
+
+/// Pricing interface shared by buy, sell and DEX migration
+pub trait CurvePricing {
+    /// Tokens bought for `net_sol` (after fees), rounded down
+    fn tokens_out(&self, pos: &CurvePosition, net_sol: u64) -> Result<u64>;
+
+    /// Net SOL (after fees) needed to buy exactly `tokens`, rounded up
+    fn sol_in(&self, pos: &CurvePosition, tokens: u64) -> Result<u64>;
+
+    /// Gross SOL (before fees) released for selling `tokens`, rounded down
+    fn sol_out(&self, pos: &CurvePosition, tokens: u64) -> Result<u64>;
+
+    /// Tokens needed to release exactly `gross_sol` (before fees), rounded up
+    fn tokens_in(&self, pos: &CurvePosition, gross_sol: u64) -> Result<u64>;
+
+    /// Marginal price in lamports per base token unit, scaled by `PRICE_SCALE`
+    fn spot_price(&self, pos: &CurvePosition) -> Result<u128>;
+
+    /// Tokens worth `sol` at the current spot price (DEX pool sizing)
+    fn tokens_at_spot(&self, pos: &CurvePosition, sol: u64) -> Result<u64>;
+
+    /// SOL worth `tokens` at the current spot price, rounded down (DEX pool
+    /// sizing when the pool is capped by the tokens left to pair)
+    fn sol_at_spot(&self, pos: &CurvePosition, tokens: u64) -> Result<u64>;
+}
+
+impl CurvePricing for CurveKind {
+    fn tokens_out(&self, pos: &CurvePosition, net_sol: u64) -> Result<u64> {
+        if let CurveKind::ConstantProduct = self {
+            // tokens_out = virtual_token - ceil(k / (virtual_sol + net_sol))
+            let (vt, vs, k) = cp_reserves(pos)?;
+            let new_vs = vs.checked_add(net_sol as u128).ok_or(FyrstError::MathOverflow)?;
+            let new_vt = div_ceil(k, new_vs)?;
+            return to_u64(vt.checked_sub(new_vt).ok_or(FyrstError::MathOverflow)?);
+        }
+
+        // Largest t with F(sold + t) - F(sold) <= net_sol. Searching one past the
+        // remaining allocation lets callers detect an oversized buy.
+        let base = self.cost_to(pos.sold, pos.allocation)?;
+        let remaining = pos.allocation.checked_sub(pos.sold).ok_or(FyrstError::MathOverflow)?;
+        let hi = remaining.checked_add(1).ok_or(FyrstError::MathOverflow)?;
+        search_max(hi, |t| {
+            let end = pos.sold.checked_add(t).ok_or(FyrstError::MathOverflow)?;
+            let cost = self
+                .cost_to(end, pos.allocation)?
+                .checked_sub(base)
+                .ok_or(FyrstError::MathOverflow)?;
+            Ok(cost <= net_sol as u128)
+        })
+    }
+
+    fn sol_in(&self, pos: &CurvePosition, tokens: u64) -> Result<u64> {
+        if let CurveKind::ConstantProduct = self {
+            // net_sol = ceil(k / (virtual_token - tokens)) - virtual_sol
+            let (vt, vs, k) = cp_reserves(pos)?;
+            let new_vt = vt.checked_sub(tokens as u128).ok_or(FyrstError::MathOverflow)?;
+            require!(new_vt > 0, FyrstError::InsufficientTokens);
+            let new_vs = div_ceil(k, new_vt)?;
+            return to_u64(new_vs.checked_sub(vs).ok_or(FyrstError::MathOverflow)?);
+        }
+
+        let end = pos.sold.checked_add(tokens).ok_or(FyrstError::MathOverflow)?;
+        let cost = self
+            .cost_to(end, pos.allocation)?
+            .checked_sub(self.cost_to(pos.sold, pos.allocation)?)
+            .ok_or(FyrstError::MathOverflow)?;
+        to_u64(cost)
+    }
+
+    fn sol_out(&self, pos: &CurvePosition, tokens: u64) -> Result<u64> {
+        if let CurveKind::ConstantProduct = self {
+            // sol_out = virtual_sol - ceil(k / (virtual_token + tokens))
+            let (vt, vs, k) = cp_reserves(pos)?;
+            let new_vt = vt.checked_add(tokens as u128).ok_or(FyrstError::MathOverflow)?;
+            let new_vs = div_ceil(k, new_vt)?;
+            return to_u64(vs.checked_sub(new_vs).ok_or(FyrstError::MathOverflow)?);
+        }
+
+        let start = pos.sold.checked_sub(tokens).ok_or(FyrstError::InsufficientReserve)?;
+        let proceeds = self
+            .cost_to(pos.sold, pos.allocation)?
+            .checked_sub(self.cost_to(start, pos.allocation)?)
+            .ok_or(FyrstError::MathOverflow)?;
+        to_u64(proceeds)
+    }
+
+    fn tokens_in(&self, pos: &CurvePosition, gross_sol: u64) -> Result<u64> {
+        if let CurveKind::ConstantProduct = self {
+            // tokens = ceil(k / (virtual_sol - gross_sol)) - virtual_token
+            let (vt, vs, k) = cp_reserves(pos)?;
+            let new_vs = vs.checked_sub(gross_sol as u128).ok_or(FyrstError::MathOverflow)?;
+            require!(new_vs > 0, FyrstError::InsufficientReserve);
+            let new_vt = div_ceil(k, new_vs)?;
+            return to_u64(new_vt.checked_sub(vt).ok_or(FyrstError::MathOverflow)?);
+        }
+
+        // Smallest t with F(sold) - F(sold - t) >= gross_sol
+        let top = self.cost_to(pos.sold, pos.allocation)?;
+        let proceeds = |t: u64| -> Result<u128> {
+            let start = pos.sold.checked_sub(t).ok_or(FyrstError::MathOverflow)?;
+            top.checked_sub(self.cost_to(start, pos.allocation)?)
+                .ok_or(FyrstError::MathOverflow.into())
+        };
+        let short = search_max(pos.sold, |t| Ok(proceeds(t)? < gross_sol as u128))?;
+        require!(short < pos.sold, FyrstError::InsufficientReserve);
+        let tokens = short + 1;
+        require!(proceeds(tokens)? >= gross_sol as u128, FyrstError::InsufficientReserve);
+        Ok(tokens)
+    }
+
+    fn spot_price(&self, pos: &CurvePosition) -> Result<u128> {
+        let s = pos.sold as u128;
+        let alloc = pos.allocation as u128;
+        match *self {
+            CurveKind::ConstantProduct => {
+                let (vt, vs, _) = cp_reserves(pos)?;
+                vs.checked_mul(PRICE_SCALE)
+                    .ok_or(FyrstError::MathOverflow)?
+                    .checked_div(vt)
+                    .ok_or(FyrstError::MathOverflow.into())
+            }
+            CurveKind::Linear { start_price, end_price } => {
+                require!(alloc > 0, FyrstError::InvalidPrice);
+                let slope = (end_price as u128)
+                    .checked_sub(start_price as u128)
+                    .ok_or(FyrstError::MathOverflow)?;
+                let rise = slope
+                    .checked_mul(s)
+                    .ok_or(FyrstError::MathOverflow)?
+                    .checked_div(alloc)
+                    .ok_or(FyrstError::MathOverflow)?;
+                (start_price as u128).checked_add(rise).ok_or(FyrstError::MathOverflow.into())
+            }
+            CurveKind::Exponential { start_price, growth_wad } => {
+                require!(alloc > 0, FyrstError::InvalidPrice);
+                let x = (growth_wad as u128)
+                    .checked_mul(s)
+                    .ok_or(FyrstError::MathOverflow)?
+                    .checked_div(alloc)
+                    .ok_or(FyrstError::MathOverflow)?;
+                mul_wad(exp_wad(x)?, start_price as u128)
+            }
+            CurveKind::Step { prices, count } => {
+                let (segment, count) = step_segment(pos.allocation, count)?;
+                let index = (pos.sold / segment).min(count as u64 - 1) as usize;
+                Ok(prices[index] as u128)
+            }
+        }
+    }
+
+    fn tokens_at_spot(&self, pos: &CurvePosition, sol: u64) -> Result<u64> {
+        if let CurveKind::ConstantProduct = self {
+            // pool_tokens = sol * virtual_token / virtual_sol
+            let (vt, vs, _) = cp_reserves(pos)?;
+            let tokens = (sol as u128)
+                .checked_mul(vt)
+                .ok_or(FyrstError::MathOverflow)?
+                .checked_div(vs)
+                .ok_or(FyrstError::MathOverflow)?;
+            return to_u64(tokens);
+        }
+
+        let price = self.spot_price(pos)?;
+        require!(price > 0, FyrstError::InvalidPrice);
+        let tokens = (sol as u128)
+            .checked_mul(PRICE_SCALE)
+            .ok_or(FyrstError::MathOverflow)?
+            .checked_div(price)
+            .ok_or(FyrstError::MathOverflow)?;
+        to_u64(tokens)
+    }
+
+    fn sol_at_spot(&self, pos: &CurvePosition, tokens: u64) -> Result<u64> {
+        if let CurveKind::ConstantProduct = self {
+            // sol = tokens * virtual_sol / virtual_token
+            let (vt, vs, _) = cp_reserves(pos)?;
+            return to_u64(mul_div(tokens as u128, vs, vt)?);
+        }
+
+        let sol = mul_div(tokens as u128, self.spot_price(pos)?, PRICE_SCALE)?;
+        to_u64(sol)
+    }
+}
+
+impl CurveKind {
+    /// F(s): lamports (rounded down) to buy the first `supply` tokens of a
+    /// price-function curve. Not defined for constant product.
+    fn cost_to(&self, supply: u64, allocation: u64) -> Result<u128> {
+        require!(allocation > 0, FyrstError::InvalidPrice);
+        let s = supply as u128;
+        let alloc = allocation as u128;
+
+        let scaled = match *self {
+            CurveKind::ConstantProduct => return err!(FyrstError::InvalidPrice),
+            // F(s) = start*s + (end - start) * s^2 / (2 * allocation)
+            CurveKind::Linear { start_price, end_price } => {
+                let slope = (end_price as u128)
+                    .checked_sub(start_price as u128)
+                    .ok_or(FyrstError::MathOverflow)?;
+                let ramp = mul_div(
+                    slope.checked_mul(s).ok_or(FyrstError::MathOverflow)?,
+                    s,
+                    alloc.checked_mul(2).ok_or(FyrstError::MathOverflow)?,
+                )?;
+                (start_price as u128)
+                    .checked_mul(s)
+                    .ok_or(FyrstError::MathOverflow)?
+                    .checked_add(ramp)
+                    .ok_or(FyrstError::MathOverflow)?
+            }
+            // F(s) = start * allocation * (e^(g * s / allocation) - 1) / g
+            CurveKind::Exponential { start_price, growth_wad } => {
+                let g = growth_wad as u128;
+                require!(g > 0, FyrstError::InvalidPrice);
+                let x = g
+                    .checked_mul(s)
+                    .ok_or(FyrstError::MathOverflow)?
+                    .checked_div(alloc)
+                    .ok_or(FyrstError::MathOverflow)?;
+                let growth = exp_wad(x)?
+                    .checked_sub(WAD)
+                    .ok_or(FyrstError::MathOverflow)?
+                    .checked_mul(start_price as u128)
+                    .ok_or(FyrstError::MathOverflow)?;
+                mul_div(growth, alloc, g)?
+            }
+            // F(s) = sum of price_i * (tokens of segment i below s); the last
+            // level extends past the allocation
+            CurveKind::Step { prices, count } => {
+                let (segment, count) = step_segment(allocation, count)?;
+                let segment = segment as u128;
+                let mut total: u128 = 0;
+                for (i, price) in prices.iter().take(count).enumerate() {
+                    let lo = segment * i as u128;
+                    if s <= lo {
+                        break;
+                    }
+                    let hi = if i + 1 == count { s } else { s.min(lo + segment) };
+                    total = (*price as u128)
+                        .checked_mul(hi - lo)
+                        .and_then(|c| total.checked_add(c))
+                        .ok_or(FyrstError::MathOverflow)?;
+                }
+                total
+            }
+        };
+
+        Ok(scaled / PRICE_SCALE)
+    }
+}
+
+/// Constant product reserves and invariant (vt, vs, k)
+fn cp_reserves(pos: &CurvePosition) -> Result<(u128, u128, u128)> {
+    let vt = pos.virtual_token_reserves as u128;
+    let vs = pos.virtual_sol_reserves as u128;
+    require!(vs > 0 && vt > 0, FyrstError::InvalidPrice);
+    let k = vt.checked_mul(vs).ok_or(FyrstError::MathOverflow)?;
+    Ok((vt, vs, k))
+}
+
+/// Step curve segment length and level count (levels split the allocation evenly)
+fn step_segment(allocation: u64, count: u8) -> Result<(u64, usize)> {
+    let count = count as usize;
+    require!(count > 0 && count <= MAX_CURVE_STEPS, FyrstError::InvalidCurve);
+    let segment = allocation / count as u64;
+    require!(segment > 0, FyrstError::InvalidPrice);
+    Ok((segment, count))
+}
+
+/// Largest t in [0, hi] for which `pred` holds, given `pred(0)` holds and
+/// `pred` is monotone (true up to some t, false after)
+fn search_max(hi: u64, mut pred: impl FnMut(u64) -> Result<bool>) -> Result<u64> {
+    let (mut lo, mut hi) = (0u64, hi);
+    while lo < hi {
+        let mid = lo + (hi - lo).div_ceil(2);
+        if pred(mid)? {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    Ok(lo)
+}
+
+/// e^x for x in WAD, x < 12 WAD
+fn exp_wad(x: u128) -> Result<u128> {
+    let whole = (x / WAD) as usize;
+    require!(whole < EXP_WAD_TABLE.len(), FyrstError::MathOverflow);
+    let frac = x % WAD;
+
+    // Taylor series for e^frac, frac < 1
+    let mut sum = WAD;
+    let mut term = WAD;
+    for i in 1..=24u128 {
+        term = term * frac / WAD / i;
+        if term == 0 {
+            break;
+        }
+        sum += term;
+    }
+
+    mul_wad(EXP_WAD_TABLE[whole], sum)
+}
+
+/// e^-x for x in WAD; 0 past the exp table (e^-12 is negligible)
+pub(crate) fn exp_neg_wad(x: u128) -> Result<u128> {
+    if x / WAD >= EXP_WAD_TABLE.len() as u128 {
+        return Ok(0);
+    }
+    Ok(WAD * WAD / exp_wad(x)?)
+}
+
+/// a * b / WAD without overflowing on large `a`
+fn mul_wad(a: u128, b: u128) -> Result<u128> {
+    mul_div(a, b, WAD)
+}
+
+/// floor(a * b / c) without overflowing on large `a` (requires b * c to fit in u128)
+fn mul_div(a: u128, b: u128, c: u128) -> Result<u128> {
+    require!(c > 0, FyrstError::MathOverflow);
+    let high = (a / c).checked_mul(b).ok_or(FyrstError::MathOverflow)?;
+    let low = (a % c).checked_mul(b).ok_or(FyrstError::MathOverflow)? / c;
+    high.checked_add(low).ok_or(FyrstError::MathOverflow.into())
+}
+
+fn div_ceil(a: u128, b: u128) -> Result<u128> {
+    require!(b > 0, FyrstError::MathOverflow);
+    Ok(a.div_ceil(b))
+}
+
+fn to_u64(v: u128) -> Result<u64> {
+    u64::try_from(v).map_err(|_| FyrstError::MathOverflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALLOCATION: u64 = 793_100_000_000_000;
+
+    fn reference_cp() -> CurvePosition {
+        CurvePosition {
+            virtual_token_reserves: INITIAL_VIRTUAL_TOKEN_RESERVES,
+            virtual_sol_reserves: INITIAL_VIRTUAL_SOL_RESERVES,
+            sold: 0,
+            allocation: INITIAL_REAL_TOKEN_RESERVES,
+        }
+    }
+
+    fn at(sold: u64, allocation: u64) -> CurvePosition {
+        CurvePosition { virtual_token_reserves: 0, virtual_sol_reserves: 0, sold, allocation }
+    }
+
+    fn shapes() -> [CurveKind; 3] {
+        [
+            CurveKind::Linear { start_price: CURVE_MIN_PRICE, end_price: 100 * CURVE_MIN_PRICE },
+            CurveKind::Exponential { start_price: CURVE_MIN_PRICE, growth_wad: CURVE_MAX_GROWTH_WAD },
+            CurveKind::Step {
+                prices: [CURVE_MIN_PRICE, 2 * CURVE_MIN_PRICE, 5 * CURVE_MIN_PRICE, 10 * CURVE_MIN_PRICE, 0, 0, 0, 0],
+                count: 4,
+            },
+        ]
+    }
+
+    /// Position after a buy of `tokens` for `net_sol`, as record_buy applies it
+    fn after_buy(pos: &CurvePosition, tokens: u64, net_sol: u64) -> CurvePosition {
+        CurvePosition {
+            virtual_token_reserves: pos.virtual_token_reserves.saturating_sub(tokens),
+            virtual_sol_reserves: pos.virtual_sol_reserves.saturating_add(net_sol),
+            sold: pos.sold + tokens,
+            allocation: pos.allocation,
+        }
+    }
+
+    fn assert_close(actual: u128, expected: f64) {
+        let error = (actual as f64 - expected).abs() / expected;
+        assert!(error < 1e-12, "{actual} vs {expected}");
+    }
+
+    #[test]
+    fn search_max_finds_last_true() {
+        assert_eq!(search_max(0, |_| Ok(true)).unwrap(), 0);
+        assert_eq!(search_max(100, |t| Ok(t <= 37)).unwrap(), 37);
+        assert_eq!(search_max(100, |_| Ok(true)).unwrap(), 100);
+        assert_eq!(search_max(100, |t| Ok(t == 0)).unwrap(), 0);
+        assert_eq!(search_max(u64::MAX, |t| Ok(t < u64::MAX)).unwrap(), u64::MAX - 1);
+        assert_eq!(search_max(u64::MAX, |_| Ok(true)).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn exp_wad_matches_table_at_integers() {
+        for (n, expected) in EXP_WAD_TABLE.iter().enumerate() {
+            assert_eq!(exp_wad(n as u128 * WAD).unwrap(), *expected);
+        }
+        assert!(exp_wad(12 * WAD).is_err());
+    }
+
+    #[test]
+    fn exp_wad_taylor_series_matches_float() {
+        for x in [1u128, WAD / 1000, WAD / 3, WAD / 2, WAD - 1, WAD + WAD / 7, 5 * WAD / 2, 10 * WAD, 12 * WAD - 1] {
+            assert_close(exp_wad(x).unwrap(), (x as f64 / WAD as f64).exp() * WAD as f64);
+        }
+    }
+
+    #[test]
+    fn exp_neg_wad_inverts_exp_wad() {
+        assert_eq!(exp_neg_wad(0).unwrap(), WAD);
+        assert_close(exp_neg_wad(WAD).unwrap(), (-1f64).exp() * WAD as f64);
+        assert_eq!(exp_neg_wad(12 * WAD).unwrap(), 0);
+    }
+
+    #[test]
+    fn mul_div_is_exact_past_u128_products() {
+        assert_eq!(mul_div(7, 3, 2).unwrap(), 10);
+        assert_eq!(mul_div(10u128.pow(30), 10u128.pow(18), 10u128.pow(18)).unwrap(), 10u128.pow(30));
+        assert_eq!(mul_div(u128::MAX, WAD, WAD).unwrap(), u128::MAX);
+        assert_eq!(mul_div(u128::MAX - 1, 1, 2).unwrap(), (u128::MAX - 1) / 2);
+        assert!(mul_div(1, 1, 0).is_err());
+        assert!(mul_div(u128::MAX, 2, 1).is_err());
+    }
+
+    #[test]
+    fn cost_to_is_monotonic() {
+        for kind in shapes() {
+            let mut last = 0;
+            for i in 0..=200u64 {
+                let cost = kind.cost_to(ALLOCATION / 200 * i, ALLOCATION).unwrap();
+                assert!(cost >= last, "{kind:?} at {i}");
+                last = cost;
+            }
+            assert!(last > 0);
+        }
+    }
+
+    #[test]
+    fn buy_then_sell_never_pays_out_more() {
+        let cp = reference_cp();
+        let mut cases = vec![(CurveKind::ConstantProduct, cp)];
+        for kind in shapes() {
+            for sold in [0, ALLOCATION / 3, ALLOCATION / 2 + 1, ALLOCATION - ALLOCATION / 10] {
+                cases.push((kind, at(sold, ALLOCATION)));
+            }
+        }
+
+        for (kind, pos) in cases {
+            for net_sol in [1, 999, 1_000_000_007, 3_000_000_000] {
+                let tokens = kind.tokens_out(&pos, net_sol).unwrap();
+                if tokens == 0 || pos.sold + tokens > pos.allocation {
+                    continue;
+                }
+                let after = after_buy(&pos, tokens, net_sol);
+                assert!(kind.sol_out(&after, tokens).unwrap() <= net_sol, "{kind:?} buy {net_sol}");
+
+                // Exact-token buys round up, so selling them back pays no more
+                let cost = kind.sol_in(&pos, tokens).unwrap();
+                let after = after_buy(&pos, tokens, cost);
+                assert!(kind.sol_out(&after, tokens).unwrap() <= cost, "{kind:?} exact {tokens}");
+            }
+        }
+    }
+
+    #[test]
+    fn tokens_in_covers_the_requested_sol() {
+        for kind in shapes() {
+            let pos = at(ALLOCATION / 2, ALLOCATION);
+            for gross in [1, 1_000_000, 50_000_000] {
+                let tokens = kind.tokens_in(&pos, gross).unwrap();
+                assert!(kind.sol_out(&pos, tokens).unwrap() >= gross);
+                assert!(kind.sol_out(&pos, tokens - 1).unwrap() < gross);
+            }
+        }
+    }
+
+    #[test]
+    fn pricing_stays_in_u128_at_the_curve_bound_ceilings() {
+        // Largest allocation a launch can mint (u64 base units) at the hard
+        // ceilings: F(s) fits in u128, and lamport totals past u64 fail
+        // cleanly with MathOverflow instead of wrapping
+        let allocation = u64::MAX / 10_000 * LAUNCH_MAX_CURVE_TOKEN_BPS;
+        let ceilings = [
+            CurveKind::Linear { start_price: MAX_CURVE_PRICE, end_price: MAX_CURVE_PRICE },
+            CurveKind::Exponential { start_price: MAX_CURVE_PRICE, growth_wad: MAX_CURVE_GROWTH_WAD },
+            CurveKind::Step { prices: [MAX_CURVE_PRICE; 8], count: MAX_CURVE_STEPS as u8 },
+        ];
+        for kind in ceilings {
+            assert!(kind.cost_to(allocation, allocation).is_ok(), "{kind:?}");
+            assert!(kind.spot_price(&at(allocation, allocation)).is_ok());
+            let err = kind.sol_in(&at(0, allocation), allocation).unwrap_err();
+            assert_eq!(err, FyrstError::MathOverflow.into());
+        }
+
+        // Growth past the exp table is what the MAX_CURVE_GROWTH_WAD ceiling prevents
+        let steep = CurveKind::Exponential { start_price: MAX_CURVE_PRICE, growth_wad: 12 * WAD as u64 };
+        assert!(steep.cost_to(allocation, allocation).is_err());
+    }
+}
@@ -55,4 +55,124 @@ pub enum FyrstError {
 
     #[msg("Tokens are still in circulation — use process_refund instead")]
     TokensStillCirculating,
+
+    #[msg("Escrow vault does not back this bonding curve")]
+    EscrowMismatch,
+
+    #[msg("Fee schedule exceeds protocol safety ceilings")]
+    InvalidFeeSchedule,
+
+    #[msg("Discount exceeds 100%")]
+    InvalidDiscount,
+
+    #[msg("Invalid graduation threshold")]
+    InvalidThreshold,
+
+    #[msg("Account is not a writable bonding curve PDA")]
+    InvalidAccount,
+
+    #[msg("Curve SOL reserve cannot cover this sale")]
+    InsufficientReserve,
+
+    #[msg("Curve parameters outside protocol bounds")]
+    InvalidCurve,
+
+    #[msg("Launch supply or reserves outside protocol bounds")]
+    InvalidLaunchParams,
+
+    #[msg("Launch protection window exceeds protocol ceiling")]
+    InvalidProtection,
+
+    #[msg("Buy exceeds the per-wallet cap during the launch protection window")]
+    WalletCapExceeded,
+
+    #[msg("Sniper tax exceeds protocol ceilings")]
+    InvalidSniperTax,
+
+    #[msg("Trading has not opened yet")]
+    TradingNotOpen,
+
+    #[msg("Trading open time is in the past or too far ahead")]
+    InvalidOpenTime,
+
+    #[msg("Presale root and duration must be set together and within limits")]
+    InvalidPresale,
+
+    #[msg("Presale is not active for this curve")]
+    PresaleNotActive,
+
+    #[msg("Merkle proof does not match the posted root")]
+    InvalidProof,
+
+    #[msg("Buy exceeds the wallet's presale allocation")]
+    PresaleAllocationExceeded,
+
+    #[msg("Batch opening cannot be combined with a presale or initial buy, or exceeds the duration limit")]
+    InvalidOpening,
+
+    #[msg("Batch opening is not accepting commits")]
+    OpeningNotActive,
+
+    #[msg("Batch opening has not been settled yet")]
+    OpeningNotSettled,
+
+    #[msg("Batch opening is already settled")]
+    OpeningAlreadySettled,
+
+    #[msg("Batch-opening tokens are still waiting to be claimed")]
+    OpeningTokensUnclaimed,
+
+    #[msg("Per-wallet holding cap must be 0 (off) or between 0.5% and 100% of supply")]
+    InvalidWalletCap,
+
+    #[msg("Buy would exceed the per-wallet holding cap")]
+    MaxWalletExceeded,
+
+    #[msg("Collateral lock exceeds protocol ceilings")]
+    InvalidCollateralLock,
+
+    #[msg("Collateral is still locked after graduation")]
+    CollateralLocked,
+
+    #[msg("No unlocked collateral to claim")]
+    NoCollateralToClaim,
+
+    #[msg("Dump threshold exceeds 100%")]
+    InvalidDumpThreshold,
+
+    #[msg("Deployer has not sold beyond the dump threshold")]
+    DeployerNotDumped,
+
+    #[msg("Escrow has already been slashed")]
+    AlreadySlashed,
+
+    #[msg("No locked collateral left to slash")]
+    NoCollateralToSlash,
+
+    #[msg("Deployer escrow was slashed: trading is closed, only refunds remain")]
+    LaunchSlashed,
+
+    #[msg("Launch failed its deadline: trading is closed, only refunds remain")]
+    LaunchFailed,
+
+    #[msg("Launch is already marked failed")]
+    AlreadyMarkedFailed,
+
+    #[msg("Contribution refunds require the buyer's position account")]
+    PositionRequired,
+
+    #[msg("Refund snapshot needs a root, 1–65,536 leaves and a non-zero total weight")]
+    InvalidSnapshot,
+
+    #[msg("Refunds for this launch are paid from its snapshot")]
+    RefundSnapshotActive,
+
+    #[msg("Snapshot refund already paid")]
+    SnapshotAlreadyClaimed,
+
+    #[msg("Remaining accounts must list one writable system-owned holder wallet per claim")]
+    HolderAccountsMismatch,
+
+    #[msg("Snapshot refunds can still be claimed")]
+    SnapshotClaimsOpen,
 }
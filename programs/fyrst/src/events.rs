@@ -0,0 +1,353 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    CollateralLock, CurveBounds, CurveKind, FeeSchedule, HolderDiscount, LaunchBounds, LaunchProtection,
+    RefundMode, SniperTax,
+};
+
+/// Emitted on every curve trade (buy or sell) with post-trade curve state
+#[event]
+pub struct TradeEvent {
+    /// Token mint traded
+    pub token_mint: Pubkey,
+    /// Buyer or seller wallet
+    pub trader: Pubkey,
+    /// true = buy, false = sell
+    pub is_buy: bool,
+    /// Gross SOL paid by the buyer / released by the curve on a sell
+    pub sol_amount: u64,
+    /// Tokens transferred to the buyer / returned by the seller
+    pub token_amount: u64,
+    /// Total trade fee charged
+    pub trade_fee: u64,
+    /// Protocol fee charged on top of the trade fee
+    pub protocol_fee: u64,
+    /// Deployer share of the trade fee (accrued on the curve)
+    pub deployer_fee: u64,
+    /// Operations wallet share (accrued on the curve)
+    pub ops_fee: u64,
+    /// Treasury (buyback+burn) share (accrued on the curve)
+    pub treasury_fee: u64,
+    /// Sniper tax on early buys (paid into the curve's holder pool)
+    pub sniper_tax: u64,
+    /// Post-trade virtual token reserves
+    pub virtual_token_reserves: u64,
+    /// Post-trade virtual SOL reserves
+    pub virtual_sol_reserves: u64,
+    /// Post-trade real token reserves
+    pub real_token_reserves: u64,
+    /// Post-trade real SOL reserves
+    pub real_sol_reserves: u64,
+    /// Post-trade reserve balance
+    pub reserve_balance: u64,
+    /// Post-trade circulating supply sold via the curve
+    pub current_supply: u64,
+    /// Unix timestamp of the trade
+    pub timestamp: i64,
+}
+
+/// Emitted when a bonding curve and its SPL mint are created
+#[event]
+pub struct CurveInitialized {
+    pub token_mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub deployer: Pubkey,
+    /// Escrow vault backing this curve
+    pub escrow_vault: Pubkey,
+    pub collateral_amount: u64,
+    /// Deploy fee actually charged (after discounts)
+    pub deploy_fee: u64,
+    /// Discount applied to the deploy fee in basis points
+    pub deploy_fee_discount_bps: u64,
+    /// Price shape chosen by the deployer
+    pub curve_kind: CurveKind,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub token_total_supply: u64,
+    pub decimals: u8,
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    /// Timestamp when public trading opens
+    pub trading_opens_at: i64,
+    /// Presale allowlist root (all zeros = no presale)
+    pub presale_root: [u8; 32],
+    /// Presale start (runs until trading_opens_at)
+    pub presale_starts_at: i64,
+    /// Batch-opening commit start (0 = no batch opening; runs until trading_opens_at)
+    pub opening_starts_at: i64,
+    /// Per-wallet holding cap in basis points of supply (0 = no cap)
+    pub max_wallet_bps: u64,
+    pub refund_mode: RefundMode,
+    pub timestamp: i64,
+}
+
+/// Emitted on each batch-opening commit
+#[event]
+pub struct OpeningCommitted {
+    pub token_mint: Pubkey,
+    pub committer: Pubkey,
+    pub sol_amount: u64,
+    /// Committer's total after this commit
+    pub sol_committed: u64,
+    /// Curve-wide total after this commit
+    pub opening_total_sol: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a batch opening clears at one uniform curve price
+#[event]
+pub struct OpeningSettled {
+    pub token_mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    /// Total SOL committed (fees included)
+    pub total_sol: u64,
+    /// Tokens cleared for committers
+    pub tokens: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a committer claims their batch-opening tokens
+#[event]
+pub struct OpeningClaimed {
+    pub token_mint: Pubkey,
+    pub committer: Pubkey,
+    pub sol_committed: u64,
+    pub tokens: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a curve reaches its graduation condition
+#[event]
+pub struct Graduated {
+    pub token_mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub reserve_balance: u64,
+    pub real_token_reserves: u64,
+    pub current_supply: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted after liquidity is migrated to the Raydium CPMM pool
+#[event]
+pub struct DexMigrated {
+    pub token_mint: Pubkey,
+    pub pool: Pubkey,
+    /// SOL deposited as pool liquidity (reserve minus pool creation fee)
+    pub liquidity_sol: u64,
+    /// Tokens deposited as pool liquidity
+    pub pool_tokens: u64,
+    /// Raydium pool creation fee
+    pub pool_creation_fee: u64,
+    /// Liquidity SOL left out because the available tokens capped the pool
+    /// (credited to the treasury's accrued fees)
+    pub surplus_sol: u64,
+    /// Payer that created the pool (reimbursed from the reserve)
+    pub payer: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when deployer collateral is locked
+#[event]
+pub struct EscrowCreated {
+    pub escrow_vault: Pubkey,
+    pub deployer: Pubkey,
+    pub token_mint: Pubkey,
+    pub collateral_amount: u64,
+    pub deadline_timestamp: i64,
+    /// Post-graduation lock the collateral is released under
+    pub collateral_lock: CollateralLock,
+    pub timestamp: i64,
+}
+
+/// Emitted when collateral is returned to the deployer after graduation
+#[event]
+pub struct EscrowReleased {
+    pub escrow_vault: Pubkey,
+    pub deployer: Pubkey,
+    pub token_mint: Pubkey,
+    /// Lamports returned to the deployer (collateral + rent)
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the deployer claims collateral unlocked after graduation
+#[event]
+pub struct CollateralClaimed {
+    pub escrow_vault: Pubkey,
+    pub deployer: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    /// Cumulative collateral claimed after this claim
+    pub total_claimed: u64,
+    /// Collateral still locked (slashable) after this claim
+    pub locked: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a dumping deployer's locked collateral is slashed to the holders
+#[event]
+pub struct EscrowSlashed {
+    pub escrow_vault: Pubkey,
+    pub deployer: Pubkey,
+    pub token_mint: Pubkey,
+    /// Wallet that cranked the slash
+    pub slasher: Pubkey,
+    /// Collateral moved to the curve's slashed pool
+    pub amount: u64,
+    pub deployer_tokens_bought: u64,
+    /// Tokens counted as sold: curve sells, or the shortfall in the deployer's wallet
+    pub deployer_tokens_sold: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a launch is marked failed after missing its deadline
+#[event]
+pub struct LaunchFailed {
+    pub token_mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub deadline_timestamp: i64,
+    /// SOL left in the curve reserve for refunds
+    pub reserve_balance: u64,
+    /// Tokens outstanding that can still be refunded
+    pub current_supply: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when an escrow expires with no holders left
+#[event]
+pub struct EscrowExpired {
+    pub escrow_vault: Pubkey,
+    pub deployer: Pubkey,
+    pub token_mint: Pubkey,
+    pub ops_share: u64,
+    pub buyback_share: u64,
+    /// Reserve dust and unclaimed holder pools (sniper tax, slashed collateral)
+    /// swept to the treasury
+    pub holder_pool: u64,
+    /// Lamports returned to the deployer (remaining collateral + rent)
+    pub deployer_refund: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted on each burn-to-refund claim
+#[event]
+pub struct RefundProcessed {
+    pub token_mint: Pubkey,
+    pub buyer: Pubkey,
+    pub tokens_burned: u64,
+    pub sol_refunded: u64,
+    /// Circulating supply remaining after the burn
+    pub remaining_supply: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a Merkle root of holder refund weights is posted to an escrow
+#[event]
+pub struct RefundSnapshotPosted {
+    pub token_mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub refund_snapshot: Pubkey,
+    pub root: [u8; 32],
+    pub leaf_count: u32,
+    pub total_weight: u64,
+    /// Lamports moved into the snapshot for refunds
+    pub pool_lamports: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted per holder paid from a refund snapshot (claim or crank)
+#[event]
+pub struct SnapshotRefundPaid {
+    pub token_mint: Pubkey,
+    pub holder: Pubkey,
+    /// Leaf index in the snapshot
+    pub index: u32,
+    pub weight: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a refund snapshot is closed after its claim period
+#[event]
+pub struct RefundSnapshotClosed {
+    pub token_mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub refund_snapshot: Pubkey,
+    /// Refund lamports never claimed, swept to the treasury with the rent
+    pub unclaimed_lamports: u64,
+    /// Escrow rent returned to the deployer (0 after graduation, when the
+    /// escrow stays open for the deployer's collateral)
+    pub escrow_rent: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted per curve when accrued protocol fees are swept to treasury and ops
+#[event]
+pub struct ProtocolFeesSwept {
+    pub token_mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub ops_amount: u64,
+    pub treasury_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the deployer claims unlocked trade fees
+#[event]
+pub struct FeesClaimed {
+    pub token_mint: Pubkey,
+    pub deployer: Pubkey,
+    pub amount: u64,
+    /// Cumulative deployer fees claimed after this claim
+    pub total_claimed: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted on protocol init and every admin config change (full post-update snapshot)
+#[event]
+pub struct ConfigUpdated {
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub ops_wallet: Pubkey,
+    pub graduation_threshold: u64,
+    pub fee_schedule: FeeSchedule,
+    pub holder_discount: HolderDiscount,
+    pub curve_bounds: CurveBounds,
+    pub launch_bounds: LaunchBounds,
+    pub launch_protection: LaunchProtection,
+    pub sniper_tax: SniperTax,
+    pub collateral_lock: CollateralLock,
+    pub dump_threshold_bps: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the authority overrides a live curve's graduation threshold
+#[event]
+pub struct CurveThresholdUpdated {
+    pub token_mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub authority: Pubkey,
+    pub old_threshold: u64,
+    pub new_threshold: u64,
+    /// max_reserve_reached after re-capping to the new threshold
+    pub max_reserve_reached: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the authority grants, changes or revokes a deploy fee waiver
+#[event]
+pub struct DeployFeeWaiverSet {
+    pub deployer: Pubkey,
+    pub discount_bps: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the protocol config is closed for migration
+#[event]
+pub struct ConfigClosed {
+    pub authority: Pubkey,
+    /// Rent returned to the authority
+    pub lamports: u64,
+    pub timestamp: i64,
+}
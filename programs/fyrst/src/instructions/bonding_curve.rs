@@ -1,240 +1,206 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use anchor_lang::solana_program::{self, program::invoke_signed};
-use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
 use anchor_spl::associated_token::AssociatedToken;
-use crate::state::{BondingCurve, ProtocolConfig};
+use crate::math::{self, BuyQuote, SellQuote};
+use crate::merkle;
+use crate::state::{BondingCurve, Position};
 use crate::errors::FyrstError;
+use crate::events::{Graduated, TradeEvent};
 use crate::constants::*;
 
-/// Metaplex Token Metadata program ID
-pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
-    solana_program::pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
-
-/// Build a CreateMetadataAccountV3 instruction manually (no external crate needed).
-/// Instruction discriminator = 33.
-fn build_create_metadata_v3_ix(
-    metadata: Pubkey,
-    mint: Pubkey,
-    mint_authority: Pubkey,
-    payer: Pubkey,
-    update_authority: Pubkey,
-    name: String,
-    symbol: String,
-    uri: String,
-) -> solana_program::instruction::Instruction {
-    let mut data = vec![33u8]; // CreateMetadataAccountV3
-
-    // name (Borsh string = u32 len + bytes)
-    data.extend_from_slice(&(name.len() as u32).to_le_bytes());
-    data.extend_from_slice(name.as_bytes());
-
-    // symbol
-    data.extend_from_slice(&(symbol.len() as u32).to_le_bytes());
-    data.extend_from_slice(symbol.as_bytes());
-
-    // uri
-    data.extend_from_slice(&(uri.len() as u32).to_le_bytes());
-    data.extend_from_slice(uri.as_bytes());
-
-    // seller_fee_basis_points (u16)
-    data.extend_from_slice(&0u16.to_le_bytes());
-
-    // creators (Option<Vec<Creator>>) = None
-    data.push(0);
-
-    // collection (Option<Collection>) = None
-    data.push(0);
-
-    // uses (Option<Uses>) = None
-    data.push(0);
-
-    // is_mutable (bool)
-    data.push(1);
-
-    // collection_details (Option<CollectionDetails>) = None
-    data.push(0);
-
-    solana_program::instruction::Instruction {
-        program_id: TOKEN_METADATA_PROGRAM_ID,
-        accounts: vec![
-            solana_program::instruction::AccountMeta::new(metadata, false),
-            solana_program::instruction::AccountMeta::new_readonly(mint, false),
-            solana_program::instruction::AccountMeta::new_readonly(mint_authority, true),
-            solana_program::instruction::AccountMeta::new(payer, true),
-            solana_program::instruction::AccountMeta::new_readonly(update_authority, true),
-            solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::ID, false),
-            solana_program::instruction::AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
-        ],
-        data,
-    }
+/// Accounts touched by a curve buy, shared by `buy_tokens` and `launch_token`'s initial buy
+pub(crate) struct BuyAccounts<'a, 'info> {
+    pub buyer: AccountInfo<'info>,
+    pub bonding_curve: &'a mut Account<'info, BondingCurve>,
+    pub position: &'a mut Account<'info, Position>,
+    pub position_bump: u8,
+    /// Presale allocation proven by the buyer (None = public buy)
+    pub presale_allocation: Option<u64>,
+    pub curve_token_account: AccountInfo<'info>,
+    pub buyer_token_account: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
 }
 
-/// Initialize bonding curve for a token with SPL mint + metadata.
-/// Mints entire token supply to curve ATA (constant product AMM model).
-pub fn init_bonding_curve(
-    ctx: Context<InitBondingCurve>,
-    name: String,
-    symbol: String,
-    uri: String,
-) -> Result<()> {
-    // Validate metadata lengths (bytes, not chars — UTF-8 multibyte safe)
-    require!(name.len() <= 64, FyrstError::InvalidMetadata);
-    require!(symbol.len() <= 20, FyrstError::InvalidMetadata);
-    require!(uri.len() <= 200, FyrstError::InvalidMetadata);
+/// Buy tokens on the bonding curve, priced by the curve's `CurveKind`.
+/// Transfers pre-minted tokens from curve ATA to buyer ATA. Protocol fees
+/// accrue on the curve so trades never write-lock the treasury or ops wallet.
+/// During the launch-protection window each wallet's cumulative buys are capped,
+/// and an optional decaying sniper tax is paid into the curve's holder pool.
+/// Rejected before the curve's scheduled `trading_opens_at`, or if the buyer's
+/// balance would exceed the curve's `max_wallet_bps` holding cap.
+pub fn buy_tokens(ctx: Context<BuyTokens>, sol_amount: u64, min_tokens_out: u64) -> Result<()> {
+    ctx.accounts.bonding_curve.require_open(Clock::get()?.unix_timestamp)?;
+
+    let (trade, graduated) = execute_buy(
+        BuyAccounts {
+            buyer: ctx.accounts.buyer.to_account_info(),
+            bonding_curve: &mut ctx.accounts.bonding_curve,
+            position: &mut ctx.accounts.position,
+            position_bump: ctx.bumps.position,
+            presale_allocation: None,
+            curve_token_account: ctx.accounts.curve_token_account.to_account_info(),
+            buyer_token_account: ctx.accounts.buyer_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        sol_amount,
+        min_tokens_out,
+    )?;
 
-    // Create token metadata via Metaplex CPI
-    let token_mint_key = ctx.accounts.token_mint.key();
-    let seeds = &[
-        CURVE_SEED,
-        token_mint_key.as_ref(),
-        &[ctx.bumps.bonding_curve],
-    ];
-    let signer_seeds = &[&seeds[..]];
+    emit_cpi!(trade);
+    if let Some(graduated) = graduated {
+        emit_cpi!(graduated);
+    }
 
-    let ix = build_create_metadata_v3_ix(
-        ctx.accounts.metadata_account.key(),
-        ctx.accounts.token_mint.key(),
-        ctx.accounts.bonding_curve.key(),
-        ctx.accounts.deployer.key(),
-        ctx.accounts.bonding_curve.key(),
-        name,
-        symbol,
-        uri,
-    );
+    Ok(())
+}
 
-    invoke_signed(
-        &ix,
-        &[
-            ctx.accounts.metadata_account.to_account_info(),
-            ctx.accounts.token_mint.to_account_info(),
-            ctx.accounts.bonding_curve.to_account_info(),
-            ctx.accounts.deployer.to_account_info(),
-            ctx.accounts.bonding_curve.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-            ctx.accounts.rent.to_account_info(),
-        ],
-        signer_seeds,
+/// Buy an exact number of tokens, paying at most `max_sol_in` (fees included).
+/// Near graduation the amount is clamped to the curve's remaining real tokens;
+/// only the SOL actually required is taken from the buyer.
+pub fn buy_exact_tokens(ctx: Context<BuyTokens>, token_amount: u64, max_sol_in: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.bonding_curve.require_open(now)?;
+    let quote = math::quote_buy_exact_tokens(&ctx.accounts.bonding_curve, token_amount, now)?;
+    require!(quote.sol_amount <= max_sol_in, FyrstError::SlippageExceeded);
+
+    let (trade, graduated) = settle_buy(
+        BuyAccounts {
+            buyer: ctx.accounts.buyer.to_account_info(),
+            bonding_curve: &mut ctx.accounts.bonding_curve,
+            position: &mut ctx.accounts.position,
+            position_bump: ctx.bumps.position,
+            presale_allocation: None,
+            curve_token_account: ctx.accounts.curve_token_account.to_account_info(),
+            buyer_token_account: ctx.accounts.buyer_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        &quote,
     )?;
 
-    // Mint entire token supply to curve's ATA
-    token::mint_to(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            MintTo {
-                mint: ctx.accounts.token_mint.to_account_info(),
-                to: ctx.accounts.curve_token_account.to_account_info(),
-                authority: ctx.accounts.bonding_curve.to_account_info(),
-            },
-            signer_seeds,
-        ),
-        TOKEN_TOTAL_SUPPLY,
-    )?;
-
-    // Initialize bonding curve state (constant product AMM)
-    let curve = &mut ctx.accounts.bonding_curve;
-    curve.token_mint = ctx.accounts.token_mint.key();
-    curve.current_supply = 0;
-    curve.virtual_token_reserves = INITIAL_VIRTUAL_TOKEN_RESERVES;
-    curve.virtual_sol_reserves = INITIAL_VIRTUAL_SOL_RESERVES;
-    curve.real_token_reserves = INITIAL_REAL_TOKEN_RESERVES;
-    curve.real_sol_reserves = 0;
-    curve.token_total_supply = TOKEN_TOTAL_SUPPLY;
-    curve.reserve_balance = 0;
-    curve.graduated = false;
-    curve.deployer = ctx.accounts.deployer.key();
-    curve.total_sol_collected = 0;
-    curve.max_reserve_reached = 0;
-    curve.total_deployer_fees = 0;
-    curve.claimed_deployer_fees = 0;
-    curve.bump = ctx.bumps.bonding_curve;
-
-    msg!(
-        "Bonding curve initialized (CPMM): mint={}, virtual_token={}, virtual_sol={}, real_token={}",
-        curve.token_mint,
-        INITIAL_VIRTUAL_TOKEN_RESERVES,
-        INITIAL_VIRTUAL_SOL_RESERVES,
-        INITIAL_REAL_TOKEN_RESERVES
-    );
+    emit_cpi!(trade);
+    if let Some(graduated) = graduated {
+        emit_cpi!(graduated);
+    }
 
     Ok(())
 }
 
-/// Buy tokens on the bonding curve — constant product AMM (x*y=k).
-/// Transfers pre-minted tokens from curve ATA to buyer ATA.
-pub fn buy_tokens(ctx: Context<BuyTokens>, sol_amount: u64, min_tokens_out: u64) -> Result<()> {
-    let tokens: u64;
-    let net_sol: u64;
-    let protocol_fee: u64;
-    let trade_fee: u64;
-    let token_mint_key: Pubkey;
-    let curve_bump: u8;
-    {
-        let curve = &ctx.accounts.bonding_curve;
-
-        require!(!curve.graduated, FyrstError::AlreadyGraduated);
-        require!(sol_amount > 0, FyrstError::InsufficientFunds);
-
-        trade_fee = sol_amount
-            .checked_mul(TRADE_FEE_BPS)
-            .ok_or(FyrstError::MathOverflow)?
-            .checked_div(BPS_DENOMINATOR)
-            .ok_or(FyrstError::MathOverflow)?;
+/// Buy during the allowlisted presale (between `presale_starts_at` and
+/// `trading_opens_at`). The buyer proves their allocation — gross SOL they may
+/// spend across presale buys — with a Merkle proof against the curve's
+/// `presale_root`. Presale buys pay no sniper tax; tokens are covered by the
+/// escrow/refund protections like any other holding.
+pub fn presale_buy(
+    ctx: Context<BuyTokens>,
+    sol_amount: u64,
+    min_tokens_out: u64,
+    allocation: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let curve = &ctx.accounts.bonding_curve;
+    require!(
+        curve.presale_active(Clock::get()?.unix_timestamp),
+        FyrstError::PresaleNotActive
+    );
+    require!(
+        merkle::verify(&proof, &curve.presale_root, merkle::leaf(&ctx.accounts.buyer.key(), allocation)),
+        FyrstError::InvalidProof
+    );
 
-        protocol_fee = sol_amount
-            .checked_mul(PROTOCOL_FEE_BPS)
-            .ok_or(FyrstError::MathOverflow)?
-            .checked_div(BPS_DENOMINATOR)
-            .ok_or(FyrstError::MathOverflow)?;
+    let quote = math::quote_buy_untaxed(curve, sol_amount)?;
+    require!(quote.tokens >= min_tokens_out, FyrstError::SlippageExceeded);
+
+    let (trade, graduated) = settle_buy(
+        BuyAccounts {
+            buyer: ctx.accounts.buyer.to_account_info(),
+            bonding_curve: &mut ctx.accounts.bonding_curve,
+            position: &mut ctx.accounts.position,
+            position_bump: ctx.bumps.position,
+            presale_allocation: Some(allocation),
+            curve_token_account: ctx.accounts.curve_token_account.to_account_info(),
+            buyer_token_account: ctx.accounts.buyer_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        &quote,
+    )?;
 
-        net_sol = sol_amount
-            .checked_sub(trade_fee)
-            .ok_or(FyrstError::MathOverflow)?
-            .checked_sub(protocol_fee)
-            .ok_or(FyrstError::MathOverflow)?;
+    emit_cpi!(trade);
+    if let Some(graduated) = graduated {
+        emit_cpi!(graduated);
+    }
 
-        // Constant product AMM: tokens_out = virtual_token - k / (virtual_sol + net_sol)
-        let vt = curve.virtual_token_reserves as u128;
-        let vs = curve.virtual_sol_reserves as u128;
-        let k = vt.checked_mul(vs).ok_or(FyrstError::MathOverflow)?;
-        let new_vs = vs.checked_add(net_sol as u128).ok_or(FyrstError::MathOverflow)?;
-        let new_vt = k.checked_div(new_vs).ok_or(FyrstError::MathOverflow)?;
-        tokens = vt.checked_sub(new_vt).ok_or(FyrstError::MathOverflow)? as u64;
+    Ok(())
+}
 
-        require!(tokens > 0, FyrstError::InsufficientFunds);
-        require!(tokens <= curve.real_token_reserves, FyrstError::InsufficientTokens);
-        require!(tokens >= min_tokens_out, FyrstError::SlippageExceeded);
+/// Execute an exact-SOL-in buy against the curve. Returns the trade event (and
+/// graduation event if the buy crossed the threshold) for the caller to emit.
+pub(crate) fn execute_buy(
+    accounts: BuyAccounts<'_, '_>,
+    sol_amount: u64,
+    min_tokens_out: u64,
+) -> Result<(TradeEvent, Option<Graduated>)> {
+    let now = Clock::get()?.unix_timestamp;
+    let quote = math::quote_buy(accounts.bonding_curve, sol_amount, now)?;
+    require!(quote.tokens >= min_tokens_out, FyrstError::SlippageExceeded);
+
+    settle_buy(accounts, &quote)
+}
 
-        token_mint_key = curve.token_mint;
-        curve_bump = curve.bump;
+/// Settle a priced buy: moves SOL in and tokens out, accrues fees and updates
+/// curve state (auto-graduating if the condition is met)
+fn settle_buy(
+    accounts: BuyAccounts<'_, '_>,
+    quote: &BuyQuote,
+) -> Result<(TradeEvent, Option<Graduated>)> {
+    let now = Clock::get()?.unix_timestamp;
+
+    // First buy on this curve: open the buyer's position
+    let position = accounts.position;
+    position.open(accounts.bonding_curve.key(), accounts.buyer.key(), accounts.position_bump);
+
+    // Presale buys are capped by the proven allocation; public buys by the
+    // launch-protection window cap
+    if let Some(allocation) = accounts.presale_allocation {
+        position.presale_sol_spent = position
+            .presale_sol_spent
+            .checked_add(quote.sol_amount)
+            .ok_or(FyrstError::MathOverflow)?;
+        require!(
+            position.presale_sol_spent <= allocation,
+            FyrstError::PresaleAllocationExceeded
+        );
+    } else if accounts.bonding_curve.protection_active(now) {
+        position.window_sol_spent = position
+            .window_sol_spent
+            .checked_add(quote.sol_amount)
+            .ok_or(FyrstError::MathOverflow)?;
+        require!(
+            position.window_sol_spent <= accounts.bonding_curve.launch_protection.max_sol_per_wallet,
+            FyrstError::WalletCapExceeded
+        );
     }
 
-    // Transfer SOL to curve PDA (sol_amount minus protocol_fee)
+    let token_mint_key = accounts.bonding_curve.token_mint;
+    let curve_bump = accounts.bonding_curve.bump;
+    let curve_info = accounts.bonding_curve.to_account_info();
+
+    // Transfer SOL to curve PDA (protocol fees accrue on the curve until swept)
     system_program::transfer(
         CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
+            accounts.system_program.clone(),
             system_program::Transfer {
-                from: ctx.accounts.buyer.to_account_info(),
-                to: ctx.accounts.bonding_curve.to_account_info(),
+                from: accounts.buyer.clone(),
+                to: curve_info.clone(),
             },
         ),
-        sol_amount.checked_sub(protocol_fee).ok_or(FyrstError::MathOverflow)?,
+        quote.sol_amount,
     )?;
 
-    // Transfer protocol fee to treasury
-    if protocol_fee > 0 {
-        system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.buyer.to_account_info(),
-                    to: ctx.accounts.treasury.to_account_info(),
-                },
-            ),
-            protocol_fee,
-        )?;
-    }
-
     // Transfer tokens from curve ATA to buyer ATA
     let seeds = &[
         CURVE_SEED,
@@ -245,273 +211,266 @@ pub fn buy_tokens(ctx: Context<BuyTokens>, sol_amount: u64, min_tokens_out: u64)
 
     token::transfer(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
+            accounts.token_program.clone(),
             token::Transfer {
-                from: ctx.accounts.curve_token_account.to_account_info(),
-                to: ctx.accounts.buyer_token_account.to_account_info(),
-                authority: ctx.accounts.bonding_curve.to_account_info(),
+                from: accounts.curve_token_account.clone(),
+                to: accounts.buyer_token_account.clone(),
+                authority: curve_info.clone(),
             },
             signer_seeds,
         ),
-        tokens,
+        quote.tokens,
     )?;
 
-    // Split trade fee: 50% deployer, 50% protocol (of which OPS_SHARE_BPS% → ops, rest → treasury)
-    let deployer_share = trade_fee / 2;
-    let treasury_trade_share = trade_fee.checked_sub(deployer_share).ok_or(FyrstError::MathOverflow)?;
-    if treasury_trade_share > 0 {
-        let ops_share = treasury_trade_share
-            .checked_mul(OPS_SHARE_BPS)
-            .ok_or(FyrstError::MathOverflow)?
-            .checked_div(BPS_DENOMINATOR)
-            .ok_or(FyrstError::MathOverflow)?;
-        let buyback_share = treasury_trade_share.checked_sub(ops_share).ok_or(FyrstError::MathOverflow)?;
-        if ops_share > 0 {
-            **ctx.accounts.bonding_curve.to_account_info().try_borrow_mut_lamports()? -= ops_share;
-            **ctx.accounts.ops_wallet.to_account_info().try_borrow_mut_lamports()? += ops_share;
-        }
-        if buyback_share > 0 {
-            **ctx.accounts.bonding_curve.to_account_info().try_borrow_mut_lamports()? -= buyback_share;
-            **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += buyback_share;
-        }
+    // Holding cap on the buyer's post-trade balance (lifted once graduated).
+    // Read before record_buy so the buy that graduates the curve is capped too.
+    let max_wallet_tokens = accounts.bonding_curve.max_wallet_tokens()?;
+
+    let events = record_buy(accounts.bonding_curve, accounts.buyer.key(), quote, now)?;
+    accounts.bonding_curve.add_contribution(position, quote.sol_amount, quote.tokens)?;
+
+    if let Some(max_tokens) = max_wallet_tokens {
+        let balance = token::accessor::amount(&accounts.buyer_token_account)?;
+        require!(balance <= max_tokens, FyrstError::MaxWalletExceeded);
     }
 
-    let graduation_threshold = ctx.accounts.protocol_config.graduation_threshold;
+    Ok(events)
+}
 
-    // Update curve state
-    let curve = &mut ctx.accounts.bonding_curve;
+/// Apply a priced buy to curve state: accrues fees, moves reserves and supply
+/// (auto-graduating if the condition is met). Token and SOL transfers are the
+/// caller's job. Returns the trade event and any graduation event to emit.
+pub(crate) fn record_buy(
+    curve: &mut Account<'_, BondingCurve>,
+    trader: Pubkey,
+    quote: &BuyQuote,
+    now: i64,
+) -> Result<(TradeEvent, Option<Graduated>)> {
+    let fees = quote.fees;
+    curve.accrued_ops_fees = curve
+        .accrued_ops_fees
+        .checked_add(fees.ops_fee)
+        .ok_or(FyrstError::MathOverflow)?;
+    curve.accrued_treasury_fees = curve
+        .accrued_treasury_fees
+        .checked_add(fees.treasury_fee)
+        .ok_or(FyrstError::MathOverflow)?;
     curve.virtual_token_reserves = curve
         .virtual_token_reserves
-        .checked_sub(tokens)
+        .checked_sub(quote.tokens)
         .ok_or(FyrstError::MathOverflow)?;
     curve.virtual_sol_reserves = curve
         .virtual_sol_reserves
-        .checked_add(net_sol)
+        .checked_add(quote.net_sol)
         .ok_or(FyrstError::MathOverflow)?;
     curve.real_token_reserves = curve
         .real_token_reserves
-        .checked_sub(tokens)
+        .checked_sub(quote.tokens)
         .ok_or(FyrstError::MathOverflow)?;
     curve.real_sol_reserves = curve
         .real_sol_reserves
-        .checked_add(net_sol)
+        .checked_add(quote.net_sol)
         .ok_or(FyrstError::MathOverflow)?;
     curve.current_supply = curve
         .current_supply
-        .checked_add(tokens)
+        .checked_add(quote.tokens)
         .ok_or(FyrstError::MathOverflow)?;
     curve.reserve_balance = curve
         .reserve_balance
-        .checked_add(net_sol)
+        .checked_add(quote.net_sol)
         .ok_or(FyrstError::MathOverflow)?;
     curve.total_sol_collected = curve
         .total_sol_collected
-        .checked_add(net_sol)
+        .checked_add(quote.net_sol)
         .ok_or(FyrstError::MathOverflow)?;
     curve.total_deployer_fees = curve
         .total_deployer_fees
-        .checked_add(deployer_share)
+        .checked_add(fees.deployer_fee)
         .ok_or(FyrstError::MathOverflow)?;
+    curve.holder_pool = curve
+        .holder_pool
+        .checked_add(fees.sniper_tax)
+        .ok_or(FyrstError::MathOverflow)?;
+    if trader == curve.deployer {
+        curve.deployer_tokens_bought = curve
+            .deployer_tokens_bought
+            .checked_add(quote.tokens)
+            .ok_or(FyrstError::MathOverflow)?;
+    }
 
-    // Update max_reserve_reached (capped at GRADUATION_THRESHOLD)
-    let capped_reserve = curve.reserve_balance.min(graduation_threshold);
+    // Update max_reserve_reached (capped at the curve's graduation threshold)
+    let capped_reserve = curve.reserve_balance.min(curve.graduation_threshold);
     if capped_reserve > curve.max_reserve_reached {
         curve.max_reserve_reached = capped_reserve;
     }
 
-    // Auto-graduation check: SOL threshold OR all real tokens sold
-    if curve.reserve_balance >= graduation_threshold || curve.real_token_reserves == 0 {
+    // Auto-graduation check: SOL threshold OR all real tokens sold. A launch
+    // past its deadline never graduates, even when a late batch-opening
+    // settlement lands its buy.
+    let auto_graduated = !curve.has_failed(now) && math::graduation_reached(curve);
+    if auto_graduated {
         curve.graduated = true;
-        msg!("Token auto-graduated: mint={}", curve.token_mint);
+        curve.graduated_at = now;
     }
 
-    msg!(
-        "Buy: buyer={}, sol={}, tokens={}, new_supply={}, vt={}, vs={}",
-        ctx.accounts.buyer.key(),
-        sol_amount,
-        tokens,
-        curve.current_supply,
-        curve.virtual_token_reserves,
-        curve.virtual_sol_reserves
-    );
-
-    Ok(())
+    let trade = TradeEvent {
+        token_mint: curve.token_mint,
+        trader,
+        is_buy: true,
+        sol_amount: quote.sol_amount,
+        token_amount: quote.tokens,
+        trade_fee: fees.trade_fee,
+        protocol_fee: fees.protocol_fee,
+        deployer_fee: fees.deployer_fee,
+        ops_fee: fees.ops_fee,
+        treasury_fee: fees.treasury_fee,
+        sniper_tax: fees.sniper_tax,
+        virtual_token_reserves: curve.virtual_token_reserves,
+        virtual_sol_reserves: curve.virtual_sol_reserves,
+        real_token_reserves: curve.real_token_reserves,
+        real_sol_reserves: curve.real_sol_reserves,
+        reserve_balance: curve.reserve_balance,
+        current_supply: curve.current_supply,
+        timestamp: now,
+    };
+    let graduated = auto_graduated.then(|| Graduated {
+        token_mint: curve.token_mint,
+        bonding_curve: curve.key(),
+        reserve_balance: curve.reserve_balance,
+        real_token_reserves: curve.real_token_reserves,
+        current_supply: curve.current_supply,
+        timestamp: now,
+    });
+
+    Ok((trade, graduated))
 }
 
-/// Sell tokens on the bonding curve — constant product AMM (x*y=k).
-/// Transfers tokens from seller ATA back to curve ATA.
+/// Sell tokens on the bonding curve, priced by the curve's `CurveKind`.
+/// Transfers tokens from seller ATA back to curve ATA. Protocol fees accrue on
+/// the curve (see `sweep_protocol_fees`). Errors if the curve's SOL reserve
+/// cannot cover the full curve price of `token_amount`. Rejected before the
+/// curve's scheduled `trading_opens_at`.
 pub fn sell_tokens(ctx: Context<SellTokens>, token_amount: u64, min_sol_out: u64) -> Result<()> {
-    let net_sol: u64;
-    let trade_fee_sell: u64;
-    let protocol_fee_sell: u64;
-    let gross_sol: u64;
-    {
-        let curve = &ctx.accounts.bonding_curve;
-
-        require!(!curve.graduated, FyrstError::AlreadyGraduated);
-        require!(token_amount > 0, FyrstError::InsufficientTokens);
-
-        // Constant product AMM: sol_out = virtual_sol - k / (virtual_token + token_amount)
-        let vt = curve.virtual_token_reserves as u128;
-        let vs = curve.virtual_sol_reserves as u128;
-        let k = vt.checked_mul(vs).ok_or(FyrstError::MathOverflow)?;
-        let new_vt = vt.checked_add(token_amount as u128).ok_or(FyrstError::MathOverflow)?;
-        let new_vs = k.checked_div(new_vt).ok_or(FyrstError::MathOverflow)?;
-        let amm_sol_out = vs.checked_sub(new_vs).ok_or(FyrstError::MathOverflow)? as u64;
-
-        // Cap at real SOL reserves (safety)
-        gross_sol = amm_sol_out.min(curve.real_sol_reserves).min(curve.reserve_balance);
-
-        trade_fee_sell = gross_sol
-            .checked_mul(TRADE_FEE_BPS)
-            .ok_or(FyrstError::MathOverflow)?
-            .checked_div(BPS_DENOMINATOR)
-            .ok_or(FyrstError::MathOverflow)?;
+    ctx.accounts.bonding_curve.require_open(Clock::get()?.unix_timestamp)?;
+    let quote = math::quote_sell(&ctx.accounts.bonding_curve, token_amount)?;
+    require!(quote.net_sol >= min_sol_out, FyrstError::SlippageExceeded);
 
-        protocol_fee_sell = gross_sol
-            .checked_mul(PROTOCOL_FEE_BPS)
-            .ok_or(FyrstError::MathOverflow)?
-            .checked_div(BPS_DENOMINATOR)
-            .ok_or(FyrstError::MathOverflow)?;
+    let trade = settle_sell(ctx.accounts, ctx.bumps.position, &quote)?;
+    emit_cpi!(trade);
 
-        net_sol = gross_sol
-            .checked_sub(trade_fee_sell)
-            .ok_or(FyrstError::MathOverflow)?
-            .checked_sub(protocol_fee_sell)
-            .ok_or(FyrstError::MathOverflow)?;
+    Ok(())
+}
 
-        require!(net_sol >= min_sol_out, FyrstError::SlippageExceeded);
-    }
+/// Sell for an exact net SOL amount (after fees), giving up at most `max_tokens_in`.
+/// Takes only the tokens the inverse curve requires; errors if the curve's SOL
+/// reserve cannot cover `sol_out`.
+pub fn sell_for_exact_sol(ctx: Context<SellTokens>, sol_out: u64, max_tokens_in: u64) -> Result<()> {
+    ctx.accounts.bonding_curve.require_open(Clock::get()?.unix_timestamp)?;
+    let quote = math::quote_sell_exact_sol(&ctx.accounts.bonding_curve, sol_out)?;
+    require!(quote.tokens <= max_tokens_in, FyrstError::SlippageExceeded);
+
+    let trade = settle_sell(ctx.accounts, ctx.bumps.position, &quote)?;
+    emit_cpi!(trade);
+
+    Ok(())
+}
 
+/// Settle a priced sell: moves tokens in and SOL out, accrues fees and updates
+/// curve state — total_sol_collected does NOT decrease. The SOL received comes
+/// off the seller's net contribution. Deployer sells are tallied and flag the
+/// curve as dumped past the dump threshold.
+fn settle_sell(accounts: &mut SellTokens<'_>, position_bump: u8, quote: &SellQuote) -> Result<TradeEvent> {
     // Transfer tokens from seller ATA to curve ATA
     token::transfer(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
+            accounts.token_program.to_account_info(),
             token::Transfer {
-                from: ctx.accounts.seller_token_account.to_account_info(),
-                to: ctx.accounts.curve_token_account.to_account_info(),
-                authority: ctx.accounts.seller.to_account_info(),
+                from: accounts.seller_token_account.to_account_info(),
+                to: accounts.curve_token_account.to_account_info(),
+                authority: accounts.seller.to_account_info(),
             },
         ),
-        token_amount,
+        quote.tokens,
     )?;
 
     // Transfer SOL from curve PDA to seller
-    **ctx.accounts.bonding_curve.to_account_info().try_borrow_mut_lamports()? -= net_sol;
-    **ctx.accounts.seller.to_account_info().try_borrow_mut_lamports()? += net_sol;
-
-    // Split trade fee: 50% deployer, 50% protocol (of which OPS_SHARE_BPS% → ops, rest → treasury)
-    let deployer_share = trade_fee_sell / 2;
-    let treasury_trade_share = trade_fee_sell.checked_sub(deployer_share).ok_or(FyrstError::MathOverflow)?;
-    let total_protocol_sell = treasury_trade_share
-        .checked_add(protocol_fee_sell)
+    **accounts.bonding_curve.to_account_info().try_borrow_mut_lamports()? -= quote.net_sol;
+    **accounts.seller.to_account_info().try_borrow_mut_lamports()? += quote.net_sol;
+
+    let fees = quote.fees;
+    let curve = &mut accounts.bonding_curve;
+    curve.accrued_ops_fees = curve
+        .accrued_ops_fees
+        .checked_add(fees.ops_fee)
+        .ok_or(FyrstError::MathOverflow)?;
+    curve.accrued_treasury_fees = curve
+        .accrued_treasury_fees
+        .checked_add(fees.treasury_fee)
         .ok_or(FyrstError::MathOverflow)?;
-    if total_protocol_sell > 0 {
-        let ops_share = total_protocol_sell
-            .checked_mul(OPS_SHARE_BPS)
-            .ok_or(FyrstError::MathOverflow)?
-            .checked_div(BPS_DENOMINATOR)
-            .ok_or(FyrstError::MathOverflow)?;
-        let buyback_share = total_protocol_sell.checked_sub(ops_share).ok_or(FyrstError::MathOverflow)?;
-        if ops_share > 0 {
-            **ctx.accounts.bonding_curve.to_account_info().try_borrow_mut_lamports()? -= ops_share;
-            **ctx.accounts.ops_wallet.to_account_info().try_borrow_mut_lamports()? += ops_share;
-        }
-        if buyback_share > 0 {
-            **ctx.accounts.bonding_curve.to_account_info().try_borrow_mut_lamports()? -= buyback_share;
-            **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += buyback_share;
-        }
-    }
-
-    // Update curve state — total_sol_collected does NOT decrease
-    let curve = &mut ctx.accounts.bonding_curve;
     curve.virtual_token_reserves = curve
         .virtual_token_reserves
-        .checked_add(token_amount)
+        .checked_add(quote.tokens)
         .ok_or(FyrstError::MathOverflow)?;
     curve.virtual_sol_reserves = curve
         .virtual_sol_reserves
-        .checked_sub(gross_sol)
+        .checked_sub(quote.gross_sol)
         .ok_or(FyrstError::MathOverflow)?;
     curve.real_token_reserves = curve
         .real_token_reserves
-        .checked_add(token_amount)
+        .checked_add(quote.tokens)
         .ok_or(FyrstError::MathOverflow)?;
     curve.real_sol_reserves = curve
         .real_sol_reserves
-        .checked_sub(gross_sol)
+        .checked_sub(quote.gross_sol)
         .ok_or(FyrstError::MathOverflow)?;
     curve.current_supply = curve
         .current_supply
-        .checked_sub(token_amount)
+        .checked_sub(quote.tokens)
         .ok_or(FyrstError::MathOverflow)?;
     curve.reserve_balance = curve
         .reserve_balance
-        .checked_sub(gross_sol)
+        .checked_sub(quote.gross_sol)
         .ok_or(FyrstError::MathOverflow)?;
     curve.total_deployer_fees = curve
         .total_deployer_fees
-        .checked_add(deployer_share)
+        .checked_add(fees.deployer_fee)
         .ok_or(FyrstError::MathOverflow)?;
-
-    msg!(
-        "Sell: seller={}, tokens={}, sol={}, new_supply={}, vt={}, vs={}",
-        ctx.accounts.seller.key(),
-        token_amount,
-        net_sol,
-        curve.current_supply,
-        curve.virtual_token_reserves,
-        curve.virtual_sol_reserves
-    );
-
-    Ok(())
-}
-
-#[derive(Accounts)]
-pub struct InitBondingCurve<'info> {
-    #[account(mut)]
-    pub deployer: Signer<'info>,
-
-    #[account(
-        init,
-        payer = deployer,
-        mint::decimals = TOKEN_DECIMALS,
-        mint::authority = bonding_curve,
-    )]
-    pub token_mint: Account<'info, Mint>,
-
-    #[account(
-        init,
-        payer = deployer,
-        space = BondingCurve::LEN,
-        seeds = [CURVE_SEED, token_mint.key().as_ref()],
-        bump,
-    )]
-    pub bonding_curve: Account<'info, BondingCurve>,
-
-    /// Curve's token ATA — holds entire token supply for AMM transfers
-    #[account(
-        init,
-        payer = deployer,
-        associated_token::mint = token_mint,
-        associated_token::authority = bonding_curve,
-    )]
-    pub curve_token_account: Account<'info, TokenAccount>,
-
-    /// CHECK: Created by Metaplex CPI — validated by the Metaplex program
-    #[account(mut)]
-    pub metadata_account: UncheckedAccount<'info>,
-
-    /// CHECK: Metaplex Token Metadata program
-    #[account(address = TOKEN_METADATA_PROGRAM_ID)]
-    pub metadata_program: UncheckedAccount<'info>,
-
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+    if accounts.seller.key() == curve.deployer {
+        curve.deployer_tokens_sold = curve
+            .deployer_tokens_sold
+            .checked_add(quote.tokens)
+            .ok_or(FyrstError::MathOverflow)?;
+        if math::deployer_dumped(curve, curve.deployer_tokens_sold)? {
+            curve.dumped = true;
+        }
+    }
+    let position = &mut accounts.position;
+    position.open(curve.key(), accounts.seller.key(), position_bump);
+    curve.sell_contribution(position, quote.tokens)?;
+
+    Ok(TradeEvent {
+        token_mint: curve.token_mint,
+        trader: accounts.seller.key(),
+        is_buy: false,
+        sol_amount: quote.gross_sol,
+        token_amount: quote.tokens,
+        trade_fee: fees.trade_fee,
+        protocol_fee: fees.protocol_fee,
+        deployer_fee: fees.deployer_fee,
+        ops_fee: fees.ops_fee,
+        treasury_fee: fees.treasury_fee,
+        sniper_tax: fees.sniper_tax,
+        virtual_token_reserves: curve.virtual_token_reserves,
+        virtual_sol_reserves: curve.virtual_sol_reserves,
+        real_token_reserves: curve.real_token_reserves,
+        real_sol_reserves: curve.real_sol_reserves,
+        reserve_balance: curve.reserve_balance,
+        current_supply: curve.current_supply,
+        timestamp: Clock::get()?.unix_timestamp,
+    })
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct BuyTokens<'info> {
     #[account(mut)]
@@ -545,31 +504,22 @@ pub struct BuyTokens<'info> {
     )]
     pub buyer_token_account: Account<'info, TokenAccount>,
 
+    /// Buyer's position on this curve — tracks launch-window spend and net SOL contributed
     #[account(
-        seeds = [PROTOCOL_SEED],
-        bump = protocol_config.bump,
-    )]
-    pub protocol_config: Account<'info, ProtocolConfig>,
-
-    /// CHECK: Treasury wallet from protocol config (buyback+burn)
-    #[account(
-        mut,
-        address = protocol_config.treasury @ FyrstError::Unauthorized,
-    )]
-    pub treasury: UncheckedAccount<'info>,
-
-    /// CHECK: Operations wallet from protocol config (service revenue)
-    #[account(
-        mut,
-        address = protocol_config.ops_wallet @ FyrstError::Unauthorized,
+        init_if_needed,
+        payer = buyer,
+        space = Position::LEN,
+        seeds = [POSITION_SEED, bonding_curve.key().as_ref(), buyer.key().as_ref()],
+        bump,
     )]
-    pub ops_wallet: UncheckedAccount<'info>,
+    pub position: Account<'info, Position>,
 
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct SellTokens<'info> {
     #[account(mut)]
@@ -602,25 +552,15 @@ pub struct SellTokens<'info> {
     )]
     pub seller_token_account: Account<'info, TokenAccount>,
 
+    /// Seller's position on this curve — sells reduce its net SOL contributed
     #[account(
-        seeds = [PROTOCOL_SEED],
-        bump = protocol_config.bump,
-    )]
-    pub protocol_config: Account<'info, ProtocolConfig>,
-
-    /// CHECK: Treasury wallet from protocol config (buyback+burn)
-    #[account(
-        mut,
-        address = protocol_config.treasury @ FyrstError::Unauthorized,
-    )]
-    pub treasury: UncheckedAccount<'info>,
-
-    /// CHECK: Operations wallet from protocol config (service revenue)
-    #[account(
-        mut,
-        address = protocol_config.ops_wallet @ FyrstError::Unauthorized,
+        init_if_needed,
+        payer = seller,
+        space = Position::LEN,
+        seeds = [POSITION_SEED, bonding_curve.key().as_ref(), seller.key().as_ref()],
+        bump,
     )]
-    pub ops_wallet: UncheckedAccount<'info>,
+    pub position: Account<'info, Position>,
 
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
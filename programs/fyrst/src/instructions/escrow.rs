@@ -1,98 +1,198 @@
 use anchor_lang::prelude::*;
-use anchor_lang::system_program;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token;
 use crate::state::{EscrowVault, BondingCurve, ProtocolConfig};
 use crate::errors::FyrstError;
+use crate::math;
+use crate::events::{CollateralClaimed, EscrowExpired, EscrowReleased, EscrowSlashed, LaunchFailed};
 use crate::constants::*;
 
-/// Create an escrow vault with deployer collateral and custom deadline
-pub fn create_escrow(ctx: Context<CreateEscrow>, collateral_amount: u64, duration_seconds: i64) -> Result<()> {
-    require!(
-        collateral_amount >= MIN_COLLATERAL,
-        FyrstError::InsufficientCollateral
-    );
+/// Release escrow back to deployer once graduated and the post-graduation
+/// lock has fully unlocked. Anchor `close = deployer` closes the PDA, returning
+/// the unclaimed collateral + rent to deployer.
+pub fn release_escrow(ctx: Context<ReleaseEscrow>) -> Result<()> {
+    let escrow = &ctx.accounts.escrow_vault;
+    let curve = &ctx.accounts.bonding_curve;
+
+    require!(!escrow.released, FyrstError::EscrowAlreadyReleased);
+    require!(curve.graduated, FyrstError::NotGraduated);
     require!(
-        duration_seconds >= MIN_DURATION && duration_seconds <= MAX_DURATION,
-        FyrstError::InvalidDuration
+        math::locked_collateral(escrow, curve, Clock::get()?.unix_timestamp)? == 0,
+        FyrstError::CollateralLocked
     );
 
+    emit_cpi!(EscrowReleased {
+        escrow_vault: escrow.key(),
+        deployer: escrow.deployer,
+        token_mint: escrow.token_mint,
+        amount: escrow.to_account_info().lamports(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    // Anchor `close = deployer` handles all lamport transfer + account cleanup
+
+    Ok(())
+}
+
+/// Claim collateral unlocked so far under the escrow's post-graduation lock
+/// (deployer only). The still-locked remainder stays in the escrow, slashable.
+pub fn claim_unlocked_collateral(ctx: Context<ClaimUnlockedCollateral>) -> Result<()> {
+    let escrow = &ctx.accounts.escrow_vault;
+    let curve = &ctx.accounts.bonding_curve;
     let now = Clock::get()?.unix_timestamp;
 
-    // Transfer SOL from deployer to escrow PDA (before mutable borrow)
-    system_program::transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.deployer.to_account_info(),
-                to: ctx.accounts.escrow_vault.to_account_info(),
-            },
-        ),
-        collateral_amount,
-    )?;
+    require!(!escrow.released, FyrstError::EscrowAlreadyReleased);
+    require!(curve.graduated, FyrstError::NotGraduated);
+
+    let claimable = math::claimable_collateral(escrow, curve, now)?;
+    require!(claimable > 0, FyrstError::NoCollateralToClaim);
+    let locked = math::locked_collateral(escrow, curve, now)?;
+
+    **ctx.accounts.escrow_vault.to_account_info().try_borrow_mut_lamports()? -= claimable;
+    **ctx.accounts.deployer.to_account_info().try_borrow_mut_lamports()? += claimable;
 
     let escrow = &mut ctx.accounts.escrow_vault;
-    escrow.deployer = ctx.accounts.deployer.key();
-    escrow.token_mint = ctx.accounts.token_mint.key();
-    escrow.collateral_amount = collateral_amount;
-    escrow.created_at = now;
-    escrow.deadline_timestamp = now + duration_seconds;
-    escrow.released = false;
-    escrow.bump = ctx.bumps.escrow_vault;
-
-    msg!(
-        "Escrow created: deployer={}, mint={}, collateral={}, deadline={}",
-        escrow.deployer,
-        escrow.token_mint,
-        collateral_amount,
-        escrow.deadline_timestamp
-    );
+    escrow.collateral_claimed = escrow
+        .collateral_claimed
+        .checked_add(claimable)
+        .ok_or(FyrstError::MathOverflow)?;
+
+    emit_cpi!(CollateralClaimed {
+        escrow_vault: escrow.key(),
+        deployer: escrow.deployer,
+        token_mint: escrow.token_mint,
+        amount: claimable,
+        total_claimed: escrow.collateral_claimed,
+        locked,
+        timestamp: now,
+    });
 
     Ok(())
 }
 
-/// Release escrow back to deployer (requires token graduation)
-/// Anchor `close = deployer` closes the PDA, returning collateral + rent to deployer.
-pub fn release_escrow(ctx: Context<ReleaseEscrow>) -> Result<()> {
+/// Slash a dumping deployer's escrow (permissionless). The deployer has dumped
+/// once they sell back to the curve, or (while the dump window is open) move
+/// out of their wallet, more than dump_threshold_bps of the tokens they
+/// acquired from the curve. All still-locked collateral moves to the curve's
+/// slashed pool, which holders draw on pro-rata through `process_refund`.
+pub fn slash_escrow(ctx: Context<SlashEscrow>) -> Result<()> {
     let escrow = &ctx.accounts.escrow_vault;
     let curve = &ctx.accounts.bonding_curve;
+    let now = Clock::get()?.unix_timestamp;
 
     require!(!escrow.released, FyrstError::EscrowAlreadyReleased);
-    require!(curve.graduated, FyrstError::NotGraduated);
-
-    msg!(
-        "Escrow released: deployer={}, amount={}",
-        escrow.deployer,
-        escrow.collateral_amount
+    require!(!curve.slashed, FyrstError::AlreadySlashed);
+
+    // Tokens missing from the deployer's wallet count as sold (DEX sells,
+    // transfers), except those burned for refunds
+    let mut tokens_sold = curve.deployer_tokens_sold;
+    if curve.dump_window_active(&escrow.collateral_lock, now) {
+        let balance = token_balance(&ctx.accounts.deployer_token_account)?;
+        let missing = curve
+            .deployer_tokens_bought
+            .saturating_sub(curve.deployer_tokens_refunded)
+            .saturating_sub(balance);
+        tokens_sold = tokens_sold.max(missing);
+    }
+    require!(
+        curve.dumped || math::deployer_dumped(curve, tokens_sold)?,
+        FyrstError::DeployerNotDumped
     );
 
-    // Anchor `close = deployer` handles all lamport transfer + account cleanup
+    let amount = math::locked_collateral(escrow, curve, now)?;
+    require!(amount > 0, FyrstError::NoCollateralToSlash);
+
+    **ctx.accounts.escrow_vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.bonding_curve.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    let escrow = &mut ctx.accounts.escrow_vault;
+    escrow.collateral_slashed = amount;
+
+    let curve = &mut ctx.accounts.bonding_curve;
+    curve.dumped = true;
+    curve.slashed = true;
+    curve.slashed_collateral = curve
+        .slashed_collateral
+        .checked_add(amount)
+        .ok_or(FyrstError::MathOverflow)?;
+
+    emit_cpi!(EscrowSlashed {
+        escrow_vault: escrow.key(),
+        deployer: escrow.deployer,
+        token_mint: escrow.token_mint,
+        slasher: ctx.accounts.slasher.key(),
+        amount,
+        deployer_tokens_bought: curve.deployer_tokens_bought,
+        deployer_tokens_sold: tokens_sold,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Balance of an SPL token account, or 0 if it was closed or never opened
+fn token_balance(account: &AccountInfo) -> Result<u64> {
+    if account.owner != &token::ID || account.data_is_empty() {
+        return Ok(0);
+    }
+    token::accessor::amount(account)
+}
+
+/// Mark a launch that missed its deadline without graduating as failed
+/// (permissionless). Trading is already rejected from the deadline on; this
+/// records the state for clients and indexers. Refunds and cleanup remain.
+pub fn mark_failed(ctx: Context<MarkFailed>) -> Result<()> {
+    let curve = &mut ctx.accounts.bonding_curve;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(!curve.graduated, FyrstError::AlreadyGraduated);
+    require!(!curve.failed, FyrstError::AlreadyMarkedFailed);
+    require!(now >= curve.deadline_timestamp, FyrstError::DeadlineNotReached);
+
+    curve.failed = true;
+
+    let failed = LaunchFailed {
+        token_mint: curve.token_mint,
+        bonding_curve: curve.key(),
+        deadline_timestamp: curve.deadline_timestamp,
+        reserve_balance: curve.reserve_balance,
+        current_supply: curve.current_supply,
+        timestamp: now,
+    };
+    emit_cpi!(failed);
 
     Ok(())
 }
 
 /// Expire escrow after deadline when no holders exist (permissionless).
 /// 50% collateral → deployer refund, 50% → treasury (for $FYRST buyback+burn).
+/// Any reserve dust, sniper tax or slashed collateral left on the curve also
+/// goes to the treasury.
 pub fn expire_escrow(ctx: Context<ExpireEscrow>) -> Result<()> {
     let escrow = &ctx.accounts.escrow_vault;
-    let curve = &ctx.accounts.bonding_curve;
+    let curve = &mut ctx.accounts.bonding_curve;
     let now = Clock::get()?.unix_timestamp;
 
     require!(!escrow.released, FyrstError::EscrowAlreadyReleased);
     require!(now >= escrow.deadline_timestamp, FyrstError::DeadlineNotReached);
     require!(!curve.graduated, FyrstError::AlreadyGraduated);
-    require!(curve.current_supply == 0, FyrstError::TokensStillCirculating);
+    require!(!curve.refunds_outstanding(), FyrstError::TokensStillCirculating);
+    require!(escrow.refund_snapshot == Pubkey::default(), FyrstError::RefundSnapshotActive);
+    require!(
+        !curve.has_batch_opening() || curve.opening_settled,
+        FyrstError::OpeningNotSettled
+    );
 
-    let collateral = escrow.collateral_amount;
+    // Refunds may have paid holders part of the collateral: split what is
+    // left above rent. The rent goes to the deployer when the escrow closes.
+    let escrow_info = ctx.accounts.escrow_vault.to_account_info();
+    let rent = Rent::get()?.minimum_balance(escrow_info.data_len());
+    let collateral = escrow_info.lamports().saturating_sub(rent);
     let protocol_share = collateral / 2;
 
-    // Split protocol share: OPS_SHARE_BPS% → ops_wallet, rest → treasury
-    let ops_share = protocol_share
-        .checked_mul(OPS_SHARE_BPS)
-        .ok_or(FyrstError::MathOverflow)?
-        .checked_div(BPS_DENOMINATOR)
-        .ok_or(FyrstError::MathOverflow)?;
-    let buyback_share = protocol_share.checked_sub(ops_share).ok_or(FyrstError::MathOverflow)?;
+    // Split protocol share: ops_share_bps → ops_wallet, rest → treasury
+    let (ops_share, buyback_share) = math::ops_split(protocol_share, curve.fee_schedule.ops_share_bps)?;
 
-    let escrow_info = ctx.accounts.escrow_vault.to_account_info();
     if ops_share > 0 {
         **escrow_info.try_borrow_mut_lamports()? -= ops_share;
         **ctx.accounts.ops_wallet.to_account_info().try_borrow_mut_lamports()? += ops_share;
@@ -102,6 +202,22 @@ pub fn expire_escrow(ctx: Context<ExpireEscrow>) -> Result<()> {
         **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += buyback_share;
     }
 
+    // No holders left to refund: refund rounding dust in the reserve and
+    // unclaimed holder pools go to buyback
+    let holder_pool = curve
+        .reserve_balance
+        .checked_add(curve.holder_pool)
+        .and_then(|sum| sum.checked_add(curve.slashed_collateral))
+        .ok_or(FyrstError::MathOverflow)?;
+    if holder_pool > 0 {
+        **curve.to_account_info().try_borrow_mut_lamports()? -= holder_pool;
+        **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += holder_pool;
+        curve.reserve_balance = 0;
+        curve.real_sol_reserves = 0;
+        curve.holder_pool = 0;
+        curve.slashed_collateral = 0;
+    }
+
     // Close escrow PDA — remaining lamports (deployer_share + rent) go to deployer
     let remaining = escrow_info.lamports();
     **escrow_info.try_borrow_mut_lamports()? = 0;
@@ -109,41 +225,52 @@ pub fn expire_escrow(ctx: Context<ExpireEscrow>) -> Result<()> {
 
     // Zero out account data and assign to system program
     escrow_info.assign(&anchor_lang::solana_program::system_program::ID);
-    escrow_info.realloc(0, false)?;
+    escrow_info.resize(0)?;
 
-    msg!(
-        "Escrow expired: deployer={}, ops_share={}, buyback_share={}, deployer_refund={}",
-        escrow.deployer,
+    emit_cpi!(EscrowExpired {
+        escrow_vault: escrow_info.key(),
+        deployer: escrow.deployer,
+        token_mint: escrow.token_mint,
         ops_share,
         buyback_share,
-        remaining
-    );
+        holder_pool,
+        deployer_refund: remaining,
+        timestamp: now,
+    });
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct CreateEscrow<'info> {
+pub struct ReleaseEscrow<'info> {
     #[account(mut)]
     pub deployer: Signer<'info>,
 
-    /// CHECK: Token mint account (validated by seed derivation)
-    pub token_mint: UncheckedAccount<'info>,
-
     #[account(
-        init,
-        payer = deployer,
-        space = EscrowVault::LEN,
-        seeds = [ESCROW_SEED, deployer.key().as_ref(), token_mint.key().as_ref()],
-        bump,
+        mut,
+        seeds = [ESCROW_SEED, deployer.key().as_ref(), escrow_vault.token_mint.as_ref()],
+        bump = escrow_vault.bump,
+        has_one = deployer,
+        has_one = bonding_curve @ FyrstError::EscrowMismatch,
+        close = deployer,
     )]
     pub escrow_vault: Account<'info, EscrowVault>,
 
+    #[account(
+        seeds = [CURVE_SEED, escrow_vault.token_mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = escrow_vault @ FyrstError::EscrowMismatch,
+        has_one = deployer @ FyrstError::Unauthorized,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct ReleaseEscrow<'info> {
+pub struct ClaimUnlockedCollateral<'info> {
     #[account(mut)]
     pub deployer: Signer<'info>,
 
@@ -152,19 +279,62 @@ pub struct ReleaseEscrow<'info> {
         seeds = [ESCROW_SEED, deployer.key().as_ref(), escrow_vault.token_mint.as_ref()],
         bump = escrow_vault.bump,
         has_one = deployer,
-        close = deployer,
+        has_one = bonding_curve @ FyrstError::EscrowMismatch,
     )]
     pub escrow_vault: Account<'info, EscrowVault>,
 
     #[account(
         seeds = [CURVE_SEED, escrow_vault.token_mint.as_ref()],
         bump = bonding_curve.bump,
+        has_one = escrow_vault @ FyrstError::EscrowMismatch,
+        has_one = deployer @ FyrstError::Unauthorized,
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
+}
 
-    pub system_program: Program<'info, System>,
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SlashEscrow<'info> {
+    /// Anyone may crank a slash
+    pub slasher: Signer<'info>,
+
+    /// The one escrow recorded on the curve at launch
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, bonding_curve.deployer.as_ref(), bonding_curve.token_mint.as_ref()],
+        bump = escrow_vault.bump,
+        has_one = bonding_curve @ FyrstError::EscrowMismatch,
+        constraint = escrow_vault.deployer == bonding_curve.deployer @ FyrstError::EscrowMismatch,
+    )]
+    pub escrow_vault: Account<'info, EscrowVault>,
+
+    #[account(
+        mut,
+        seeds = [CURVE_SEED, bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = escrow_vault @ FyrstError::EscrowMismatch,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    /// CHECK: Deployer's token ATA, read for its balance (may be closed or uninitialized)
+    #[account(
+        address = get_associated_token_address(&bonding_curve.deployer, &bonding_curve.token_mint),
+    )]
+    pub deployer_token_account: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MarkFailed<'info> {
+    #[account(
+        mut,
+        seeds = [CURVE_SEED, bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ExpireEscrow<'info> {
     /// CHECK: Deployer wallet (receives 50% refund + rent). Not required to sign.
@@ -176,12 +346,16 @@ pub struct ExpireEscrow<'info> {
         seeds = [ESCROW_SEED, deployer.key().as_ref(), escrow_vault.token_mint.as_ref()],
         bump = escrow_vault.bump,
         has_one = deployer,
+        has_one = bonding_curve @ FyrstError::EscrowMismatch,
     )]
     pub escrow_vault: Account<'info, EscrowVault>,
 
     #[account(
+        mut,
         seeds = [CURVE_SEED, escrow_vault.token_mint.as_ref()],
         bump = bonding_curve.bump,
+        has_one = escrow_vault @ FyrstError::EscrowMismatch,
+        has_one = deployer @ FyrstError::Unauthorized,
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
 
@@ -4,6 +4,8 @@ use anchor_spl::token::{self, Mint, Token, TokenAccount, SetAuthority};
 use anchor_spl::associated_token::AssociatedToken;
 use crate::state::BondingCurve;
 use crate::errors::FyrstError;
+use crate::math;
+use crate::events::DexMigrated;
 use crate::constants::*;
 
 /// Graduate a bonding curve to Raydium CPMM DEX.
@@ -30,7 +32,11 @@ pub fn graduate_to_dex(ctx: Context<GraduateToDex>) -> Result<()> {
     require!(!curve.dex_migrated, FyrstError::AlreadyMigratedToDex);
     require!(curve.reserve_balance > 0, FyrstError::EmptyReserve);
 
-    let reserve_sol = curve.reserve_balance;
+    // Sniper tax in the holder pool migrates with the reserve, deepening the pool
+    let reserve_sol = curve
+        .reserve_balance
+        .checked_add(curve.holder_pool)
+        .ok_or(FyrstError::MathOverflow)?;
     let token_mint_key = curve.token_mint;
     let curve_bump = curve.bump;
 
@@ -45,16 +51,16 @@ pub fn graduate_to_dex(ctx: Context<GraduateToDex>) -> Result<()> {
     require!(reserve_sol > pool_creation_fee, FyrstError::InsufficientFunds);
     let liquidity_sol = reserve_sol - pool_creation_fee;
 
-    // 3. Calculate pool tokens from liquidity_sol (reserve minus fee)
-    //    so Raydium pool initial price matches bonding curve final price.
-    //    Constant product AMM: spot_price = virtual_sol / virtual_token
-    let vt = curve.virtual_token_reserves as u128;
-    let vs = curve.virtual_sol_reserves as u128;
-    require!(vs > 0 && vt > 0, FyrstError::InvalidPrice);
-
-    // pool_tokens = liquidity_sol * virtual_token / virtual_sol
-    let pool_tokens = ((liquidity_sol as u128) * vt / vs) as u64;
-    require!(pool_tokens > 0, FyrstError::InvalidPrice);
+    // 3. Size the pool from liquidity_sol (reserve minus fee) so the Raydium
+    //    pool's initial price matches the bonding curve's final spot price.
+    //    Unclaimed batch-opening tokens stay in the curve ATA for their
+    //    committers; if the rest cannot pair all of liquidity_sol, the pool
+    //    takes only the SOL they are worth and the surplus goes to the treasury.
+    let available_tokens = ctx.accounts.curve_token_account.amount.saturating_sub(curve.opening_tokens);
+    let requested_sol = liquidity_sol;
+    let (liquidity_sol, pool_tokens) = math::pool_liquidity(curve, requested_sol, available_tokens)?;
+    require!(pool_tokens > 0 && liquidity_sol > 0, FyrstError::InvalidPrice);
+    let surplus_sol = requested_sol - liquidity_sol;
 
     let seeds = &[
         CURVE_SEED,
@@ -221,27 +227,37 @@ pub fn graduate_to_dex(ctx: Context<GraduateToDex>) -> Result<()> {
     )?;
 
     // 8. Reimburse payer from bonding curve reserve (AFTER all CPIs — no more CPIs follow)
-    **ctx.accounts.bonding_curve.to_account_info().try_borrow_mut_lamports()? -= reserve_sol;
-    **ctx.accounts.payer.to_account_info().try_borrow_mut_lamports()? += reserve_sol;
+    let reimbursement = reserve_sol - surplus_sol;
+    **ctx.accounts.bonding_curve.to_account_info().try_borrow_mut_lamports()? -= reimbursement;
+    **ctx.accounts.payer.to_account_info().try_borrow_mut_lamports()? += reimbursement;
 
-    // 9. Update state
+    // 9. Update state — the surplus stays on the curve as treasury fees
     let curve = &mut ctx.accounts.bonding_curve;
     curve.dex_migrated = true;
     curve.raydium_pool = ctx.accounts.pool_state.key();
     curve.reserve_balance = 0;
-
-    msg!(
-        "DEX migration: mint={}, pool={}, sol={}, tokens={}, fee={}",
-        token_mint_key,
-        ctx.accounts.pool_state.key(),
+    curve.holder_pool = 0;
+    curve.accrued_treasury_fees = curve
+        .accrued_treasury_fees
+        .checked_add(surplus_sol)
+        .ok_or(FyrstError::MathOverflow)?;
+
+    let migrated = DexMigrated {
+        token_mint: token_mint_key,
+        pool: curve.raydium_pool,
         liquidity_sol,
         pool_tokens,
-        pool_creation_fee
-    );
+        pool_creation_fee,
+        surplus_sol,
+        payer: ctx.accounts.payer.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+    emit_cpi!(migrated);
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct GraduateToDex<'info> {
     /// Anyone can trigger graduation (permissionless).
@@ -0,0 +1,562 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::solana_program::{self, program::invoke_signed};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::{
+    BondingCurve, CurveKind, DeployFeeWaiver, EscrowVault, Position, ProtocolConfig, RefundMode,
+};
+use crate::errors::FyrstError;
+use crate::math;
+use crate::events::{CurveInitialized, EscrowCreated};
+use crate::constants::*;
+use super::bonding_curve::{execute_buy, BuyAccounts};
+
+/// Metaplex Token Metadata program ID
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Build a CreateMetadataAccountV3 instruction manually (no external crate needed).
+/// Instruction discriminator = 33.
+#[allow(clippy::too_many_arguments)]
+fn build_create_metadata_v3_ix(
+    metadata: Pubkey,
+    mint: Pubkey,
+    mint_authority: Pubkey,
+    payer: Pubkey,
+    update_authority: Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> solana_program::instruction::Instruction {
+    let mut data = vec![33u8]; // CreateMetadataAccountV3
+
+    // name (Borsh string = u32 len + bytes)
+    data.extend_from_slice(&(name.len() as u32).to_le_bytes());
+    data.extend_from_slice(name.as_bytes());
+
+    // symbol
+    data.extend_from_slice(&(symbol.len() as u32).to_le_bytes());
+    data.extend_from_slice(symbol.as_bytes());
+
+    // uri
+    data.extend_from_slice(&(uri.len() as u32).to_le_bytes());
+    data.extend_from_slice(uri.as_bytes());
+
+    // seller_fee_basis_points (u16)
+    data.extend_from_slice(&0u16.to_le_bytes());
+
+    // creators (Option<Vec<Creator>>) = None
+    data.push(0);
+
+    // collection (Option<Collection>) = None
+    data.push(0);
+
+    // uses (Option<Uses>) = None
+    data.push(0);
+
+    // is_mutable (bool)
+    data.push(1);
+
+    // collection_details (Option<CollectionDetails>) = None
+    data.push(0);
+
+    solana_program::instruction::Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            solana_program::instruction::AccountMeta::new(metadata, false),
+            solana_program::instruction::AccountMeta::new_readonly(mint, false),
+            solana_program::instruction::AccountMeta::new_readonly(mint_authority, true),
+            solana_program::instruction::AccountMeta::new(payer, true),
+            solana_program::instruction::AccountMeta::new_readonly(update_authority, true),
+            solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::ID, false),
+            solana_program::instruction::AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+        ],
+        data,
+    }
+}
+
+/// Launch parameters for `launch_token`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LaunchTokenArgs {
+    /// Token name (≤ 64 bytes)
+    pub name: String,
+    /// Token symbol (≤ 20 bytes)
+    pub symbol: String,
+    /// Metadata URI (≤ 200 bytes)
+    pub uri: String,
+    /// Deployer collateral locked in the escrow vault (lamports)
+    pub collateral_amount: u64,
+    /// Escrow duration before refunds open (seconds)
+    pub duration_seconds: i64,
+    /// Optional deployer buy executed in the same instruction (0 = none)
+    pub initial_buy_sol: u64,
+    /// Slippage guard for the initial buy
+    pub min_tokens_out: u64,
+    /// Price shape, validated against the protocol's curve bounds
+    pub curve_kind: CurveKind,
+    /// Total supply in whole tokens (minted as total_supply * 10^decimals)
+    pub total_supply: u64,
+    /// Mint decimals
+    pub decimals: u8,
+    /// Initial virtual SOL reserves (lamports) — sets the starting valuation
+    pub virtual_sol: u64,
+    /// Share of supply sold on the curve in basis points (rest reserved for DEX liquidity)
+    pub curve_token_bps: u64,
+    /// Scheduled trading open time (0 = open immediately). With a presale or
+    /// batch opening this is when that phase starts; public trading opens
+    /// `presale_seconds` / `opening_seconds` later. The escrow deadline, launch
+    /// protection and sniper tax all count from the public open.
+    pub trading_opens_at: i64,
+    /// Merkle root of the presale allowlist (all zeros = no presale)
+    pub presale_root: [u8; 32],
+    /// Presale length in seconds (0 when there is no presale)
+    pub presale_seconds: i64,
+    /// Batch-auction commit window in seconds (0 = no batch opening).
+    /// Cannot be combined with a presale or an initial buy.
+    pub opening_seconds: i64,
+    /// Per-wallet holding cap in basis points of total supply while on the
+    /// curve (0 = no cap). Enforced on buys; lifted at graduation.
+    pub max_wallet_bps: u64,
+    /// How refunds are split if the launch fails or the escrow is slashed
+    pub refund_mode: RefundMode,
+}
+
+/// Launch a token atomically: SPL mint + Metaplex metadata, bonding curve,
+/// curve ATA holding the full supply, escrow vault with deployer collateral,
+/// and an optional initial deployer buy.
+///
+/// Everything succeeds or fails together, so a curve can never be tradable
+/// without its collateral locked.
+pub fn launch_token(ctx: Context<LaunchToken>, args: LaunchTokenArgs) -> Result<()> {
+    let LaunchTokenArgs {
+        name,
+        symbol,
+        uri,
+        collateral_amount,
+        duration_seconds,
+        initial_buy_sol,
+        min_tokens_out,
+        curve_kind,
+        total_supply,
+        decimals,
+        virtual_sol,
+        curve_token_bps,
+        trading_opens_at,
+        presale_root,
+        presale_seconds,
+        opening_seconds,
+        max_wallet_bps,
+        refund_mode,
+    } = args;
+
+    // Validate metadata lengths (bytes, not chars — UTF-8 multibyte safe)
+    require!(name.len() <= 64, FyrstError::InvalidMetadata);
+    require!(symbol.len() <= 20, FyrstError::InvalidMetadata);
+    require!(uri.len() <= 200, FyrstError::InvalidMetadata);
+    let fee_schedule = ctx.accounts.protocol_config.fee_schedule;
+    require!(
+        collateral_amount >= fee_schedule.min_collateral,
+        FyrstError::InsufficientCollateral
+    );
+    require!(
+        (MIN_DURATION..=MAX_DURATION).contains(&duration_seconds),
+        FyrstError::InvalidDuration
+    );
+    curve_kind.validate(&ctx.accounts.protocol_config.curve_bounds)?;
+
+    // Supply and starting reserves, within the protocol's launch bounds.
+    // Virtual token reserves keep the reference virtual:real ratio.
+    require!(
+        ctx.accounts
+            .protocol_config
+            .launch_bounds
+            .contains(total_supply, decimals, virtual_sol, curve_token_bps),
+        FyrstError::InvalidLaunchParams
+    );
+    let token_total_supply = 10u64
+        .checked_pow(decimals as u32)
+        .and_then(|unit| total_supply.checked_mul(unit))
+        .ok_or(FyrstError::InvalidLaunchParams)?;
+    let real_token_reserves = math::apply_bps(token_total_supply, curve_token_bps)?;
+    let virtual_token_reserves = u64::try_from(
+        (real_token_reserves as u128)
+            .checked_mul(INITIAL_VIRTUAL_TOKEN_RESERVES as u128)
+            .ok_or(FyrstError::MathOverflow)?
+            .checked_div(INITIAL_REAL_TOKEN_RESERVES as u128)
+            .ok_or(FyrstError::MathOverflow)?,
+    )
+    .map_err(|_| FyrstError::InvalidLaunchParams)?;
+    require!(real_token_reserves > 0, FyrstError::InvalidLaunchParams);
+
+    let now = Clock::get()?.unix_timestamp;
+    let phase_starts_at = if trading_opens_at == 0 { now } else { trading_opens_at };
+    require!(
+        phase_starts_at >= now && phase_starts_at - now <= MAX_OPEN_DELAY,
+        FyrstError::InvalidOpenTime
+    );
+    let presale_valid = if presale_root == [0u8; 32] {
+        presale_seconds == 0
+    } else {
+        (1..=MAX_PRESALE_DURATION).contains(&presale_seconds)
+    };
+    require!(presale_valid, FyrstError::InvalidPresale);
+    let opening_valid = opening_seconds == 0
+        || ((1..=MAX_OPENING_DURATION).contains(&opening_seconds)
+            && presale_seconds == 0
+            && initial_buy_sol == 0);
+    require!(opening_valid, FyrstError::InvalidOpening);
+    require!(
+        max_wallet_bps == 0 || (MIN_MAX_WALLET_BPS..=BPS_DENOMINATOR).contains(&max_wallet_bps),
+        FyrstError::InvalidWalletCap
+    );
+    let trading_opens_at = phase_starts_at
+        .checked_add(presale_seconds)
+        .and_then(|t| t.checked_add(opening_seconds))
+        .ok_or(FyrstError::MathOverflow)?;
+
+    // Create token metadata via Metaplex CPI
+    let token_mint_key = ctx.accounts.token_mint.key();
+    let seeds = &[
+        CURVE_SEED,
+        token_mint_key.as_ref(),
+        &[ctx.bumps.bonding_curve],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    let ix = build_create_metadata_v3_ix(
+        ctx.accounts.metadata_account.key(),
+        ctx.accounts.token_mint.key(),
+        ctx.accounts.bonding_curve.key(),
+        ctx.accounts.deployer.key(),
+        ctx.accounts.bonding_curve.key(),
+        name.clone(),
+        symbol.clone(),
+        uri.clone(),
+    );
+
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.metadata_account.to_account_info(),
+            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts.bonding_curve.to_account_info(),
+            ctx.accounts.deployer.to_account_info(),
+            ctx.accounts.bonding_curve.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.rent.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+
+    // Mint entire token supply to curve's ATA
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.curve_token_account.to_account_info(),
+                authority: ctx.accounts.bonding_curve.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        token_total_supply,
+    )?;
+
+    // Charge the deploy fee (after any holder / waiver discount)
+    let discount_bps = deploy_fee_discount_bps(ctx.accounts, now)?;
+    let deploy_fee = math::discounted_fee(fee_schedule.deploy_fee, discount_bps)?;
+    let (deploy_ops_share, deploy_treasury_share) =
+        math::ops_split(deploy_fee, fee_schedule.ops_share_bps)?;
+    for (to, amount) in [
+        (ctx.accounts.ops_wallet.to_account_info(), deploy_ops_share),
+        (ctx.accounts.treasury.to_account_info(), deploy_treasury_share),
+    ] {
+        if amount > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.deployer.to_account_info(),
+                        to,
+                    },
+                ),
+                amount,
+            )?;
+        }
+    }
+
+    // Lock deployer collateral in the escrow PDA
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.deployer.to_account_info(),
+                to: ctx.accounts.escrow_vault.to_account_info(),
+            },
+        ),
+        collateral_amount,
+    )?;
+
+    let escrow = &mut ctx.accounts.escrow_vault;
+    escrow.deployer = ctx.accounts.deployer.key();
+    escrow.token_mint = token_mint_key;
+    escrow.bonding_curve = ctx.accounts.bonding_curve.key();
+    escrow.collateral_amount = collateral_amount;
+    escrow.created_at = now;
+    escrow.deadline_timestamp = trading_opens_at
+        .checked_add(duration_seconds)
+        .ok_or(FyrstError::MathOverflow)?;
+    escrow.released = false;
+    escrow.bump = ctx.bumps.escrow_vault;
+    escrow.collateral_lock = ctx.accounts.protocol_config.collateral_lock;
+
+    let escrow_created = EscrowCreated {
+        escrow_vault: escrow.key(),
+        deployer: escrow.deployer,
+        token_mint: escrow.token_mint,
+        collateral_amount,
+        deadline_timestamp: escrow.deadline_timestamp,
+        collateral_lock: escrow.collateral_lock,
+        timestamp: now,
+    };
+
+    // Initialize bonding curve state
+    let curve = &mut ctx.accounts.bonding_curve;
+    curve.token_mint = token_mint_key;
+    curve.current_supply = 0;
+    curve.virtual_token_reserves = virtual_token_reserves;
+    curve.virtual_sol_reserves = virtual_sol;
+    curve.real_token_reserves = real_token_reserves;
+    curve.real_sol_reserves = 0;
+    curve.token_total_supply = token_total_supply;
+    curve.reserve_balance = 0;
+    curve.graduated = false;
+    curve.deployer = ctx.accounts.deployer.key();
+    curve.total_sol_collected = 0;
+    curve.max_reserve_reached = 0;
+    curve.total_deployer_fees = 0;
+    curve.claimed_deployer_fees = 0;
+    curve.bump = ctx.bumps.bonding_curve;
+    curve.escrow_vault = escrow_created.escrow_vault;
+    curve.collateral_amount = collateral_amount;
+    curve.fee_schedule = fee_schedule;
+    curve.graduation_threshold = ctx.accounts.protocol_config.graduation_threshold;
+    curve.curve_kind = curve_kind;
+    curve.created_at = now;
+    curve.deadline_timestamp = escrow_created.deadline_timestamp;
+    curve.failed = false;
+    curve.launch_protection = ctx.accounts.protocol_config.launch_protection;
+    curve.dump_threshold_bps = ctx.accounts.protocol_config.dump_threshold_bps;
+    curve.sniper_tax = ctx.accounts.protocol_config.sniper_tax;
+    curve.holder_pool = 0;
+    curve.trading_opens_at = trading_opens_at;
+    curve.presale_root = presale_root;
+    curve.presale_starts_at = phase_starts_at;
+    curve.opening_starts_at = if opening_seconds > 0 { phase_starts_at } else { 0 };
+    curve.opening_total_sol = 0;
+    curve.opening_tokens = 0;
+    curve.opening_settled = false;
+    curve.max_wallet_bps = max_wallet_bps;
+    curve.refund_mode = refund_mode;
+    curve.total_net_contributed = 0;
+
+    let curve_initialized = CurveInitialized {
+        token_mint: curve.token_mint,
+        bonding_curve: curve.key(),
+        deployer: curve.deployer,
+        escrow_vault: curve.escrow_vault,
+        collateral_amount: curve.collateral_amount,
+        deploy_fee,
+        deploy_fee_discount_bps: discount_bps,
+        curve_kind: curve.curve_kind,
+        name,
+        symbol,
+        uri,
+        token_total_supply: curve.token_total_supply,
+        decimals,
+        virtual_token_reserves: curve.virtual_token_reserves,
+        virtual_sol_reserves: curve.virtual_sol_reserves,
+        real_token_reserves: curve.real_token_reserves,
+        trading_opens_at,
+        presale_root,
+        presale_starts_at: curve.presale_starts_at,
+        opening_starts_at: curve.opening_starts_at,
+        max_wallet_bps,
+        refund_mode,
+        timestamp: now,
+    };
+
+    // Open the deployer's position on the new curve
+    let position = &mut ctx.accounts.deployer_position;
+    position.bonding_curve = curve_initialized.bonding_curve;
+    position.owner = curve_initialized.deployer;
+    position.bump = ctx.bumps.deployer_position;
+
+    emit_cpi!(curve_initialized);
+    emit_cpi!(escrow_created);
+
+    // Optional initial deployer buy — collateral is already locked above.
+    // Allowed before a scheduled open (it is public in the launch transaction)
+    // but counts against the deployer's launch-protection cap and pays the
+    // full sniper tax.
+    if initial_buy_sol > 0 {
+        let (trade, graduated) = execute_buy(
+            BuyAccounts {
+                buyer: ctx.accounts.deployer.to_account_info(),
+                bonding_curve: &mut ctx.accounts.bonding_curve,
+                position: &mut ctx.accounts.deployer_position,
+                position_bump: ctx.bumps.deployer_position,
+                presale_allocation: None,
+                curve_token_account: ctx.accounts.curve_token_account.to_account_info(),
+                buyer_token_account: ctx.accounts.deployer_token_account.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            initial_buy_sol,
+            min_tokens_out,
+        )?;
+
+        emit_cpi!(trade);
+        if let Some(graduated) = graduated {
+            emit_cpi!(graduated);
+        }
+    }
+
+    Ok(())
+}
+
+/// Best available deploy fee discount: $FYRST holder discount or an
+/// authority-granted waiver, whichever is larger
+fn deploy_fee_discount_bps(accounts: &LaunchToken, now: i64) -> Result<u64> {
+    let deployer = accounts.deployer.key();
+    let mut discount_bps = 0;
+
+    if let Some(holding) = &accounts.deployer_fyrst_account {
+        let holder_discount = accounts.protocol_config.holder_discount;
+        require!(
+            holder_discount.fyrst_mint != Pubkey::default()
+                && holding.mint == holder_discount.fyrst_mint
+                && holding.owner == deployer,
+            FyrstError::InvalidDiscount
+        );
+        if holding.amount >= holder_discount.min_balance {
+            discount_bps = discount_bps.max(holder_discount.discount_bps);
+        }
+    }
+
+    if let Some(waiver) = &accounts.fee_waiver {
+        require!(waiver.deployer == deployer, FyrstError::Unauthorized);
+        if waiver.expires_at == 0 || now < waiver.expires_at {
+            discount_bps = discount_bps.max(waiver.discount_bps);
+        }
+    }
+
+    Ok(discount_bps.min(BPS_DENOMINATOR))
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(args: LaunchTokenArgs)]
+pub struct LaunchToken<'info> {
+    #[account(mut)]
+    pub deployer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = deployer,
+        mint::decimals = args.decimals,
+        mint::authority = bonding_curve,
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = deployer,
+        space = BondingCurve::LEN,
+        seeds = [CURVE_SEED, token_mint.key().as_ref()],
+        bump,
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    /// Curve's token ATA — holds entire token supply for AMM transfers
+    #[account(
+        init,
+        payer = deployer,
+        associated_token::mint = token_mint,
+        associated_token::authority = bonding_curve,
+    )]
+    pub curve_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Escrow vault holding deployer collateral for this launch
+    #[account(
+        init,
+        payer = deployer,
+        space = EscrowVault::LEN,
+        seeds = [ESCROW_SEED, deployer.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub escrow_vault: Box<Account<'info, EscrowVault>>,
+
+    /// Deployer's token ATA — receives the optional initial buy
+    #[account(
+        init,
+        payer = deployer,
+        associated_token::mint = token_mint,
+        associated_token::authority = deployer,
+    )]
+    pub deployer_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Deployer's position on the new curve — tracks launch-window spend
+    #[account(
+        init,
+        payer = deployer,
+        space = Position::LEN,
+        seeds = [POSITION_SEED, bonding_curve.key().as_ref(), deployer.key().as_ref()],
+        bump,
+    )]
+    pub deployer_position: Box<Account<'info, Position>>,
+
+    /// CHECK: Created by Metaplex CPI — validated by the Metaplex program
+    #[account(mut)]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex Token Metadata program
+    #[account(address = TOKEN_METADATA_PROGRAM_ID)]
+    pub metadata_program: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// CHECK: Treasury wallet from protocol config (receives deploy fee share)
+    #[account(
+        mut,
+        address = protocol_config.treasury @ FyrstError::Unauthorized,
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Operations wallet from protocol config (receives deploy fee share)
+    #[account(
+        mut,
+        address = protocol_config.ops_wallet @ FyrstError::Unauthorized,
+    )]
+    pub ops_wallet: UncheckedAccount<'info>,
+
+    /// Optional: deployer's $FYRST token account for the holder discount
+    pub deployer_fyrst_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Optional: authority-granted deploy fee waiver for this deployer
+    #[account(
+        seeds = [FEE_WAIVER_SEED, deployer.key().as_ref()],
+        bump = fee_waiver.bump,
+    )]
+    pub fee_waiver: Option<Account<'info, DeployFeeWaiver>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
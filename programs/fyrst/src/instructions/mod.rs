@@ -1,11 +1,19 @@
+pub mod launch;
 pub mod escrow;
 pub mod bonding_curve;
 pub mod refund;
 pub mod protocol;
 pub mod graduation;
+pub mod quote;
+pub mod opening;
+pub mod snapshot;
 
+pub use launch::*;
 pub use escrow::*;
 pub use bonding_curve::*;
 pub use refund::*;
 pub use protocol::*;
 pub use graduation::*;
+pub use quote::*;
+pub use opening::*;
+pub use snapshot::*;
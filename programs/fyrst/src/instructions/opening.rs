@@ -0,0 +1,273 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::{BondingCurve, OpeningCommit, Position};
+use crate::errors::FyrstError;
+use crate::math;
+use crate::events::{OpeningClaimed, OpeningCommitted, OpeningSettled};
+use crate::constants::*;
+use super::bonding_curve::record_buy;
+
+/// Commit SOL to a curve's batch-auction opening (between `opening_starts_at`
+/// and `trading_opens_at`). The SOL is held on the curve PDA, outside the
+/// reserve, until `settle_opening`; the commit PDA records the committer's
+/// running total. Rejected once the combined commits could no longer be
+/// filled by the curve in a single buy.
+pub fn commit_opening(ctx: Context<CommitOpening>, sol_amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let curve = &ctx.accounts.bonding_curve;
+    require!(curve.opening_active(now), FyrstError::OpeningNotActive);
+    require!(sol_amount > 0, FyrstError::InsufficientFunds);
+
+    // Settlement must be able to fill every commit at one price
+    let opening_total_sol = curve
+        .opening_total_sol
+        .checked_add(sol_amount)
+        .ok_or(FyrstError::MathOverflow)?;
+    math::quote_buy_untaxed(curve, opening_total_sol)?;
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.committer.to_account_info(),
+                to: ctx.accounts.bonding_curve.to_account_info(),
+            },
+        ),
+        sol_amount,
+    )?;
+
+    // First commit: open the committer's commit PDA
+    let commit = &mut ctx.accounts.commit;
+    if commit.owner == Pubkey::default() {
+        commit.bonding_curve = ctx.accounts.bonding_curve.key();
+        commit.owner = ctx.accounts.committer.key();
+        commit.bump = ctx.bumps.commit;
+    }
+    commit.sol_committed = commit
+        .sol_committed
+        .checked_add(sol_amount)
+        .ok_or(FyrstError::MathOverflow)?;
+
+    let curve = &mut ctx.accounts.bonding_curve;
+    curve.opening_total_sol = opening_total_sol;
+
+    let committed = OpeningCommitted {
+        token_mint: curve.token_mint,
+        committer: commit.owner,
+        sol_amount,
+        sol_committed: commit.sol_committed,
+        opening_total_sol,
+        timestamp: now,
+    };
+    emit_cpi!(committed);
+
+    Ok(())
+}
+
+/// Settle a batch opening once its commit window has closed (permissionless).
+/// All committed SOL is priced as one buy against the curve, so every
+/// committer pays the same uniform price and no sniper tax applies.
+/// Public trading opens once the opening is settled. Still allowed after the
+/// launch has failed, so committers can claim their tokens and refund them.
+pub fn settle_opening(ctx: Context<SettleOpening>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let curve = &mut ctx.accounts.bonding_curve;
+    require!(curve.has_batch_opening(), FyrstError::OpeningNotActive);
+    require!(!curve.opening_settled, FyrstError::OpeningAlreadySettled);
+    require!(now >= curve.trading_opens_at, FyrstError::DeadlineNotReached);
+
+    curve.opening_settled = true;
+
+    let mut events = None;
+    if curve.opening_total_sol > 0 {
+        let quote = math::quote_buy_untaxed(curve, curve.opening_total_sol)?;
+        let trader = curve.key();
+        events = Some(record_buy(curve, trader, &quote, now)?);
+        curve.opening_tokens = quote.tokens;
+        // Committed SOL is in the reserve from here on, so it counts toward
+        // contribution refunds now; positions are credited as commits are claimed
+        curve.total_net_contributed = curve
+            .total_net_contributed
+            .checked_add(curve.opening_total_sol)
+            .ok_or(FyrstError::MathOverflow)?;
+    }
+
+    let settled = OpeningSettled {
+        token_mint: curve.token_mint,
+        bonding_curve: curve.key(),
+        total_sol: curve.opening_total_sol,
+        tokens: curve.opening_tokens,
+        timestamp: now,
+    };
+    emit_cpi!(settled);
+    if let Some((trade, graduated)) = events {
+        emit_cpi!(trade);
+        if let Some(graduated) = graduated {
+            emit_cpi!(graduated);
+        }
+    }
+
+    Ok(())
+}
+
+/// Claim batch-opening tokens pro-rata to the SOL committed:
+/// tokens = opening_tokens × sol_committed / opening_total_sol.
+/// Both totals shrink as claims are paid, so the last claimer receives the
+/// rounding remainder. Closes the commit PDA, returning its rent, and credits
+/// the committed SOL and claimed tokens to the committer's position. Claims are not held to
+/// `max_wallet_bps` — commits were final once the window closed.
+pub fn claim_opening(ctx: Context<ClaimOpening>) -> Result<()> {
+    let curve = &ctx.accounts.bonding_curve;
+    require!(curve.opening_settled, FyrstError::OpeningNotSettled);
+
+    let sol_committed = ctx.accounts.commit.sol_committed;
+    let tokens = math::opening_claim(sol_committed, curve.opening_total_sol, curve.opening_tokens)?;
+
+    let token_mint_key = curve.token_mint;
+    let seeds = &[
+        CURVE_SEED,
+        token_mint_key.as_ref(),
+        &[curve.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.curve_token_account.to_account_info(),
+                to: ctx.accounts.committer_token_account.to_account_info(),
+                authority: ctx.accounts.bonding_curve.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        tokens,
+    )?;
+
+    let curve = &mut ctx.accounts.bonding_curve;
+    curve.opening_tokens = curve
+        .opening_tokens
+        .checked_sub(tokens)
+        .ok_or(FyrstError::MathOverflow)?;
+    curve.opening_total_sol = curve
+        .opening_total_sol
+        .checked_sub(sol_committed)
+        .ok_or(FyrstError::MathOverflow)?;
+    if ctx.accounts.committer.key() == curve.deployer {
+        curve.deployer_tokens_bought = curve
+            .deployer_tokens_bought
+            .checked_add(tokens)
+            .ok_or(FyrstError::MathOverflow)?;
+    }
+
+    // The curve-wide total was credited with every commit at settlement
+    let position = &mut ctx.accounts.position;
+    position.open(curve.key(), ctx.accounts.committer.key(), ctx.bumps.position);
+    position.credit(sol_committed, tokens)?;
+
+    let claimed = OpeningClaimed {
+        token_mint: token_mint_key,
+        committer: ctx.accounts.committer.key(),
+        sol_committed,
+        tokens,
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+    emit_cpi!(claimed);
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CommitOpening<'info> {
+    #[account(mut)]
+    pub committer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CURVE_SEED, bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        init_if_needed,
+        payer = committer,
+        space = OpeningCommit::LEN,
+        seeds = [COMMIT_SEED, bonding_curve.key().as_ref(), committer.key().as_ref()],
+        bump,
+    )]
+    pub commit: Account<'info, OpeningCommit>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SettleOpening<'info> {
+    #[account(
+        mut,
+        seeds = [CURVE_SEED, bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimOpening<'info> {
+    #[account(mut)]
+    pub committer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CURVE_SEED, bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        address = bonding_curve.token_mint @ FyrstError::TokenMintMismatch,
+    )]
+    pub token_mint: Account<'info, Mint>,
+
+    /// Curve's token ATA — holds cleared tokens until claimed
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = bonding_curve,
+    )]
+    pub curve_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = committer,
+        associated_token::mint = token_mint,
+        associated_token::authority = committer,
+    )]
+    pub committer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [COMMIT_SEED, bonding_curve.key().as_ref(), committer.key().as_ref()],
+        bump = commit.bump,
+        close = committer,
+    )]
+    pub commit: Account<'info, OpeningCommit>,
+
+    /// Committer's position on this curve — credited with the committed SOL
+    #[account(
+        init_if_needed,
+        payer = committer,
+        space = Position::LEN,
+        seeds = [POSITION_SEED, bonding_curve.key().as_ref(), committer.key().as_ref()],
+        bump,
+    )]
+    pub position: Account<'info, Position>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
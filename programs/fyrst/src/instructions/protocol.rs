@@ -1,8 +1,35 @@
 use anchor_lang::prelude::*;
-use crate::state::{ProtocolConfig, BondingCurve};
+use crate::state::{
+    BondingCurve, CollateralLock, CurveBounds, DeployFeeWaiver, FeeSchedule, HolderDiscount,
+    LaunchBounds, LaunchProtection, ProtocolConfig, SniperTax,
+};
 use crate::errors::FyrstError;
+use crate::math;
+use crate::events::{
+    ConfigClosed, ConfigUpdated, CurveThresholdUpdated, DeployFeeWaiverSet, FeesClaimed, Graduated,
+    ProtocolFeesSwept,
+};
 use crate::constants::*;
 
+/// Snapshot of the full protocol config for `ConfigUpdated`
+fn config_updated(config: &ProtocolConfig) -> Result<ConfigUpdated> {
+    Ok(ConfigUpdated {
+        authority: config.authority,
+        treasury: config.treasury,
+        ops_wallet: config.ops_wallet,
+        graduation_threshold: config.graduation_threshold,
+        fee_schedule: config.fee_schedule,
+        holder_discount: config.holder_discount,
+        curve_bounds: config.curve_bounds,
+        launch_bounds: config.launch_bounds,
+        launch_protection: config.launch_protection,
+        sniper_tax: config.sniper_tax,
+        collateral_lock: config.collateral_lock,
+        dump_threshold_bps: config.dump_threshold_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    })
+}
+
 /// Initialize protocol configuration (one-time setup)
 pub fn init_protocol(
     ctx: Context<InitProtocol>,
@@ -15,14 +42,16 @@ pub fn init_protocol(
     config.ops_wallet = ops_wallet;
     config.graduation_threshold = GRADUATION_THRESHOLD;
     config.bump = ctx.bumps.protocol_config;
+    config.fee_schedule = FeeSchedule::default();
+    config.curve_bounds = CurveBounds::default();
+    config.launch_bounds = LaunchBounds::default();
+    config.launch_protection = LaunchProtection::default();
+    config.sniper_tax = SniperTax::default();
+    config.collateral_lock = CollateralLock::default();
+    config.dump_threshold_bps = DUMP_THRESHOLD_BPS;
 
-    msg!(
-        "Protocol initialized: authority={}, treasury={}, ops_wallet={}, threshold={}",
-        config.authority,
-        config.treasury,
-        config.ops_wallet,
-        config.graduation_threshold
-    );
+    let updated = config_updated(config)?;
+    emit_cpi!(updated);
 
     Ok(())
 }
@@ -32,7 +61,8 @@ pub fn update_treasury(ctx: Context<UpdateTreasury>, new_treasury: Pubkey) -> Re
     let config = &mut ctx.accounts.protocol_config;
     config.treasury = new_treasury;
 
-    msg!("Treasury updated to: {}", new_treasury);
+    let updated = config_updated(config)?;
+    emit_cpi!(updated);
     Ok(())
 }
 
@@ -41,26 +71,65 @@ pub fn update_ops_wallet(ctx: Context<UpdateTreasury>, new_ops_wallet: Pubkey) -
     let config = &mut ctx.accounts.protocol_config;
     config.ops_wallet = new_ops_wallet;
 
-    msg!("Ops wallet updated to: {}", new_ops_wallet);
+    let updated = config_updated(config)?;
+    emit_cpi!(updated);
     Ok(())
 }
 
-/// Claim accumulated trade fees with progressive unlock (deployer only)
-/// unlocked = (total_deployer_fees * max_reserve_reached) / GRADUATION_THRESHOLD
-/// claimable = unlocked - claimed_deployer_fees
-pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
-    let curve = &ctx.accounts.bonding_curve;
-    let threshold = GRADUATION_THRESHOLD as u128;
+/// Sweep protocol fees accrued on bonding curves to the treasury and ops wallet
+/// (permissionless). Curves are passed as writable `remaining_accounts`, so
+/// trades never need to write-lock the global fee wallets.
+pub fn sweep_protocol_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SweepProtocolFees<'info>>,
+) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let treasury_info = ctx.accounts.treasury.to_account_info();
+    let ops_info = ctx.accounts.ops_wallet.to_account_info();
+
+    for curve_info in ctx.remaining_accounts.iter() {
+        require!(curve_info.is_writable, FyrstError::InvalidAccount);
+        let mut curve = Account::<BondingCurve>::try_from(curve_info)?;
+        let expected = Pubkey::create_program_address(
+            &[CURVE_SEED, curve.token_mint.as_ref(), &[curve.bump]],
+            &crate::ID,
+        )
+        .map_err(|_| FyrstError::InvalidAccount)?;
+        require_keys_eq!(curve_info.key(), expected, FyrstError::InvalidAccount);
+
+        let ops_amount = curve.accrued_ops_fees;
+        let treasury_amount = curve.accrued_treasury_fees;
+        if ops_amount == 0 && treasury_amount == 0 {
+            continue;
+        }
+
+        let total = ops_amount.checked_add(treasury_amount).ok_or(FyrstError::MathOverflow)?;
+        let new_balance = curve_info.lamports().checked_sub(total).ok_or(FyrstError::MathOverflow)?;
+        **curve_info.try_borrow_mut_lamports()? = new_balance;
+        **ops_info.try_borrow_mut_lamports()? += ops_amount;
+        **treasury_info.try_borrow_mut_lamports()? += treasury_amount;
+
+        curve.accrued_ops_fees = 0;
+        curve.accrued_treasury_fees = 0;
+        curve.exit(&crate::ID)?;
+
+        emit_cpi!(ProtocolFeesSwept {
+            token_mint: curve.token_mint,
+            bonding_curve: curve_info.key(),
+            ops_amount,
+            treasury_amount,
+            timestamp,
+        });
+    }
 
-    let unlocked = (curve.total_deployer_fees as u128)
-        .checked_mul(curve.max_reserve_reached as u128)
-        .ok_or(FyrstError::MathOverflow)?
-        .checked_div(threshold)
-        .ok_or(FyrstError::MathOverflow)? as u64;
+    Ok(())
+}
 
-    let claimable = unlocked
-        .checked_sub(curve.claimed_deployer_fees)
-        .ok_or(FyrstError::MathOverflow)?;
+/// Claim accumulated trade fees with progressive unlock (deployer only)
+/// unlocked = (total_deployer_fees * max_reserve_reached) / curve.graduation_threshold
+/// claimable = unlocked - claimed_deployer_fees (zero, not an error, while a
+/// raised threshold keeps unlocked below claimed)
+pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
+    let claimable = math::claimable_deployer_fees(&ctx.accounts.bonding_curve)?;
 
     require!(claimable > 0, FyrstError::NoFeesToClaim);
 
@@ -74,40 +143,232 @@ pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
         .checked_add(claimable)
         .ok_or(FyrstError::MathOverflow)?;
 
-    msg!("Fees claimed: deployer={}, amount={}", ctx.accounts.deployer.key(), claimable);
+    let claimed = FeesClaimed {
+        token_mint: curve_mut.token_mint,
+        deployer: ctx.accounts.deployer.key(),
+        amount: claimable,
+        total_claimed: curve_mut.claimed_deployer_fees,
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+    emit_cpi!(claimed);
 
     Ok(())
 }
 
-/// Update graduation threshold (authority only)
+/// Update graduation threshold for future launches (authority only).
+/// Live curves keep the threshold they snapshotted at launch.
 pub fn update_graduation_threshold(
     ctx: Context<UpdateTreasury>,
     new_threshold: u64,
 ) -> Result<()> {
+    require!(new_threshold > 0, FyrstError::InvalidThreshold);
+
     let config = &mut ctx.accounts.protocol_config;
     config.graduation_threshold = new_threshold;
-    msg!("Graduation threshold updated to: {}", new_threshold);
+
+    let updated = config_updated(config)?;
+    emit_cpi!(updated);
+    Ok(())
+}
+
+/// Update the fee schedule for future launches (authority only).
+/// Live curves keep the schedule they snapshotted at launch.
+pub fn update_fee_schedule(
+    ctx: Context<UpdateTreasury>,
+    new_schedule: FeeSchedule,
+) -> Result<()> {
+    let config = &mut ctx.accounts.protocol_config;
+    new_schedule.validate(&config.sniper_tax)?;
+    config.fee_schedule = new_schedule;
+
+    let updated = config_updated(config)?;
+    emit_cpi!(updated);
+    Ok(())
+}
+
+/// Update the parameter bounds for deployer-chosen curve shapes (authority only).
+/// Only affects future launches — live curves keep their shape.
+pub fn update_curve_bounds(ctx: Context<UpdateTreasury>, new_bounds: CurveBounds) -> Result<()> {
+    new_bounds.validate()?;
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.curve_bounds = new_bounds;
+
+    let updated = config_updated(config)?;
+    emit_cpi!(updated);
+    Ok(())
+}
+
+/// Update the bounds for deployer-chosen supply and starting reserves
+/// (authority only). Only affects future launches.
+pub fn update_launch_bounds(ctx: Context<UpdateTreasury>, new_bounds: LaunchBounds) -> Result<()> {
+    new_bounds.validate()?;
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.launch_bounds = new_bounds;
+
+    let updated = config_updated(config)?;
+    emit_cpi!(updated);
     Ok(())
 }
 
-/// Graduate a bonding curve when reserve meets threshold
+/// Update the launch-protection window and per-wallet cap (authority only).
+/// Only affects future launches.
+pub fn update_launch_protection(
+    ctx: Context<UpdateTreasury>,
+    new_protection: LaunchProtection,
+) -> Result<()> {
+    new_protection.validate()?;
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.launch_protection = new_protection;
+
+    let updated = config_updated(config)?;
+    emit_cpi!(updated);
+    Ok(())
+}
+
+/// Update the sniper tax on early buys (authority only; start_fee_bps = 0
+/// disables it). Only affects future launches.
+pub fn update_sniper_tax(ctx: Context<UpdateTreasury>, new_tax: SniperTax) -> Result<()> {
+    new_tax.validate()?;
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.fee_schedule.validate(&new_tax)?;
+    config.sniper_tax = new_tax;
+
+    let updated = config_updated(config)?;
+    emit_cpi!(updated);
+    Ok(())
+}
+
+/// Update the post-graduation collateral lock (authority only).
+/// Only affects future launches; live escrows keep their snapshot.
+pub fn update_collateral_lock(ctx: Context<UpdateTreasury>, new_lock: CollateralLock) -> Result<()> {
+    new_lock.validate()?;
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.collateral_lock = new_lock;
+
+    let updated = config_updated(config)?;
+    emit_cpi!(updated);
+    Ok(())
+}
+
+/// Update the share of their acquired tokens a deployer may sell before the
+/// escrow becomes slashable (authority only). Only affects future launches.
+pub fn update_dump_threshold(ctx: Context<UpdateTreasury>, new_threshold_bps: u64) -> Result<()> {
+    require!(new_threshold_bps <= BPS_DENOMINATOR, FyrstError::InvalidDumpThreshold);
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.dump_threshold_bps = new_threshold_bps;
+
+    let updated = config_updated(config)?;
+    emit_cpi!(updated);
+    Ok(())
+}
+
+/// Update the $FYRST holder deploy fee discount (authority only)
+pub fn update_holder_discount(
+    ctx: Context<UpdateTreasury>,
+    new_discount: HolderDiscount,
+) -> Result<()> {
+    require!(
+        new_discount.discount_bps <= BPS_DENOMINATOR,
+        FyrstError::InvalidDiscount
+    );
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.holder_discount = new_discount;
+
+    let updated = config_updated(config)?;
+    emit_cpi!(updated);
+    Ok(())
+}
+
+/// Grant, change or revoke (discount_bps = 0) a deployer's deploy fee waiver
+/// (authority only)
+pub fn set_deploy_fee_waiver(
+    ctx: Context<SetDeployFeeWaiver>,
+    deployer: Pubkey,
+    discount_bps: u64,
+    expires_at: i64,
+) -> Result<()> {
+    require!(discount_bps <= BPS_DENOMINATOR, FyrstError::InvalidDiscount);
+
+    let waiver = &mut ctx.accounts.fee_waiver;
+    waiver.deployer = deployer;
+    waiver.discount_bps = discount_bps;
+    waiver.expires_at = expires_at;
+    waiver.bump = ctx.bumps.fee_waiver;
+
+    emit_cpi!(DeployFeeWaiverSet {
+        deployer,
+        discount_bps,
+        expires_at,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Override one live curve's graduation threshold (authority only), up to
+/// the reserve a full sell-out would hold. Explicit migration path for the
+/// per-curve snapshot; emits `CurveThresholdUpdated` so every change is
+/// auditable on-chain.
+pub fn set_curve_graduation_threshold(
+    ctx: Context<SetCurveGraduationThreshold>,
+    new_threshold: u64,
+) -> Result<()> {
+    let curve = &mut ctx.accounts.bonding_curve;
+    require!(!curve.graduated, FyrstError::AlreadyGraduated);
+    require!(
+        new_threshold > 0 && new_threshold <= math::sell_out_reserve(curve)?,
+        FyrstError::InvalidThreshold
+    );
+
+    let old_threshold = curve.graduation_threshold;
+    curve.graduation_threshold = new_threshold;
+    // Keep the fee-unlock ratio ≤ 100% under a lowered threshold
+    curve.max_reserve_reached = curve.max_reserve_reached.min(new_threshold);
+
+    let updated = CurveThresholdUpdated {
+        token_mint: curve.token_mint,
+        bonding_curve: curve.key(),
+        authority: ctx.accounts.authority.key(),
+        old_threshold,
+        new_threshold,
+        max_reserve_reached: curve.max_reserve_reached,
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+    emit_cpi!(updated);
+    Ok(())
+}
+
+/// Graduate a bonding curve when reserve meets its threshold
 pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
     let curve = &mut ctx.accounts.bonding_curve;
-    let config = &ctx.accounts.protocol_config;
+    let now = Clock::get()?.unix_timestamp;
 
     require!(!curve.graduated, FyrstError::AlreadyGraduated);
+    require!(!curve.slashed, FyrstError::LaunchSlashed);
+    require!(!curve.has_failed(now), FyrstError::LaunchFailed);
     require!(
-        curve.reserve_balance >= config.graduation_threshold,
+        curve.reserve_balance >= curve.graduation_threshold,
         FyrstError::InsufficientFunds
     );
 
     curve.graduated = true;
-
-    msg!(
-        "Token graduated: mint={}, reserve={}",
-        curve.token_mint,
-        curve.reserve_balance
-    );
+    curve.graduated_at = now;
+
+    let graduated = Graduated {
+        token_mint: curve.token_mint,
+        bonding_curve: curve.key(),
+        reserve_balance: curve.reserve_balance,
+        real_token_reserves: curve.real_token_reserves,
+        current_supply: curve.current_supply,
+        timestamp: now,
+    };
+    emit_cpi!(graduated);
 
     Ok(())
 }
@@ -116,17 +377,23 @@ pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
 pub fn close_config(ctx: Context<CloseConfig>) -> Result<()> {
     let config_info = ctx.accounts.protocol_config.to_account_info();
     let authority_info = ctx.accounts.authority.to_account_info();
+    let lamports = config_info.lamports();
 
-    **authority_info.try_borrow_mut_lamports()? += config_info.lamports();
+    **authority_info.try_borrow_mut_lamports()? += lamports;
     **config_info.try_borrow_mut_lamports()? = 0;
 
     config_info.assign(&anchor_lang::solana_program::system_program::ID);
-    config_info.realloc(0, false)?;
+    config_info.resize(0)?;
 
-    msg!("Protocol config closed for migration");
+    emit_cpi!(ConfigClosed {
+        authority: authority_info.key(),
+        lamports,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CloseConfig<'info> {
     #[account(mut)]
@@ -141,6 +408,7 @@ pub struct CloseConfig<'info> {
     pub protocol_config: UncheckedAccount<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitProtocol<'info> {
     #[account(mut)]
@@ -158,6 +426,7 @@ pub struct InitProtocol<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct UpdateTreasury<'info> {
     #[account(
@@ -173,6 +442,81 @@ pub struct UpdateTreasury<'info> {
     pub protocol_config: Account<'info, ProtocolConfig>,
 }
 
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(deployer: Pubkey)]
+pub struct SetDeployFeeWaiver<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == protocol_config.authority @ FyrstError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = DeployFeeWaiver::LEN,
+        seeds = [FEE_WAIVER_SEED, deployer.as_ref()],
+        bump,
+    )]
+    pub fee_waiver: Account<'info, DeployFeeWaiver>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCurveGraduationThreshold<'info> {
+    #[account(
+        constraint = authority.key() == protocol_config.authority @ FyrstError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [CURVE_SEED, bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SweepProtocolFees<'info> {
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// CHECK: Treasury wallet from protocol config (buyback+burn)
+    #[account(
+        mut,
+        address = protocol_config.treasury @ FyrstError::Unauthorized,
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Operations wallet from protocol config (service revenue)
+    #[account(
+        mut,
+        address = protocol_config.ops_wallet @ FyrstError::Unauthorized,
+    )]
+    pub ops_wallet: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ClaimFees<'info> {
     #[account(
@@ -189,6 +533,7 @@ pub struct ClaimFees<'info> {
     pub bonding_curve: Account<'info, BondingCurve>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Graduate<'info> {
     pub caller: Signer<'info>,
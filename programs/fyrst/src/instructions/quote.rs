@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use crate::state::{BondingCurve, EscrowVault, Position};
+use crate::errors::FyrstError;
+use crate::math::{self, BuyQuote, SellQuote};
+use crate::constants::*;
+
+/// Result of `quote_refund`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RefundQuote {
+    /// Tokens that would be burned
+    pub tokens: u64,
+    /// Lamports the holder would receive
+    pub sol_refunded: u64,
+    /// Whether `process_refund` would accept the claim right now
+    pub available: bool,
+}
+
+/// Quote an exact-SOL-in buy with the program's own pricing (read-only).
+/// Returned via `set_return_data` — simulate the transaction to read it.
+pub fn quote_buy(ctx: Context<QuoteCurve>, sol_amount: u64) -> Result<BuyQuote> {
+    let now = Clock::get()?.unix_timestamp;
+    math::quote_buy(&ctx.accounts.bonding_curve, sol_amount, now)
+}
+
+/// Quote an exact-tokens-in sell with the program's own pricing (read-only)
+pub fn quote_sell(ctx: Context<QuoteCurve>, token_amount: u64) -> Result<SellQuote> {
+    math::quote_sell(&ctx.accounts.bonding_curve, token_amount)
+}
+
+/// Quote the burn-to-refund payout for `token_amount` tokens (read-only).
+/// In `Contribution` refund mode pass the holder's position; the net SOL it
+/// contributed toward `token_amount` of its curve-acquired tokens sets the payout.
+pub fn quote_refund(ctx: Context<QuoteRefund>, token_amount: u64) -> Result<RefundQuote> {
+    let curve = &ctx.accounts.bonding_curve;
+    let escrow = &ctx.accounts.escrow_vault;
+    let now = Clock::get()?.unix_timestamp;
+
+    let contribution = match ctx.accounts.position.as_ref() {
+        Some(position) => position.contribution_for(token_amount)?,
+        None => 0,
+    };
+    let (share, total) = math::refund_weight(curve, token_amount, contribution);
+    let escrow_info = escrow.to_account_info();
+    let escrow_rent = Rent::get()?.minimum_balance(escrow_info.data_len());
+    let escrow_lamports = escrow_info.lamports().saturating_sub(escrow_rent);
+    let sol_refunded = math::refund_payout(curve, share, total, escrow_lamports)?.total()?;
+
+    Ok(RefundQuote {
+        tokens: token_amount,
+        sol_refunded,
+        available: curve.refunds_open(now)
+            && escrow.refund_snapshot == Pubkey::default()
+            && sol_refunded > 0,
+    })
+}
+
+/// Quote the deployer fees `claim_fees` would pay out right now (read-only)
+pub fn quote_claimable_fees(ctx: Context<QuoteCurve>) -> Result<u64> {
+    math::claimable_deployer_fees(&ctx.accounts.bonding_curve)
+}
+
+#[derive(Accounts)]
+pub struct QuoteCurve<'info> {
+    #[account(
+        seeds = [CURVE_SEED, bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteRefund<'info> {
+    #[account(
+        seeds = [CURVE_SEED, bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = escrow_vault @ FyrstError::EscrowMismatch,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        seeds = [ESCROW_SEED, bonding_curve.deployer.as_ref(), bonding_curve.token_mint.as_ref()],
+        bump = escrow_vault.bump,
+        has_one = bonding_curve @ FyrstError::EscrowMismatch,
+    )]
+    pub escrow_vault: Account<'info, EscrowVault>,
+
+    /// Optional: the holder's position (used in `Contribution` refund mode)
+    #[account(has_one = bonding_curve)]
+    pub position: Option<Account<'info, Position>>,
+}
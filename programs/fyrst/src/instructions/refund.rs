@@ -1,88 +1,173 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
-use crate::state::{EscrowVault, BondingCurve};
+use crate::state::{EscrowVault, BondingCurve, Position, RefundMode};
 use crate::errors::FyrstError;
+use crate::math;
+use crate::events::RefundProcessed;
 use crate::constants::*;
 
 /// Process burn-to-refund: buyer burns their SPL tokens and receives
-/// a pro-rata share of the escrow lamports.
+/// a pro-rata share of the escrow lamports, of the SOL left in the curve
+/// reserve, of the curve's holder pool (sniper tax collected on early buys)
+/// and of any slashed collateral, so a failed launch unwinds fully.
 ///
-/// refund = (buyer_tokens / current_supply) × escrow_remaining_lamports
+/// refund = weight × (escrow_remaining_lamports + reserve_balance + holder_pool + slashed_collateral)
 ///
-/// Conditions: token NOT graduated AND deadline passed AND buyer holds tokens.
+/// where weight is buyer_tokens / current_supply in `TokenShare` mode, or the
+/// position's net_sol_contributed / total_net_contributed in `Contribution`
+/// mode, scaled by the share of the position's curve-acquired tokens still
+/// held and burned (which zeroes the position's contribution).
+///
+/// Conditions: buyer holds tokens (or, in `Contribution` mode, has a position)
+/// AND either the launch failed (deadline passed without graduation), or the
+/// escrow was slashed (then only slashed collateral is refunded after
+/// graduation, and the deployer cannot claim). Closed once a refund snapshot
+/// is posted — holders are then paid from the snapshot instead.
 pub fn process_refund(ctx: Context<ProcessRefund>) -> Result<()> {
-    let escrow = &ctx.accounts.escrow_vault;
     let curve = &mut ctx.accounts.bonding_curve;
     let buyer_balance = ctx.accounts.buyer_token_account.amount;
 
-    // Must not be graduated AND deadline must have passed
     let now = Clock::get()?.unix_timestamp;
+    require!(curve.refunds_open(now), FyrstError::DeadlineNotReached);
+    // The exclusion keys on the deployer wallet only: tokens the deployer
+    // moved to another wallet before the slash are refunded like any holder's
+    require!(
+        ctx.accounts.escrow_vault.refund_snapshot == Pubkey::default(),
+        FyrstError::RefundSnapshotActive
+    );
     require!(
-        !curve.graduated && now >= escrow.deadline_timestamp,
-        FyrstError::DeadlineNotReached
+        !curve.slashed || ctx.accounts.buyer.key() != curve.deployer,
+        FyrstError::Unauthorized
     );
-    require!(buyer_balance > 0, FyrstError::InsufficientTokens);
+    // Contribution weight counts only what backs the curve-acquired tokens
+    // still in the wallet and burned here, so tokens passed to another wallet
+    // and sold there take their contribution's refund with them
+    let (contribution, clears_contribution) = match curve.refund_mode {
+        RefundMode::TokenShare => {
+            require!(buyer_balance > 0, FyrstError::InsufficientTokens);
+            (0, false)
+        }
+        RefundMode::Contribution => {
+            let position = ctx.accounts.position.as_ref().ok_or(FyrstError::PositionRequired)?;
+            (position.contribution_for(buyer_balance)?, position.net_sol_contributed > 0)
+        }
+    };
 
-    // refund = (buyer_tokens / current_supply) × escrow remaining lamports
-    let escrow_lamports = ctx.accounts.escrow_vault.to_account_info().lamports();
-    let refund_amount = (buyer_balance as u128)
-        .checked_mul(escrow_lamports as u128)
-        .ok_or(FyrstError::MathOverflow)?
-        .checked_div(curve.current_supply as u128)
-        .ok_or(FyrstError::MathOverflow)? as u64;
+    // Only the escrow balance above rent is refundable; the rent stays until
+    // the escrow is closed
+    let escrow_info = ctx.accounts.escrow_vault.to_account_info();
+    let escrow_rent = Rent::get()?.minimum_balance(escrow_info.data_len());
+    let escrow_lamports = escrow_info.lamports().saturating_sub(escrow_rent);
+    let (share, total) = math::refund_weight(curve, buyer_balance, contribution);
+    let payout = math::refund_payout(curve, share, total, escrow_lamports)?;
+    let refund_amount = payout.total()?;
 
-    require!(refund_amount > 0, FyrstError::InsufficientFunds);
+    // A position whose tokens have left its wallet may refund nothing, but
+    // still clears its contribution from the total
+    require!(refund_amount > 0 || clears_contribution, FyrstError::InsufficientFunds);
 
     // Burn buyer's SPL tokens
-    token::burn(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Burn {
-                mint: ctx.accounts.token_mint.to_account_info(),
-                from: ctx.accounts.buyer_token_account.to_account_info(),
-                authority: ctx.accounts.buyer.to_account_info(),
-            },
-        ),
-        buyer_balance,
-    )?;
-
-    // Transfer SOL from escrow to buyer
-    **ctx.accounts.escrow_vault.to_account_info().try_borrow_mut_lamports()? -= refund_amount;
-    **ctx.accounts.buyer.to_account_info().try_borrow_mut_lamports()? += refund_amount;
+    if buyer_balance > 0 {
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    from: ctx.accounts.buyer_token_account.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                },
+            ),
+            buyer_balance,
+        )?;
+    }
 
-    // Update bonding curve supply (so next refund has correct ratio)
-    curve.current_supply = curve
-        .current_supply
-        .checked_sub(buyer_balance)
+    // Transfer SOL from escrow and the curve's reserve and holder pools to buyer
+    let curve_share = payout
+        .reserve_share
+        .checked_add(payout.pool_share)
+        .and_then(|sum| sum.checked_add(payout.slashed_share))
+        .ok_or(FyrstError::MathOverflow)?;
+    **ctx.accounts.escrow_vault.to_account_info().try_borrow_mut_lamports()? -= payout.escrow_share;
+    **curve.to_account_info().try_borrow_mut_lamports()? -= curve_share;
+    **ctx.accounts.buyer.to_account_info().try_borrow_mut_lamports()? += refund_amount;
+    curve.reserve_balance = curve
+        .reserve_balance
+        .checked_sub(payout.reserve_share)
+        .ok_or(FyrstError::MathOverflow)?;
+    curve.real_sol_reserves = curve
+        .real_sol_reserves
+        .checked_sub(payout.reserve_share)
+        .ok_or(FyrstError::MathOverflow)?;
+    curve.holder_pool = curve
+        .holder_pool
+        .checked_sub(payout.pool_share)
+        .ok_or(FyrstError::MathOverflow)?;
+    curve.slashed_collateral = curve
+        .slashed_collateral
+        .checked_sub(payout.slashed_share)
         .ok_or(FyrstError::MathOverflow)?;
 
-    msg!(
-        "Refund: buyer={}, tokens_burned={}, sol_refunded={}",
-        ctx.accounts.buyer.key(),
-        buyer_balance,
-        refund_amount
-    );
+    // A deployer's refund burn leaves their wallet without being a dump
+    if ctx.accounts.buyer.key() == curve.deployer {
+        curve.deployer_tokens_refunded = curve
+            .deployer_tokens_refunded
+            .checked_add(buyer_balance)
+            .ok_or(FyrstError::MathOverflow)?;
+    }
+
+    // Contribution refunds settle the whole position at once
+    if curve.refund_mode == RefundMode::Contribution {
+        if let Some(position) = ctx.accounts.position.as_mut() {
+            curve.clear_contribution(position)?;
+        }
+    }
+
+    // Update bonding curve supply (so next refund has correct ratio). After
+    // graduation holders may hold DEX-side tokens beyond current_supply.
+    curve.current_supply = if curve.graduated {
+        curve.current_supply.saturating_sub(buyer_balance)
+    } else {
+        curve
+            .current_supply
+            .checked_sub(buyer_balance)
+            .ok_or(FyrstError::MathOverflow)?
+    };
+
+    let refunded = RefundProcessed {
+        token_mint: curve.token_mint,
+        buyer: ctx.accounts.buyer.key(),
+        tokens_burned: buyer_balance,
+        sol_refunded: refund_amount,
+        remaining_supply: curve.current_supply,
+        timestamp: now,
+    };
+    emit_cpi!(refunded);
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ProcessRefund<'info> {
     /// Buyer claiming their own refund (permissionless)
     #[account(mut)]
     pub buyer: Signer<'info>,
 
+    /// The one escrow recorded on the curve at launch
     #[account(
         mut,
-        seeds = [ESCROW_SEED, escrow_vault.deployer.as_ref(), escrow_vault.token_mint.as_ref()],
+        seeds = [ESCROW_SEED, bonding_curve.deployer.as_ref(), bonding_curve.token_mint.as_ref()],
         bump = escrow_vault.bump,
+        has_one = bonding_curve @ FyrstError::EscrowMismatch,
+        constraint = escrow_vault.deployer == bonding_curve.deployer @ FyrstError::EscrowMismatch,
     )]
     pub escrow_vault: Account<'info, EscrowVault>,
 
     #[account(
         mut,
-        seeds = [CURVE_SEED, escrow_vault.token_mint.as_ref()],
+        seeds = [CURVE_SEED, bonding_curve.token_mint.as_ref()],
         bump = bonding_curve.bump,
+        has_one = escrow_vault @ FyrstError::EscrowMismatch,
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
 
@@ -96,6 +181,14 @@ pub struct ProcessRefund<'info> {
     )]
     pub buyer_token_account: Account<'info, TokenAccount>,
 
+    /// Buyer's position on this curve (required in `Contribution` refund mode)
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, bonding_curve.key().as_ref(), buyer.key().as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Option<Account<'info, Position>>,
+
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
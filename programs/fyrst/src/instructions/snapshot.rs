@@ -0,0 +1,390 @@
+use anchor_lang::prelude::*;
+use crate::state::{EscrowVault, BondingCurve, ProtocolConfig, RefundSnapshot};
+use crate::errors::FyrstError;
+use crate::math;
+use crate::merkle;
+use crate::events::{RefundSnapshotClosed, RefundSnapshotPosted, SnapshotRefundPaid};
+use crate::constants::*;
+
+/// One holder's leaf in a refund snapshot, with its Merkle proof
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotClaim {
+    /// Leaf index (bit in the snapshot's claimed bitmap)
+    pub index: u32,
+    /// Holder's refund weight (token balance or net SOL contributed)
+    pub weight: u64,
+    /// Sibling hashes linking indexed_leaf(index, holder, weight) to the root
+    pub proof: Vec<[u8; 32]>,
+}
+
+/// Post a Merkle root of holder refund weights for a failed launch, or for a
+/// slashed escrow after graduation (authority only). Everything refundable
+/// moves into the snapshot PDA — escrow lamports above rent, the curve's
+/// reserve, holder pool and slashed collateral before graduation, slashed
+/// collateral only after — and holders are paid from it by proof, without
+/// burning tokens. Burn-to-refund is closed for the launch from then on.
+/// A batch opening must be settled and fully claimed first, so every token
+/// the snapshot weighs is already in a holder's wallet.
+///
+/// Only system-owned wallets can be paid: a leaf for a program-owned holder
+/// (a PDA, a multisig vault) stays unpaid and its share goes to the treasury
+/// when the snapshot closes. Snapshot builders should leave such holders out
+/// or key their weight to a wallet that can receive SOL.
+///
+/// refund = weight / total_weight × pool_lamports
+pub fn post_refund_snapshot(
+    ctx: Context<PostRefundSnapshot>,
+    root: [u8; 32],
+    leaf_count: u32,
+    total_weight: u64,
+) -> Result<()> {
+    let curve = &mut ctx.accounts.bonding_curve;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        curve.has_failed(now) || (curve.graduated && curve.slashed),
+        FyrstError::DeadlineNotReached
+    );
+    require!(
+        root != [0u8; 32] && leaf_count > 0 && leaf_count <= MAX_SNAPSHOT_LEAVES && total_weight > 0,
+        FyrstError::InvalidSnapshot
+    );
+    require!(
+        !curve.has_batch_opening() || curve.opening_settled,
+        FyrstError::OpeningNotSettled
+    );
+    require!(curve.opening_tokens == 0, FyrstError::OpeningTokensUnclaimed);
+
+    // Escrow lamports above rent are refundable only before graduation; after
+    // it they are the deployer's unlocked collateral
+    let escrow_info = ctx.accounts.escrow_vault.to_account_info();
+    let escrow_share = if curve.graduated {
+        0
+    } else {
+        let rent = Rent::get()?.minimum_balance(escrow_info.data_len());
+        escrow_info.lamports().saturating_sub(rent)
+    };
+    let curve_share = if curve.graduated {
+        curve.slashed_collateral
+    } else {
+        curve
+            .reserve_balance
+            .checked_add(curve.holder_pool)
+            .and_then(|sum| sum.checked_add(curve.slashed_collateral))
+            .ok_or(FyrstError::MathOverflow)?
+    };
+    let pool_lamports = escrow_share.checked_add(curve_share).ok_or(FyrstError::MathOverflow)?;
+    require!(pool_lamports > 0, FyrstError::InsufficientFunds);
+
+    let snapshot_info = ctx.accounts.refund_snapshot.to_account_info();
+    **escrow_info.try_borrow_mut_lamports()? -= escrow_share;
+    **curve.to_account_info().try_borrow_mut_lamports()? -= curve_share;
+    **snapshot_info.try_borrow_mut_lamports()? += pool_lamports;
+    if !curve.graduated {
+        curve.reserve_balance = 0;
+        curve.real_sol_reserves = 0;
+        curve.holder_pool = 0;
+    }
+    curve.slashed_collateral = 0;
+
+    let snapshot = &mut ctx.accounts.refund_snapshot;
+    snapshot.escrow_vault = ctx.accounts.escrow_vault.key();
+    snapshot.bonding_curve = curve.key();
+    snapshot.root = root;
+    snapshot.leaf_count = leaf_count;
+    snapshot.total_weight = total_weight;
+    snapshot.pool_lamports = pool_lamports;
+    snapshot.paid_lamports = 0;
+    snapshot.claimed_weight = 0;
+    snapshot.claimed_count = 0;
+    snapshot.posted_at = now;
+    snapshot.bump = ctx.bumps.refund_snapshot;
+    snapshot.claimed = vec![0u8; RefundSnapshot::bitmap_len(leaf_count)];
+
+    ctx.accounts.escrow_vault.refund_snapshot = snapshot.key();
+
+    let posted = RefundSnapshotPosted {
+        token_mint: curve.token_mint,
+        bonding_curve: curve.key(),
+        refund_snapshot: snapshot.key(),
+        root,
+        leaf_count,
+        total_weight,
+        pool_lamports,
+        timestamp: now,
+    };
+    emit_cpi!(posted);
+
+    Ok(())
+}
+
+/// Pay `holder` for one snapshot leaf. Returns `None` if the leaf was
+/// already paid, else the lamports moved from the snapshot to the holder.
+fn pay_claim<'info>(
+    snapshot: &mut Account<'info, RefundSnapshot>,
+    holder: &AccountInfo<'info>,
+    claim: &SnapshotClaim,
+) -> Result<Option<u64>> {
+    require!(claim.index < snapshot.leaf_count, FyrstError::InvalidSnapshot);
+    if snapshot.is_claimed(claim.index) {
+        return Ok(None);
+    }
+    require!(
+        merkle::verify(&claim.proof, &snapshot.root, merkle::indexed_leaf(claim.index, holder.key, claim.weight)),
+        FyrstError::InvalidProof
+    );
+
+    // Weights over-summing total_weight in a bad root cannot drain past the pool
+    snapshot.claimed_weight = snapshot
+        .claimed_weight
+        .checked_add(claim.weight)
+        .ok_or(FyrstError::MathOverflow)?;
+    require!(snapshot.claimed_weight <= snapshot.total_weight, FyrstError::InvalidSnapshot);
+
+    let amount = math::refund_share(claim.weight, snapshot.total_weight, snapshot.pool_lamports)?;
+    snapshot.paid_lamports = snapshot
+        .paid_lamports
+        .checked_add(amount)
+        .ok_or(FyrstError::MathOverflow)?;
+    snapshot.claimed_count = snapshot.claimed_count.checked_add(1).ok_or(FyrstError::MathOverflow)?;
+    snapshot.set_claimed(claim.index);
+
+    if amount > 0 {
+        **snapshot.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **holder.try_borrow_mut_lamports()? += amount;
+    }
+    Ok(Some(amount))
+}
+
+/// Pay one holder's snapshot refund by Merkle proof (permissionless — the
+/// lamports can only go to the wallet in the leaf, which need not sign)
+pub fn claim_snapshot_refund(ctx: Context<ClaimSnapshotRefund>, claim: SnapshotClaim) -> Result<()> {
+    let holder = ctx.accounts.holder.to_account_info();
+    let amount = pay_claim(&mut ctx.accounts.refund_snapshot, &holder, &claim)?
+        .ok_or(FyrstError::SnapshotAlreadyClaimed)?;
+
+    let paid = SnapshotRefundPaid {
+        token_mint: ctx.accounts.bonding_curve.token_mint,
+        holder: holder.key(),
+        index: claim.index,
+        weight: claim.weight,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+    emit_cpi!(paid);
+
+    Ok(())
+}
+
+/// Push snapshot refunds to a batch of holders (permissionless crank).
+/// `remaining_accounts` lists one writable system-owned holder wallet per
+/// claim, in order. Leaves that were already paid are skipped so overlapping
+/// batches are safe, as are payouts too small to open an empty wallet (those
+/// holders claim once their wallet is funded).
+pub fn push_snapshot_refunds<'info>(
+    ctx: Context<'_, '_, 'info, 'info, PushSnapshotRefunds<'info>>,
+    claims: Vec<SnapshotClaim>,
+) -> Result<()> {
+    require!(
+        ctx.remaining_accounts.len() == claims.len(),
+        FyrstError::HolderAccountsMismatch
+    );
+    let token_mint = ctx.accounts.bonding_curve.token_mint;
+    let now = Clock::get()?.unix_timestamp;
+    let wallet_rent = Rent::get()?.minimum_balance(0);
+
+    for (holder, claim) in ctx.remaining_accounts.iter().zip(claims.iter()) {
+        require!(
+            holder.is_writable && holder.owner == &System::id(),
+            FyrstError::HolderAccountsMismatch
+        );
+        let snapshot = &ctx.accounts.refund_snapshot;
+        let amount = math::refund_share(claim.weight, snapshot.total_weight, snapshot.pool_lamports)?;
+        if holder.lamports() == 0 && amount > 0 && amount < wallet_rent {
+            continue;
+        }
+        let Some(amount) = pay_claim(&mut ctx.accounts.refund_snapshot, holder, claim)? else {
+            continue;
+        };
+
+        let paid = SnapshotRefundPaid {
+            token_mint,
+            holder: holder.key(),
+            index: claim.index,
+            weight: claim.weight,
+            amount,
+            timestamp: now,
+        };
+        emit_cpi!(paid);
+    }
+
+    Ok(())
+}
+
+/// Close a refund snapshot once every leaf is paid or its claim period has
+/// ended (permissionless). Unclaimed refunds and the snapshot's rent go to the
+/// treasury. Before graduation the escrow holds only its rent by now, so it
+/// closes too and the rent returns to the deployer.
+pub fn close_refund_snapshot(ctx: Context<CloseRefundSnapshot>) -> Result<()> {
+    let snapshot = &ctx.accounts.refund_snapshot;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        snapshot.claimed_count == snapshot.leaf_count
+            || now >= snapshot.posted_at.saturating_add(SNAPSHOT_CLAIM_SECONDS),
+        FyrstError::SnapshotClaimsOpen
+    );
+    let unclaimed_lamports = snapshot
+        .pool_lamports
+        .checked_sub(snapshot.paid_lamports)
+        .ok_or(FyrstError::MathOverflow)?;
+
+    let mut escrow_rent = 0;
+    if !ctx.accounts.bonding_curve.graduated {
+        let escrow_info = ctx.accounts.escrow_vault.to_account_info();
+        escrow_rent = escrow_info.lamports();
+        **escrow_info.try_borrow_mut_lamports()? = 0;
+        **ctx.accounts.deployer.to_account_info().try_borrow_mut_lamports()? += escrow_rent;
+        escrow_info.assign(&System::id());
+        escrow_info.resize(0)?;
+    }
+
+    let closed = RefundSnapshotClosed {
+        token_mint: ctx.accounts.bonding_curve.token_mint,
+        bonding_curve: ctx.accounts.bonding_curve.key(),
+        refund_snapshot: snapshot.key(),
+        unclaimed_lamports,
+        escrow_rent,
+        timestamp: now,
+    };
+    emit_cpi!(closed);
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], leaf_count: u32)]
+pub struct PostRefundSnapshot<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == protocol_config.authority @ FyrstError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// The one escrow recorded on the curve at launch
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, bonding_curve.deployer.as_ref(), bonding_curve.token_mint.as_ref()],
+        bump = escrow_vault.bump,
+        has_one = bonding_curve @ FyrstError::EscrowMismatch,
+        constraint = !escrow_vault.released @ FyrstError::EscrowAlreadyReleased,
+    )]
+    pub escrow_vault: Account<'info, EscrowVault>,
+
+    #[account(
+        mut,
+        seeds = [CURVE_SEED, bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = escrow_vault @ FyrstError::EscrowMismatch,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    /// One snapshot per escrow — a second post fails on init
+    #[account(
+        init,
+        payer = authority,
+        space = RefundSnapshot::space(leaf_count),
+        seeds = [REFUND_SNAPSHOT_SEED, escrow_vault.key().as_ref()],
+        bump,
+    )]
+    pub refund_snapshot: Account<'info, RefundSnapshot>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimSnapshotRefund<'info> {
+    /// Wallet in the proven leaf; receives the refund
+    #[account(mut)]
+    pub holder: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [REFUND_SNAPSHOT_SEED, refund_snapshot.escrow_vault.as_ref()],
+        bump = refund_snapshot.bump,
+        has_one = bonding_curve @ FyrstError::EscrowMismatch,
+    )]
+    pub refund_snapshot: Account<'info, RefundSnapshot>,
+
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PushSnapshotRefunds<'info> {
+    /// Keeper cranking the batch
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [REFUND_SNAPSHOT_SEED, refund_snapshot.escrow_vault.as_ref()],
+        bump = refund_snapshot.bump,
+        has_one = bonding_curve @ FyrstError::EscrowMismatch,
+    )]
+    pub refund_snapshot: Account<'info, RefundSnapshot>,
+
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseRefundSnapshot<'info> {
+    /// Deployer wallet (receives the escrow rent). Not required to sign.
+    #[account(mut)]
+    pub deployer: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, deployer.key().as_ref(), escrow_vault.token_mint.as_ref()],
+        bump = escrow_vault.bump,
+        has_one = deployer,
+        has_one = bonding_curve @ FyrstError::EscrowMismatch,
+        has_one = refund_snapshot @ FyrstError::EscrowMismatch,
+    )]
+    pub escrow_vault: Account<'info, EscrowVault>,
+
+    #[account(
+        seeds = [CURVE_SEED, escrow_vault.token_mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = escrow_vault @ FyrstError::EscrowMismatch,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        close = treasury,
+        seeds = [REFUND_SNAPSHOT_SEED, escrow_vault.key().as_ref()],
+        bump = refund_snapshot.bump,
+    )]
+    pub refund_snapshot: Account<'info, RefundSnapshot>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Treasury wallet (receives unclaimed refunds and the snapshot rent)
+    #[account(
+        mut,
+        constraint = treasury.key() == protocol_config.treasury
+    )]
+    pub treasury: SystemAccount<'info>,
+}
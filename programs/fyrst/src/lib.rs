@@ -3,118 +3,14 @@ use anchor_lang::prelude::*;
 pub mod state;
 pub mod instructions;
 pub mod errors;
+pub mod events;
 pub mod constants;
+pub mod curve;
+pub mod math;
+pub mod merkle;
+mod processor;
 
 use instructions::*;
+pub use processor::*;
 
 declare_id!("CcyByKGzRDK17icyNGAgdUN4q7WzbL1BPi4BNzqytyMP");
-
-#[program]
-pub mod fyrst {
-    use super::*;
-
-    /// Initialize protocol configuration (one-time setup)
-    pub fn init_protocol(
-        ctx: Context<InitProtocol>,
-        treasury: Pubkey,
-        ops_wallet: Pubkey,
-    ) -> Result<()> {
-        instructions::protocol::init_protocol(ctx, treasury, ops_wallet)
-    }
-
-    /// Close protocol config for migration (authority only)
-    pub fn close_config(ctx: Context<CloseConfig>) -> Result<()> {
-        instructions::protocol::close_config(ctx)
-    }
-
-    /// Initialize escrow vault with deployer collateral and custom deadline
-    pub fn create_escrow(
-        ctx: Context<CreateEscrow>,
-        collateral_amount: u64,
-        duration_seconds: i64,
-    ) -> Result<()> {
-        instructions::escrow::create_escrow(ctx, collateral_amount, duration_seconds)
-    }
-
-    /// Release escrow back to deployer (requires token graduation)
-    pub fn release_escrow(ctx: Context<ReleaseEscrow>) -> Result<()> {
-        instructions::escrow::release_escrow(ctx)
-    }
-
-    /// Expire escrow after deadline (permissionless). 50% deployer refund, 50% treasury buyback+burn.
-    pub fn expire_escrow(ctx: Context<ExpireEscrow>) -> Result<()> {
-        instructions::escrow::expire_escrow(ctx)
-    }
-
-    /// Initialize bonding curve with SPL token mint + metadata (constant product AMM)
-    pub fn init_bonding_curve(
-        ctx: Context<InitBondingCurve>,
-        name: String,
-        symbol: String,
-        uri: String,
-    ) -> Result<()> {
-        instructions::bonding_curve::init_bonding_curve(ctx, name, symbol, uri)
-    }
-
-    /// Buy tokens on the bonding curve (mints SPL tokens)
-    pub fn buy_tokens(
-        ctx: Context<BuyTokens>,
-        sol_amount: u64,
-        min_tokens_out: u64,
-    ) -> Result<()> {
-        instructions::bonding_curve::buy_tokens(ctx, sol_amount, min_tokens_out)
-    }
-
-    /// Sell tokens on the bonding curve (burns SPL tokens)
-    pub fn sell_tokens(
-        ctx: Context<SellTokens>,
-        token_amount: u64,
-        min_sol_out: u64,
-    ) -> Result<()> {
-        instructions::bonding_curve::sell_tokens(ctx, token_amount, min_sol_out)
-    }
-
-    /// Process burn-to-refund for a buyer (permissionless — buyer claims own refund)
-    pub fn process_refund(ctx: Context<ProcessRefund>) -> Result<()> {
-        instructions::refund::process_refund(ctx)
-    }
-
-    /// Update protocol treasury (authority only)
-    pub fn update_treasury(
-        ctx: Context<UpdateTreasury>,
-        new_treasury: Pubkey,
-    ) -> Result<()> {
-        instructions::protocol::update_treasury(ctx, new_treasury)
-    }
-
-    /// Claim accumulated trade fees (deployer only)
-    pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
-        instructions::protocol::claim_fees(ctx)
-    }
-
-    /// Update operations wallet (authority only)
-    pub fn update_ops_wallet(
-        ctx: Context<UpdateTreasury>,
-        new_ops_wallet: Pubkey,
-    ) -> Result<()> {
-        instructions::protocol::update_ops_wallet(ctx, new_ops_wallet)
-    }
-
-    /// Update graduation threshold (authority only)
-    pub fn update_graduation_threshold(
-        ctx: Context<UpdateTreasury>,
-        new_threshold: u64,
-    ) -> Result<()> {
-        instructions::protocol::update_graduation_threshold(ctx, new_threshold)
-    }
-
-    /// Graduate a bonding curve when reserve meets threshold
-    pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
-        instructions::protocol::graduate(ctx)
-    }
-
-    /// Migrate graduated token to Raydium CPMM DEX (permissionless)
-    pub fn graduate_to_dex(ctx: Context<GraduateToDex>) -> Result<()> {
-        instructions::graduation::graduate_to_dex(ctx)
-    }
-}
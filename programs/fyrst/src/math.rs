@@ -0,0 +1,783 @@
+//! Pure curve, fee and graduation math shared by every instruction.
+//!
+//! Nothing here touches accounts or sysvars, so off-chain clients (depending
+//! on this crate with the `no-entrypoint` or `cpi` feature) compute exactly
+//! the numbers the program will. Quotes fail with the same `FyrstError` the
+//! instruction would; slippage guards are left to the caller.
+
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::curve::{exp_neg_wad, CurvePricing};
+use crate::errors::FyrstError;
+use crate::state::{
+    BondingCurve, CollateralLock, EscrowVault, FeeSchedule, RefundMode, TaxDecay, UnlockSchedule,
+};
+
+/// Fees charged on one trade and where they go
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeSplit {
+    /// Total trade fee (includes the deployer share)
+    pub trade_fee: u64,
+    /// Protocol fee charged on top of the trade fee
+    pub protocol_fee: u64,
+    /// Deployer share of the trade fee (accrued on the curve)
+    pub deployer_fee: u64,
+    /// Operations wallet share of protocol revenue (accrued on the curve)
+    pub ops_fee: u64,
+    /// Treasury (buyback+burn) share of protocol revenue (accrued on the curve)
+    pub treasury_fee: u64,
+    /// Sniper tax on early buys (paid into the curve's holder pool)
+    pub sniper_tax: u64,
+}
+
+/// Priced buy: `sol_amount` (fees included) in, `tokens` out
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuyQuote {
+    /// Gross SOL paid by the buyer
+    pub sol_amount: u64,
+    /// SOL added to the curve reserve (after fees)
+    pub net_sol: u64,
+    /// Tokens delivered to the buyer
+    pub tokens: u64,
+    pub fees: FeeSplit,
+}
+
+/// Priced sell: `tokens` in, `net_sol` out
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SellQuote {
+    /// Tokens returned to the curve
+    pub tokens: u64,
+    /// SOL removed from the curve reserve (before fees)
+    pub gross_sol: u64,
+    /// SOL paid to the seller
+    pub net_sol: u64,
+    pub fees: FeeSplit,
+}
+
+/// amount * bps / 10_000, rounded down
+pub fn apply_bps(amount: u64, bps: u64) -> Result<u64> {
+    let value = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(FyrstError::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(value).map_err(|_| FyrstError::MathOverflow.into())
+}
+
+/// Split protocol revenue: ops_share_bps → ops wallet, rest → treasury.
+/// Returns (ops, treasury).
+pub fn ops_split(amount: u64, ops_share_bps: u64) -> Result<(u64, u64)> {
+    let ops = apply_bps(amount, ops_share_bps)?;
+    let treasury = amount.checked_sub(ops).ok_or(FyrstError::MathOverflow)?;
+    Ok((ops, treasury))
+}
+
+/// Fees on a gross trade amount and the net SOL left after them.
+/// deployer_fee_bps of volume → deployer (carved out of the trade fee);
+/// the rest of the trade fee plus the protocol fee is protocol revenue.
+/// `sniper_tax_bps` (buys only) is charged on top and goes to holders.
+pub fn split_fees(schedule: &FeeSchedule, gross_sol: u64, sniper_tax_bps: u64) -> Result<(FeeSplit, u64)> {
+    let trade_fee = apply_bps(gross_sol, schedule.trade_fee_bps)?;
+    let protocol_fee = apply_bps(gross_sol, schedule.protocol_fee_bps)?;
+    let deployer_fee = apply_bps(gross_sol, schedule.deployer_fee_bps)?;
+    let sniper_tax = apply_bps(gross_sol, sniper_tax_bps)?;
+
+    let protocol_revenue = trade_fee
+        .checked_sub(deployer_fee)
+        .ok_or(FyrstError::MathOverflow)?
+        .checked_add(protocol_fee)
+        .ok_or(FyrstError::MathOverflow)?;
+    let (ops_fee, treasury_fee) = ops_split(protocol_revenue, schedule.ops_share_bps)?;
+
+    let net_sol = gross_sol
+        .checked_sub(trade_fee)
+        .ok_or(FyrstError::MathOverflow)?
+        .checked_sub(protocol_fee)
+        .ok_or(FyrstError::MathOverflow)?
+        .checked_sub(sniper_tax)
+        .ok_or(FyrstError::MathOverflow)?;
+
+    let split = FeeSplit {
+        trade_fee,
+        protocol_fee,
+        deployer_fee,
+        ops_fee,
+        treasury_fee,
+        sniper_tax,
+    };
+    Ok((split, net_sol))
+}
+
+/// Smallest gross SOL amount whose net (after trade, protocol and sniper
+/// fees) is at least `net_sol`
+pub fn gross_up_for_fees(schedule: &FeeSchedule, net_sol: u64, sniper_tax_bps: u64) -> Result<u64> {
+    let fee_bps = schedule
+        .trade_fee_bps
+        .checked_add(schedule.protocol_fee_bps)
+        .and_then(|bps| bps.checked_add(sniper_tax_bps))
+        .ok_or(FyrstError::MathOverflow)?;
+    let net_bps = BPS_DENOMINATOR.checked_sub(fee_bps).ok_or(FyrstError::MathOverflow)? as u128;
+    require!(net_bps > 0, FyrstError::InvalidPrice);
+
+    let gross = (net_sol as u128)
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .ok_or(FyrstError::MathOverflow)?
+        .div_ceil(net_bps);
+    let mut gross = u64::try_from(gross).map_err(|_| FyrstError::MathOverflow)?;
+
+    // Each fee floors on its own, so the net can beat the blended rate by a
+    // lamport per fee; step down to the smallest gross that still covers it
+    while gross > net_sol && split_fees(schedule, gross - 1, sniper_tax_bps)?.1 >= net_sol {
+        gross -= 1;
+    }
+    Ok(gross)
+}
+
+/// Sniper tax on a buy at `now`, in basis points on top of the trade fee.
+/// Falls from (start_fee_bps - trade_fee_bps) at the open time to zero once
+/// the tax duration has elapsed.
+pub fn sniper_tax_bps(curve: &BondingCurve, now: i64) -> Result<u64> {
+    let tax = curve.sniper_tax;
+    let elapsed = now.saturating_sub(curve.trading_opens_at).max(0);
+    if tax.start_fee_bps == 0 || elapsed >= tax.duration_seconds {
+        return Ok(0);
+    }
+
+    let start_bps = tax.start_fee_bps.saturating_sub(curve.fee_schedule.trade_fee_bps) as u128;
+    let bps = match tax.decay {
+        TaxDecay::Linear => {
+            start_bps * (tax.duration_seconds - elapsed) as u128 / tax.duration_seconds as u128
+        }
+        TaxDecay::Exponential { half_life_seconds } => {
+            // start * e^(-ln2 * elapsed / half_life) = start * 2^(-elapsed / half_life)
+            let exponent = LN2_WAD * elapsed as u128 / half_life_seconds as u128;
+            start_bps * exp_neg_wad(exponent)? / WAD
+        }
+    };
+    u64::try_from(bps).map_err(|_| FyrstError::MathOverflow.into())
+}
+
+/// Quote an exact-SOL-in buy at `now` (the sniper tax depends on curve age)
+pub fn quote_buy(curve: &BondingCurve, sol_amount: u64, now: i64) -> Result<BuyQuote> {
+    price_buy(curve, sol_amount, sniper_tax_bps(curve, now)?)
+}
+
+/// Quote an exact-SOL-in buy with no sniper tax (presale buys and batch-opening
+/// settlement)
+pub fn quote_buy_untaxed(curve: &BondingCurve, sol_amount: u64) -> Result<BuyQuote> {
+    price_buy(curve, sol_amount, 0)
+}
+
+fn price_buy(curve: &BondingCurve, sol_amount: u64, tax_bps: u64) -> Result<BuyQuote> {
+    require!(!curve.graduated, FyrstError::AlreadyGraduated);
+    require!(sol_amount > 0, FyrstError::InsufficientFunds);
+
+    let (fees, net_sol) = split_fees(&curve.fee_schedule, sol_amount, tax_bps)?;
+    let tokens = curve.curve_kind.tokens_out(&curve.position()?, net_sol)?;
+
+    require!(tokens > 0, FyrstError::InsufficientFunds);
+    require!(tokens <= curve.real_token_reserves, FyrstError::InsufficientTokens);
+
+    Ok(BuyQuote { sol_amount, net_sol, tokens, fees })
+}
+
+/// Quote an exact-tokens-out buy at `now`. Clamps to the curve's remaining
+/// real tokens and charges only the SOL those tokens require.
+pub fn quote_buy_exact_tokens(curve: &BondingCurve, token_amount: u64, now: i64) -> Result<BuyQuote> {
+    require!(!curve.graduated, FyrstError::AlreadyGraduated);
+
+    let tokens = token_amount.min(curve.real_token_reserves);
+    require!(tokens > 0, FyrstError::InsufficientTokens);
+
+    let tax_bps = sniper_tax_bps(curve, now)?;
+    let required_net = curve.curve_kind.sol_in(&curve.position()?, tokens)?;
+    let sol_amount = gross_up_for_fees(&curve.fee_schedule, required_net, tax_bps)?;
+    let (fees, net_sol) = split_fees(&curve.fee_schedule, sol_amount, tax_bps)?;
+
+    Ok(BuyQuote { sol_amount, net_sol, tokens, fees })
+}
+
+/// Quote an exact-tokens-in sell. Errors if the curve's SOL reserve cannot
+/// cover the full curve price of `token_amount`.
+pub fn quote_sell(curve: &BondingCurve, token_amount: u64) -> Result<SellQuote> {
+    require!(!curve.graduated, FyrstError::AlreadyGraduated);
+    require!(token_amount > 0, FyrstError::InsufficientTokens);
+
+    let gross_sol = curve.curve_kind.sol_out(&curve.position()?, token_amount)?;
+    require!(
+        gross_sol <= curve.real_sol_reserves.min(curve.reserve_balance),
+        FyrstError::InsufficientReserve
+    );
+    let (fees, net_sol) = split_fees(&curve.fee_schedule, gross_sol, 0)?;
+
+    Ok(SellQuote { tokens: token_amount, gross_sol, net_sol, fees })
+}
+
+/// Quote an exact-SOL-out sell (`sol_out` after fees). Takes only the tokens
+/// the inverse curve requires; errors if the reserve cannot cover it.
+pub fn quote_sell_exact_sol(curve: &BondingCurve, sol_out: u64) -> Result<SellQuote> {
+    require!(!curve.graduated, FyrstError::AlreadyGraduated);
+    require!(sol_out > 0, FyrstError::InsufficientFunds);
+
+    let gross_sol = gross_up_for_fees(&curve.fee_schedule, sol_out, 0)?;
+    require!(
+        gross_sol <= curve.real_sol_reserves.min(curve.reserve_balance),
+        FyrstError::InsufficientReserve
+    );
+    let tokens = curve.curve_kind.tokens_in(&curve.position()?, gross_sol)?;
+    require!(tokens > 0, FyrstError::InsufficientTokens);
+    let (fees, net_sol) = split_fees(&curve.fee_schedule, gross_sol, 0)?;
+
+    Ok(SellQuote { tokens, gross_sol, net_sol, fees })
+}
+
+/// Deployer fees unlocked so far:
+/// unlocked = total_deployer_fees * max_reserve_reached / graduation_threshold
+pub fn unlocked_deployer_fees(
+    total_deployer_fees: u64,
+    max_reserve_reached: u64,
+    graduation_threshold: u64,
+) -> Result<u64> {
+    let unlocked = (total_deployer_fees as u128)
+        .checked_mul(max_reserve_reached as u128)
+        .ok_or(FyrstError::MathOverflow)?
+        .checked_div(graduation_threshold as u128)
+        .ok_or(FyrstError::MathOverflow)?;
+    u64::try_from(unlocked).map_err(|_| FyrstError::MathOverflow.into())
+}
+
+/// Deployer fees claimable right now (unlocked minus already claimed).
+/// Zero, not an error, while a raised threshold keeps unlocked below claimed.
+pub fn claimable_deployer_fees(curve: &BondingCurve) -> Result<u64> {
+    Ok(unlocked_deployer_fees(
+        curve.total_deployer_fees,
+        curve.max_reserve_reached,
+        curve.graduation_threshold,
+    )?
+    .saturating_sub(curve.claimed_deployer_fees))
+}
+
+/// Most SOL the curve's reserve can hold: the current reserve plus what
+/// buying out the remaining real tokens adds. Thresholds above it never trip.
+pub fn sell_out_reserve(curve: &BondingCurve) -> Result<u64> {
+    let remaining = curve
+        .curve_kind
+        .sol_in(&curve.position()?, curve.real_token_reserves)?;
+    curve
+        .reserve_balance
+        .checked_add(remaining)
+        .ok_or(FyrstError::MathOverflow.into())
+}
+
+/// Collateral unlocked `now` under a post-graduation lock. Nothing unlocks
+/// before graduated_at + lock_seconds; after that, Linear unlocks
+/// collateral × elapsed / unlock_seconds and Stepped unlocks whole tranches
+/// of collateral / steps, until everything is unlocked at the end.
+pub fn unlocked_collateral(lock: &CollateralLock, collateral: u64, graduated_at: i64, now: i64) -> Result<u64> {
+    let unlock_starts_at = graduated_at.saturating_add(lock.lock_seconds);
+    if now < unlock_starts_at {
+        return Ok(0);
+    }
+    let elapsed = now - unlock_starts_at;
+    if elapsed >= lock.unlock_seconds {
+        return Ok(collateral);
+    }
+
+    let (numerator, denominator) = match lock.schedule {
+        UnlockSchedule::Linear => (elapsed as u128, lock.unlock_seconds as u128),
+        UnlockSchedule::Stepped { steps } => {
+            let steps_done = (elapsed as u128)
+                .checked_mul(steps as u128)
+                .ok_or(FyrstError::MathOverflow)?
+                / lock.unlock_seconds as u128;
+            (steps_done, steps as u128)
+        }
+    };
+    let unlocked = (collateral as u128)
+        .checked_mul(numerator)
+        .ok_or(FyrstError::MathOverflow)?
+        .checked_div(denominator)
+        .ok_or(FyrstError::MathOverflow)?;
+    u64::try_from(unlocked).map_err(|_| FyrstError::MathOverflow.into())
+}
+
+/// Collateral still locked in the escrow (slashable); all of it before
+/// graduation. A slash takes everything locked at the time, so later unlocks
+/// only release what the deployer kept.
+pub fn locked_collateral(escrow: &EscrowVault, curve: &BondingCurve, now: i64) -> Result<u64> {
+    let collateral = escrow
+        .collateral_amount
+        .checked_sub(escrow.collateral_slashed)
+        .ok_or(FyrstError::MathOverflow)?;
+    if !curve.graduated {
+        return Ok(collateral);
+    }
+    let unlocked = unlocked_collateral(
+        &escrow.collateral_lock,
+        escrow.collateral_amount,
+        curve.graduated_at,
+        now,
+    )?;
+    Ok(collateral - unlocked.min(collateral))
+}
+
+/// Collateral the deployer can claim right now (unlocked minus already claimed)
+pub fn claimable_collateral(escrow: &EscrowVault, curve: &BondingCurve, now: i64) -> Result<u64> {
+    let kept = escrow
+        .collateral_amount
+        .checked_sub(escrow.collateral_slashed)
+        .ok_or(FyrstError::MathOverflow)?;
+    kept.checked_sub(locked_collateral(escrow, curve, now)?)
+        .and_then(|unlocked| unlocked.checked_sub(escrow.collateral_claimed))
+        .ok_or(FyrstError::MathOverflow.into())
+}
+
+/// Whether the deployer has dumped: tokens sold (or otherwise moved out of
+/// their wallet) exceed dump_threshold_bps of the tokens they acquired
+pub fn deployer_dumped(curve: &BondingCurve, tokens_sold: u64) -> Result<bool> {
+    Ok(tokens_sold > apply_bps(curve.deployer_tokens_bought, curve.dump_threshold_bps)?)
+}
+
+/// Pro-rata refund: holder_tokens / circulating_supply × pool_lamports
+/// (or any other weight over its total)
+pub fn refund_share(holder_tokens: u64, circulating_supply: u64, pool_lamports: u64) -> Result<u64> {
+    let share = (holder_tokens as u128)
+        .checked_mul(pool_lamports as u128)
+        .ok_or(FyrstError::MathOverflow)?
+        .checked_div(circulating_supply as u128)
+        .ok_or(FyrstError::MathOverflow)?;
+    u64::try_from(share).map_err(|_| FyrstError::MathOverflow.into())
+}
+
+/// Committer's share of the tokens cleared by a batch opening:
+/// opening_tokens × sol_committed / opening_total_sol
+pub fn opening_claim(sol_committed: u64, opening_total_sol: u64, opening_tokens: u64) -> Result<u64> {
+    let tokens = (opening_tokens as u128)
+        .checked_mul(sol_committed as u128)
+        .ok_or(FyrstError::MathOverflow)?
+        .checked_div(opening_total_sol as u128)
+        .ok_or(FyrstError::MathOverflow)?;
+    u64::try_from(tokens).map_err(|_| FyrstError::MathOverflow.into())
+}
+
+/// Burn-to-refund payout, split by where the lamports come from
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RefundPayout {
+    /// Paid from the escrow vault
+    pub escrow_share: u64,
+    /// Paid from the curve's SOL reserve
+    pub reserve_share: u64,
+    /// Paid from the curve's holder pool (sniper tax)
+    pub pool_share: u64,
+    /// Paid from the curve's slashed collateral
+    pub slashed_share: u64,
+}
+
+impl RefundPayout {
+    /// Lamports the holder receives
+    pub fn total(&self) -> Result<u64> {
+        self.escrow_share
+            .checked_add(self.reserve_share)
+            .and_then(|sum| sum.checked_add(self.pool_share))
+            .and_then(|sum| sum.checked_add(self.slashed_share))
+            .ok_or(FyrstError::MathOverflow.into())
+    }
+}
+
+/// A holder's refund weight and the curve-wide total it is measured against:
+/// tokens burned over current_supply (`TokenShare`), or the contribution
+/// backing them (`Position::contribution_for`) over total_net_contributed
+/// (`Contribution`)
+pub fn refund_weight(curve: &BondingCurve, holder_tokens: u64, contribution: u64) -> (u64, u64) {
+    match curve.refund_mode {
+        RefundMode::TokenShare => (holder_tokens, curve.current_supply),
+        RefundMode::Contribution => (contribution, curve.total_net_contributed),
+    }
+}
+
+/// Burn-to-refund payout for a holder with refund weight `share` out of
+/// `total` (see `refund_weight`): pro-rata shares of the escrow lamports, the
+/// curve's SOL reserve, its holder pool and any slashed collateral. After
+/// graduation only slashed collateral is refunded — what is left in the escrow
+/// is the deployer's unlocked collateral and the reserve and holder pool went
+/// to the DEX. DEX trading can leave holders with more than current_supply,
+/// so the slashed share is capped at what remains.
+pub fn refund_payout(curve: &BondingCurve, share: u64, total: u64, escrow_lamports: u64) -> Result<RefundPayout> {
+    let slashed_share = refund_share(share, total, curve.slashed_collateral)?
+        .min(curve.slashed_collateral);
+    if curve.graduated {
+        return Ok(RefundPayout { slashed_share, ..Default::default() });
+    }
+    Ok(RefundPayout {
+        escrow_share: refund_share(share, total, escrow_lamports)?,
+        reserve_share: refund_share(share, total, curve.reserve_balance)?,
+        pool_share: refund_share(share, total, curve.holder_pool)?,
+        slashed_share,
+    })
+}
+
+/// DEX pool liquidity as (sol, tokens), opening at the curve's final spot
+/// price. Pairs all of `liquidity_sol` when the tokens it is worth fit in
+/// `available_tokens`; otherwise pairs every available token with only the
+/// SOL they are worth. A buy can overshoot the threshold, so the worst-case
+/// pool of some curve shapes needs more tokens than were kept back for it.
+pub fn pool_liquidity(curve: &BondingCurve, liquidity_sol: u64, available_tokens: u64) -> Result<(u64, u64)> {
+    let pos = curve.position()?;
+    let tokens = curve.curve_kind.tokens_at_spot(&pos, liquidity_sol)?;
+    if tokens <= available_tokens {
+        return Ok((liquidity_sol, tokens));
+    }
+    let sol = curve.curve_kind.sol_at_spot(&pos, available_tokens)?;
+    Ok((sol.min(liquidity_sol), available_tokens))
+}
+
+/// Deploy fee after a discount in basis points
+pub fn discounted_fee(fee: u64, discount_bps: u64) -> Result<u64> {
+    let keep_bps = BPS_DENOMINATOR.checked_sub(discount_bps).ok_or(FyrstError::InvalidDiscount)?;
+    apply_bps(fee, keep_bps)
+}
+
+/// Whether the curve has met its graduation condition:
+/// SOL reserve at the threshold OR all real tokens sold
+pub fn graduation_reached(curve: &BondingCurve) -> bool {
+    curve.reserve_balance >= curve.graduation_threshold || curve.real_token_reserves == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{CurveKind, SniperTax};
+
+    const TOTAL: u64 = 1_000_000_000_000_000;
+    const SOL: u64 = 1_000_000_000;
+
+    /// Constant product curve launched with `curve_token_bps` and `virtual_sol`,
+    /// after `sold` tokens were bought; returns it with its reserve
+    fn cp_curve(curve_token_bps: u64, virtual_sol: u64, sold: u64) -> (BondingCurve, u64) {
+        let real = apply_bps(TOTAL, curve_token_bps).unwrap();
+        let vt0 = (real as u128 * INITIAL_VIRTUAL_TOKEN_RESERVES as u128
+            / INITIAL_REAL_TOKEN_RESERVES as u128) as u64;
+        let mut curve = BondingCurve {
+            virtual_token_reserves: vt0,
+            virtual_sol_reserves: virtual_sol,
+            real_token_reserves: real,
+            token_total_supply: TOTAL,
+            ..Default::default()
+        };
+        let reserve = curve.curve_kind.sol_in(&curve.position().unwrap(), sold).unwrap();
+        curve.virtual_token_reserves -= sold;
+        curve.virtual_sol_reserves += reserve;
+        curve.real_token_reserves -= sold;
+        curve.current_supply = sold;
+        curve.reserve_balance = reserve;
+        (curve, reserve)
+    }
+
+    /// Sold-out constant product curve, with the tokens left for its pool
+    fn sold_out(curve_token_bps: u64, virtual_sol: u64) -> (BondingCurve, u64, u64) {
+        let real = apply_bps(TOTAL, curve_token_bps).unwrap();
+        let (curve, reserve) = cp_curve(curve_token_bps, virtual_sol, real);
+        (curve, reserve, TOTAL - real)
+    }
+
+    #[test]
+    fn raised_threshold_leaves_nothing_claimable() {
+        let mut curve = BondingCurve {
+            total_deployer_fees: 1_000,
+            max_reserve_reached: 5 * SOL,
+            graduation_threshold: 5 * SOL,
+            claimed_deployer_fees: 1_000,
+            ..Default::default()
+        };
+        assert_eq!(claimable_deployer_fees(&curve).unwrap(), 0);
+        curve.graduation_threshold = 10 * SOL;
+        assert_eq!(claimable_deployer_fees(&curve).unwrap(), 0);
+        curve.max_reserve_reached = 10 * SOL;
+        curve.total_deployer_fees = 3_000;
+        assert_eq!(claimable_deployer_fees(&curve).unwrap(), 2_000);
+    }
+
+    #[test]
+    fn sell_out_reserve_adds_the_unsold_allocation() {
+        let (fresh, _) = cp_curve(CURVE_TOKEN_BPS, INITIAL_VIRTUAL_SOL_RESERVES, 0);
+        let (done, reserve, _) = sold_out(CURVE_TOKEN_BPS, INITIAL_VIRTUAL_SOL_RESERVES);
+        assert_eq!(sell_out_reserve(&done).unwrap(), reserve);
+        assert_eq!(sell_out_reserve(&fresh).unwrap(), reserve);
+    }
+
+    /// Sold-out price-function curve over `curve_token_bps` of the supply,
+    /// with its reserve and the tokens left for its pool
+    fn shaped_sold_out(curve_kind: CurveKind, curve_token_bps: u64) -> (BondingCurve, u64, u64) {
+        let real = apply_bps(TOTAL, curve_token_bps).unwrap();
+        let mut curve = BondingCurve {
+            curve_kind,
+            real_token_reserves: real,
+            token_total_supply: TOTAL,
+            ..Default::default()
+        };
+        let reserve = curve_kind.sol_in(&curve.position().unwrap(), real).unwrap();
+        curve.current_supply = real;
+        curve.real_token_reserves = 0;
+        curve.reserve_balance = reserve;
+        (curve, reserve, TOTAL - real)
+    }
+
+    #[test]
+    fn shaped_pool_fits_when_end_price_covers_the_average() {
+        // Reserve / final spot = allocation × average price / end price
+        let linear = CurveKind::Linear { start_price: CURVE_MIN_PRICE, end_price: 100 * CURVE_MIN_PRICE };
+        let (curve, reserve, available) = shaped_sold_out(linear, 5_000);
+        let (sol, tokens) = pool_liquidity(&curve, reserve, available).unwrap();
+        assert_eq!(sol, reserve);
+        assert!(tokens <= available);
+    }
+
+    #[test]
+    fn shaped_pool_capped_when_end_price_is_too_low() {
+        let linear = CurveKind::Linear { start_price: CURVE_MIN_PRICE, end_price: 100 * CURVE_MIN_PRICE };
+        let step = CurveKind::Step { prices: [CURVE_MIN_PRICE, 0, 0, 0, 0, 0, 0, 0], count: 1 };
+        for kind in [linear, step] {
+            let (curve, reserve, available) = shaped_sold_out(kind, CURVE_TOKEN_BPS);
+            let (sol, tokens) = pool_liquidity(&curve, reserve, available).unwrap();
+            assert_eq!(tokens, available, "{kind:?}");
+            assert!(sol < reserve);
+            let spot = curve.curve_kind.spot_price(&curve.position().unwrap()).unwrap();
+            assert!(sol as u128 * PRICE_SCALE <= tokens as u128 * spot);
+        }
+    }
+
+    #[test]
+    fn pool_pairs_whole_reserve_at_reference_token_share() {
+        let (curve, reserve, available) = sold_out(CURVE_TOKEN_BPS, INITIAL_VIRTUAL_SOL_RESERVES);
+        let (sol, tokens) = pool_liquidity(&curve, reserve, available).unwrap();
+        assert_eq!(sol, reserve);
+        assert!(tokens <= available);
+    }
+
+    #[test]
+    fn pool_capped_above_reference_token_share() {
+        let (curve, reserve, available) = sold_out(CURVE_TOKEN_BPS + 100, INITIAL_VIRTUAL_SOL_RESERVES);
+        let (sol, tokens) = pool_liquidity(&curve, reserve, available).unwrap();
+        assert_eq!(tokens, available);
+        assert!(sol < reserve);
+        // Pool price never undercuts the curve's final spot price
+        assert!(sol as u128 * curve.virtual_token_reserves as u128
+            <= tokens as u128 * curve.virtual_sol_reserves as u128);
+    }
+
+    #[test]
+    fn pool_capped_when_buy_overshoots_threshold() {
+        // 5 SOL virtual, 5 SOL threshold: one buy to virtual_token / 2 needs a
+        // quarter of virtual_token for the pool, more than the LP share
+        let real = apply_bps(TOTAL, CURVE_TOKEN_BPS).unwrap();
+        let (probe, _) = cp_curve(CURVE_TOKEN_BPS, 5 * SOL, 0);
+        let half = probe.virtual_token_reserves / 2;
+        let (curve, reserve) = cp_curve(CURVE_TOKEN_BPS, 5 * SOL, half);
+        assert!(reserve >= 5 * SOL);
+        let available = TOTAL - real;
+        assert!(curve.curve_kind.tokens_at_spot(&curve.position().unwrap(), reserve).unwrap() > available);
+
+        let (sol, tokens) = pool_liquidity(&curve, reserve, available).unwrap();
+        assert_eq!(tokens, available);
+        assert!(sol < reserve && sol > 0);
+    }
+
+    #[test]
+    fn pool_unchanged_below_the_overshoot_bound() {
+        // Graduating exactly at a 5 SOL threshold on the reference curve fits
+        let real = apply_bps(TOTAL, CURVE_TOKEN_BPS).unwrap();
+        let (probe, _) = cp_curve(CURVE_TOKEN_BPS, INITIAL_VIRTUAL_SOL_RESERVES, 0);
+        let sold = probe.curve_kind.tokens_out(&probe.position().unwrap(), 5 * SOL).unwrap();
+        let (curve, reserve) = cp_curve(CURVE_TOKEN_BPS, INITIAL_VIRTUAL_SOL_RESERVES, sold);
+        let (sol, tokens) = pool_liquidity(&curve, reserve, TOTAL - real).unwrap();
+        assert_eq!(sol, reserve);
+        assert!(tokens < TOTAL - real);
+    }
+
+    /// Fee schedules with every fee switched on, rounding-heavy and default
+    fn schedules() -> [FeeSchedule; 3] {
+        let base = FeeSchedule::default();
+        [
+            base,
+            FeeSchedule { protocol_fee_bps: 37, ..base },
+            FeeSchedule { trade_fee_bps: 333, deployer_fee_bps: 111, ops_share_bps: 3_333, protocol_fee_bps: 199, ..base },
+        ]
+    }
+
+    #[test]
+    fn split_fees_accounts_for_every_lamport() {
+        for schedule in schedules() {
+            for gross in [1, 99, 101, 9_999, 10_001, 123_456_789, 50 * SOL] {
+                for tax_bps in [0, 1, 977] {
+                    let (fees, net) = split_fees(&schedule, gross, tax_bps).unwrap();
+                    assert_eq!(net + fees.trade_fee + fees.protocol_fee + fees.sniper_tax, gross);
+                    assert_eq!(
+                        fees.deployer_fee + fees.ops_fee + fees.treasury_fee,
+                        fees.trade_fee + fees.protocol_fee
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gross_up_never_undercharges() {
+        for schedule in schedules() {
+            for net in [1, 2, 99, 9_900, 123_456_789, 50 * SOL] {
+                for tax_bps in [0, 977] {
+                    let gross = gross_up_for_fees(&schedule, net, tax_bps).unwrap();
+                    let (_, charged_net) = split_fees(&schedule, gross, tax_bps).unwrap();
+                    assert!(charged_net >= net, "gross {gross} nets {charged_net} < {net}");
+                    // ...and no smaller gross would do
+                    assert!(split_fees(&schedule, gross - 1, tax_bps).unwrap().1 < net);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gross_up_rejects_fees_of_the_whole_amount() {
+        let schedule = FeeSchedule::default();
+        let tax_bps = BPS_DENOMINATOR - schedule.trade_fee_bps;
+        assert!(gross_up_for_fees(&schedule, 1, tax_bps).is_err());
+    }
+
+    /// Reference curve with `sold` tokens bought and its SOL reserve recorded
+    fn trading_curve(sold: u64) -> BondingCurve {
+        let (mut curve, reserve) = cp_curve(CURVE_TOKEN_BPS, INITIAL_VIRTUAL_SOL_RESERVES, sold);
+        curve.real_sol_reserves = reserve;
+        curve
+    }
+
+    #[test]
+    fn exact_token_buy_delivers_what_quote_buy_prices() {
+        let curve = trading_curve(INITIAL_REAL_TOKEN_RESERVES / 10);
+        for tokens in [1, 1_000_000, 1_000_000_000_000, INITIAL_REAL_TOKEN_RESERVES / 5] {
+            let exact = quote_buy_exact_tokens(&curve, tokens, 0).unwrap();
+            assert_eq!(exact.tokens, tokens);
+            // Spending the quoted SOL as an exact-SOL buy gets at least as many tokens
+            let spent = quote_buy(&curve, exact.sol_amount, 0).unwrap();
+            assert!(spent.tokens >= tokens);
+            assert_eq!(spent.net_sol, exact.net_sol);
+        }
+        for sol in [1_000, SOL / 100, 3 * SOL] {
+            let bought = quote_buy(&curve, sol, 0).unwrap();
+            let exact = quote_buy_exact_tokens(&curve, bought.tokens, 0).unwrap();
+            assert!(exact.sol_amount <= sol);
+        }
+    }
+
+    #[test]
+    fn exact_token_buy_clamps_to_remaining_tokens() {
+        let curve = trading_curve(INITIAL_REAL_TOKEN_RESERVES - 1_000);
+        let quote = quote_buy_exact_tokens(&curve, u64::MAX, 0).unwrap();
+        assert_eq!(quote.tokens, 1_000);
+    }
+
+    #[test]
+    fn exact_sol_sell_pays_what_quote_sell_prices() {
+        let curve = trading_curve(INITIAL_REAL_TOKEN_RESERVES / 2);
+        for sol_out in [1, 1_000_000, SOL / 3, 2 * SOL] {
+            let exact = quote_sell_exact_sol(&curve, sol_out).unwrap();
+            assert!(exact.net_sol >= sol_out);
+            // Selling the quoted tokens as an exact-token sell pays at least as much
+            let sold = quote_sell(&curve, exact.tokens).unwrap();
+            assert!(sold.net_sol >= sol_out);
+        }
+        for tokens in [1_000_000_000, INITIAL_REAL_TOKEN_RESERVES / 4] {
+            let sold = quote_sell(&curve, tokens).unwrap();
+            let exact = quote_sell_exact_sol(&curve, sold.net_sol).unwrap();
+            assert!(exact.tokens <= tokens);
+        }
+    }
+
+    #[test]
+    fn exact_sol_sell_beyond_reserve_fails() {
+        let curve = trading_curve(INITIAL_REAL_TOKEN_RESERVES / 10);
+        assert!(quote_sell_exact_sol(&curve, curve.reserve_balance).is_err());
+    }
+
+    /// Curve opening at t=1000 with a 11% launch fee decaying over 600s
+    fn taxed_curve(decay: TaxDecay) -> BondingCurve {
+        BondingCurve {
+            trading_opens_at: 1_000,
+            sniper_tax: SniperTax { start_fee_bps: 1_100, duration_seconds: 600, decay },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn linear_sniper_tax_endpoints() {
+        let curve = taxed_curve(TaxDecay::Linear);
+        // Start is the launch fee less the trade fee
+        assert_eq!(sniper_tax_bps(&curve, 0).unwrap(), 1_000);
+        assert_eq!(sniper_tax_bps(&curve, 1_000).unwrap(), 1_000);
+        assert_eq!(sniper_tax_bps(&curve, 1_300).unwrap(), 500);
+        assert_eq!(sniper_tax_bps(&curve, 1_599).unwrap(), 1);
+        assert_eq!(sniper_tax_bps(&curve, 1_600).unwrap(), 0);
+        assert_eq!(sniper_tax_bps(&curve, i64::MAX).unwrap(), 0);
+    }
+
+    #[test]
+    fn exponential_sniper_tax_halves_per_half_life() {
+        let curve = taxed_curve(TaxDecay::Exponential { half_life_seconds: 100 });
+        assert_eq!(sniper_tax_bps(&curve, 1_000).unwrap(), 1_000);
+        for (halvings, expected) in [(1, 500), (2, 250), (3, 125)] {
+            let bps = sniper_tax_bps(&curve, 1_000 + 100 * halvings).unwrap();
+            assert!(bps.abs_diff(expected) <= 1, "{halvings} half-lives: {bps}");
+        }
+        assert!(sniper_tax_bps(&curve, 1_599).unwrap() > 0);
+        assert_eq!(sniper_tax_bps(&curve, 1_600).unwrap(), 0);
+    }
+
+    #[test]
+    fn sniper_tax_off_without_a_start_fee() {
+        let curve = BondingCurve::default();
+        assert_eq!(sniper_tax_bps(&curve, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn linear_unlock_starts_after_the_safety_period() {
+        let lock = CollateralLock { lock_seconds: 100, unlock_seconds: 1_000, schedule: UnlockSchedule::Linear };
+        let unlocked = |now| unlocked_collateral(&lock, SOL, 5_000, now).unwrap();
+        assert_eq!(unlocked(0), 0);
+        assert_eq!(unlocked(5_099), 0);
+        assert_eq!(unlocked(5_100), 0);
+        assert_eq!(unlocked(5_600), SOL / 2);
+        assert_eq!(unlocked(6_099), SOL * 999 / 1_000);
+        assert_eq!(unlocked(6_100), SOL);
+        assert_eq!(unlocked(i64::MAX), SOL);
+    }
+
+    #[test]
+    fn stepped_unlock_releases_whole_tranches() {
+        let lock = CollateralLock { lock_seconds: 0, unlock_seconds: 1_000, schedule: UnlockSchedule::Stepped { steps: 4 } };
+        let unlocked = |now| unlocked_collateral(&lock, 1_000, 0, now).unwrap();
+        assert_eq!(unlocked(249), 0);
+        assert_eq!(unlocked(250), 250);
+        assert_eq!(unlocked(749), 500);
+        assert_eq!(unlocked(999), 750);
+        assert_eq!(unlocked(1_000), 1_000);
+    }
+
+    #[test]
+    fn zero_unlock_duration_releases_everything_at_once() {
+        let lock = CollateralLock { lock_seconds: 100, unlock_seconds: 0, schedule: UnlockSchedule::Linear };
+        assert_eq!(unlocked_collateral(&lock, SOL, 0, 99).unwrap(), 0);
+        assert_eq!(unlocked_collateral(&lock, SOL, 0, 100).unwrap(), SOL);
+    }
+
+    #[test]
+    fn refund_shares_never_exceed_the_pool() {
+        let weights = [1, 7, 333_333, 1_000_000_007, 5];
+        let total: u64 = weights.iter().sum();
+        for pool in [0, 1, 999, 12_345_678_901] {
+            let paid: u64 = weights.iter().map(|w| refund_share(*w, total, pool).unwrap()).sum();
+            assert!(paid <= pool);
+            // Each share floors, so at most one lamport per holder is left behind
+            assert!(pool - paid < weights.len() as u64);
+        }
+        assert_eq!(refund_share(total, total, 12_345).unwrap(), 12_345);
+        assert_eq!(refund_share(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+        assert!(refund_share(1, 0, 1).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! Keccak-256 Merkle proofs for allowlists and refund snapshots.
+//!
+//! Inner nodes hash their two children in sorted order, so proofs are a flat
+//! list of sibling hashes with no left/right flags. Leaves are
+//! keccak(wallet || amount_le), or keccak(index_le || wallet || amount_le)
+//! where claims are tracked in a bitmap — off-chain tree builders must match.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+/// Leaf for a wallet and its u64 amount (allocation, refund, ...)
+pub fn leaf(wallet: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[wallet.as_ref(), &amount.to_le_bytes()]).to_bytes()
+}
+
+/// Leaf for a wallet's u64 amount at a bitmap index (refund snapshots)
+pub fn indexed_leaf(index: u32, wallet: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[&index.to_le_bytes(), wallet.as_ref(), &amount.to_le_bytes()]).to_bytes()
+}
+
+/// Whether `proof` links `leaf` to `root`
+pub fn verify(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).to_bytes()
+        } else {
+            keccak::hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+        keccak::hashv(&[&lo, &hi]).to_bytes()
+    }
+
+    /// Root of a four-leaf tree
+    fn tree(leaves: [[u8; 32]; 4]) -> [u8; 32] {
+        hash_pair(hash_pair(leaves[0], leaves[1]), hash_pair(leaves[2], leaves[3]))
+    }
+
+    fn wallets() -> [Pubkey; 4] {
+        [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()]
+    }
+
+    #[test]
+    fn single_leaf_root_needs_no_proof() {
+        let wallet = Pubkey::new_unique();
+        let root = leaf(&wallet, 42);
+        assert!(verify(&[], &root, leaf(&wallet, 42)));
+        assert!(!verify(&[], &root, leaf(&wallet, 43)));
+    }
+
+    #[test]
+    fn every_leaf_proves_against_the_root() {
+        let wallets = wallets();
+        let leaves = [0, 1, 2, 3].map(|i| leaf(&wallets[i], 1_000 * (i as u64 + 1)));
+        let root = tree(leaves);
+        let proofs = [
+            [leaves[1], hash_pair(leaves[2], leaves[3])],
+            [leaves[0], hash_pair(leaves[2], leaves[3])],
+            [leaves[3], hash_pair(leaves[0], leaves[1])],
+            [leaves[2], hash_pair(leaves[0], leaves[1])],
+        ];
+        for (i, proof) in proofs.iter().enumerate() {
+            assert!(verify(proof, &root, leaves[i]), "leaf {i}");
+        }
+    }
+
+    #[test]
+    fn tampered_leaves_and_proofs_fail() {
+        let wallets = wallets();
+        let leaves = [0, 1, 2, 3].map(|i| leaf(&wallets[i], 1_000));
+        let root = tree(leaves);
+        let proof = [leaves[1], hash_pair(leaves[2], leaves[3])];
+        // Wrong amount, wrong wallet, truncated and reordered proof
+        assert!(!verify(&proof, &root, leaf(&wallets[0], 1_001)));
+        assert!(!verify(&proof, &root, leaf(&Pubkey::new_unique(), 1_000)));
+        assert!(!verify(&proof[..1], &root, leaves[0]));
+        assert!(!verify(&[proof[1], proof[0]], &root, leaves[0]));
+    }
+
+    #[test]
+    fn indexed_leaves_bind_the_index() {
+        let wallets = wallets();
+        let leaves = [0u32, 1, 2, 3].map(|i| indexed_leaf(i, &wallets[i as usize], 7));
+        let root = tree(leaves);
+        let proof = [leaves[1], hash_pair(leaves[2], leaves[3])];
+        assert!(verify(&proof, &root, indexed_leaf(0, &wallets[0], 7)));
+        assert!(!verify(&proof, &root, indexed_leaf(1, &wallets[0], 7)));
+        assert_ne!(indexed_leaf(0, &wallets[0], 7), leaf(&wallets[0], 7));
+    }
+}
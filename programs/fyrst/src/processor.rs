@@ -0,0 +1,293 @@
+// Anchor 0.31 `#[program]` generates IDL instructions next to the program
+// module that call the deprecated `AccountInfo::realloc`, so the program
+// lives in its own module and the allow stops here.
+#![allow(deprecated)]
+
+use super::*;
+use crate::math::{BuyQuote, SellQuote};
+use crate::state::{CollateralLock, CurveBounds, FeeSchedule, HolderDiscount, LaunchBounds, LaunchProtection, SniperTax};
+
+#[program]
+pub mod fyrst {
+    use super::*;
+
+    /// Initialize protocol configuration (one-time setup)
+    pub fn init_protocol(
+        ctx: Context<InitProtocol>,
+        treasury: Pubkey,
+        ops_wallet: Pubkey,
+    ) -> Result<()> {
+        instructions::protocol::init_protocol(ctx, treasury, ops_wallet)
+    }
+
+    /// Close protocol config for migration (authority only)
+    pub fn close_config(ctx: Context<CloseConfig>) -> Result<()> {
+        instructions::protocol::close_config(ctx)
+    }
+
+    /// Launch a token atomically: mint + metadata, bonding curve, escrow
+    /// collateral and an optional initial deployer buy
+    pub fn launch_token(ctx: Context<LaunchToken>, args: LaunchTokenArgs) -> Result<()> {
+        instructions::launch::launch_token(ctx, args)
+    }
+
+    /// Release escrow back to deployer once its collateral has fully unlocked after graduation
+    pub fn release_escrow(ctx: Context<ReleaseEscrow>) -> Result<()> {
+        instructions::escrow::release_escrow(ctx)
+    }
+
+    /// Claim collateral unlocked since graduation; the locked rest stays slashable
+    pub fn claim_unlocked_collateral(ctx: Context<ClaimUnlockedCollateral>) -> Result<()> {
+        instructions::escrow::claim_unlocked_collateral(ctx)
+    }
+
+    /// Slash a dumping deployer's locked collateral to the holders (permissionless)
+    pub fn slash_escrow(ctx: Context<SlashEscrow>) -> Result<()> {
+        instructions::escrow::slash_escrow(ctx)
+    }
+
+    /// Mark a launch that missed its deadline as failed (permissionless)
+    pub fn mark_failed(ctx: Context<MarkFailed>) -> Result<()> {
+        instructions::escrow::mark_failed(ctx)
+    }
+
+    /// Expire escrow after deadline (permissionless). 50% deployer refund, 50% treasury buyback+burn.
+    pub fn expire_escrow(ctx: Context<ExpireEscrow>) -> Result<()> {
+        instructions::escrow::expire_escrow(ctx)
+    }
+
+    /// Buy tokens on the bonding curve (mints SPL tokens)
+    pub fn buy_tokens(
+        ctx: Context<BuyTokens>,
+        sol_amount: u64,
+        min_tokens_out: u64,
+    ) -> Result<()> {
+        instructions::bonding_curve::buy_tokens(ctx, sol_amount, min_tokens_out)
+    }
+
+    /// Buy an exact token amount on the bonding curve, paying at most `max_sol_in`
+    pub fn buy_exact_tokens(
+        ctx: Context<BuyTokens>,
+        token_amount: u64,
+        max_sol_in: u64,
+    ) -> Result<()> {
+        instructions::bonding_curve::buy_exact_tokens(ctx, token_amount, max_sol_in)
+    }
+
+    /// Buy during the allowlisted presale, proving `allocation` with a Merkle proof
+    pub fn presale_buy(
+        ctx: Context<BuyTokens>,
+        sol_amount: u64,
+        min_tokens_out: u64,
+        allocation: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::bonding_curve::presale_buy(ctx, sol_amount, min_tokens_out, allocation, proof)
+    }
+
+    /// Sell tokens on the bonding curve (burns SPL tokens)
+    pub fn sell_tokens(
+        ctx: Context<SellTokens>,
+        token_amount: u64,
+        min_sol_out: u64,
+    ) -> Result<()> {
+        instructions::bonding_curve::sell_tokens(ctx, token_amount, min_sol_out)
+    }
+
+    /// Sell for an exact net SOL amount, giving up at most `max_tokens_in`
+    pub fn sell_for_exact_sol(
+        ctx: Context<SellTokens>,
+        sol_out: u64,
+        max_tokens_in: u64,
+    ) -> Result<()> {
+        instructions::bonding_curve::sell_for_exact_sol(ctx, sol_out, max_tokens_in)
+    }
+
+    /// Process burn-to-refund for a buyer (permissionless — buyer claims own refund)
+    pub fn process_refund(ctx: Context<ProcessRefund>) -> Result<()> {
+        instructions::refund::process_refund(ctx)
+    }
+
+    /// Post a Merkle root of holder refund weights for a failed launch (authority only)
+    pub fn post_refund_snapshot(
+        ctx: Context<PostRefundSnapshot>,
+        root: [u8; 32],
+        leaf_count: u32,
+        total_weight: u64,
+    ) -> Result<()> {
+        instructions::snapshot::post_refund_snapshot(ctx, root, leaf_count, total_weight)
+    }
+
+    /// Pay one holder's snapshot refund by Merkle proof (permissionless, no burn)
+    pub fn claim_snapshot_refund(ctx: Context<ClaimSnapshotRefund>, claim: SnapshotClaim) -> Result<()> {
+        instructions::snapshot::claim_snapshot_refund(ctx, claim)
+    }
+
+    /// Push snapshot refunds to holder wallets passed as remaining accounts (permissionless)
+    pub fn push_snapshot_refunds<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PushSnapshotRefunds<'info>>,
+        claims: Vec<SnapshotClaim>,
+    ) -> Result<()> {
+        instructions::snapshot::push_snapshot_refunds(ctx, claims)
+    }
+
+    /// Close a refund snapshot after its claim period, sweeping unclaimed refunds (permissionless)
+    pub fn close_refund_snapshot(ctx: Context<CloseRefundSnapshot>) -> Result<()> {
+        instructions::snapshot::close_refund_snapshot(ctx)
+    }
+
+    /// Quote an exact-SOL-in buy (read-only, result via return data)
+    pub fn quote_buy(ctx: Context<QuoteCurve>, sol_amount: u64) -> Result<BuyQuote> {
+        instructions::quote::quote_buy(ctx, sol_amount)
+    }
+
+    /// Quote an exact-tokens-in sell (read-only, result via return data)
+    pub fn quote_sell(ctx: Context<QuoteCurve>, token_amount: u64) -> Result<SellQuote> {
+        instructions::quote::quote_sell(ctx, token_amount)
+    }
+
+    /// Quote a burn-to-refund payout (read-only, result via return data)
+    pub fn quote_refund(ctx: Context<QuoteRefund>, token_amount: u64) -> Result<RefundQuote> {
+        instructions::quote::quote_refund(ctx, token_amount)
+    }
+
+    /// Quote the deployer's currently claimable fees (read-only, result via return data)
+    pub fn quote_claimable_fees(ctx: Context<QuoteCurve>) -> Result<u64> {
+        instructions::quote::quote_claimable_fees(ctx)
+    }
+
+    /// Update protocol treasury (authority only)
+    pub fn update_treasury(
+        ctx: Context<UpdateTreasury>,
+        new_treasury: Pubkey,
+    ) -> Result<()> {
+        instructions::protocol::update_treasury(ctx, new_treasury)
+    }
+
+    /// Sweep protocol fees accrued on curves (passed as remaining accounts)
+    /// to the treasury and ops wallet (permissionless)
+    pub fn sweep_protocol_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SweepProtocolFees<'info>>,
+    ) -> Result<()> {
+        instructions::protocol::sweep_protocol_fees(ctx)
+    }
+
+    /// Claim accumulated trade fees (deployer only)
+    pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
+        instructions::protocol::claim_fees(ctx)
+    }
+
+    /// Update operations wallet (authority only)
+    pub fn update_ops_wallet(
+        ctx: Context<UpdateTreasury>,
+        new_ops_wallet: Pubkey,
+    ) -> Result<()> {
+        instructions::protocol::update_ops_wallet(ctx, new_ops_wallet)
+    }
+
+    /// Update graduation threshold for future launches (authority only)
+    pub fn update_graduation_threshold(
+        ctx: Context<UpdateTreasury>,
+        new_threshold: u64,
+    ) -> Result<()> {
+        instructions::protocol::update_graduation_threshold(ctx, new_threshold)
+    }
+
+    /// Update the fee schedule for future launches (authority only, bounded by ceilings)
+    pub fn update_fee_schedule(
+        ctx: Context<UpdateTreasury>,
+        new_schedule: FeeSchedule,
+    ) -> Result<()> {
+        instructions::protocol::update_fee_schedule(ctx, new_schedule)
+    }
+
+    /// Update the parameter bounds for deployer-chosen curve shapes (authority only)
+    pub fn update_curve_bounds(
+        ctx: Context<UpdateTreasury>,
+        new_bounds: CurveBounds,
+    ) -> Result<()> {
+        instructions::protocol::update_curve_bounds(ctx, new_bounds)
+    }
+
+    /// Update the bounds for deployer-chosen supply and starting reserves (authority only)
+    pub fn update_launch_bounds(
+        ctx: Context<UpdateTreasury>,
+        new_bounds: LaunchBounds,
+    ) -> Result<()> {
+        instructions::protocol::update_launch_bounds(ctx, new_bounds)
+    }
+
+    /// Update the launch-protection window and per-wallet buy cap (authority only)
+    pub fn update_launch_protection(
+        ctx: Context<UpdateTreasury>,
+        new_protection: LaunchProtection,
+    ) -> Result<()> {
+        instructions::protocol::update_launch_protection(ctx, new_protection)
+    }
+
+    /// Update the time-decaying sniper tax on early buys (authority only)
+    pub fn update_sniper_tax(ctx: Context<UpdateTreasury>, new_tax: SniperTax) -> Result<()> {
+        instructions::protocol::update_sniper_tax(ctx, new_tax)
+    }
+
+    /// Update the post-graduation collateral lock and unlock schedule (authority only)
+    pub fn update_collateral_lock(ctx: Context<UpdateTreasury>, new_lock: CollateralLock) -> Result<()> {
+        instructions::protocol::update_collateral_lock(ctx, new_lock)
+    }
+
+    /// Update the share of acquired tokens a deployer may sell before slashing (authority only)
+    pub fn update_dump_threshold(ctx: Context<UpdateTreasury>, new_threshold_bps: u64) -> Result<()> {
+        instructions::protocol::update_dump_threshold(ctx, new_threshold_bps)
+    }
+
+    /// Update the $FYRST holder deploy fee discount (authority only)
+    pub fn update_holder_discount(
+        ctx: Context<UpdateTreasury>,
+        new_discount: HolderDiscount,
+    ) -> Result<()> {
+        instructions::protocol::update_holder_discount(ctx, new_discount)
+    }
+
+    /// Grant, change or revoke a deployer's deploy fee waiver (authority only)
+    pub fn set_deploy_fee_waiver(
+        ctx: Context<SetDeployFeeWaiver>,
+        deployer: Pubkey,
+        discount_bps: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::protocol::set_deploy_fee_waiver(ctx, deployer, discount_bps, expires_at)
+    }
+
+    /// Override a live curve's graduation threshold (authority only, audited via event)
+    pub fn set_curve_graduation_threshold(
+        ctx: Context<SetCurveGraduationThreshold>,
+        new_threshold: u64,
+    ) -> Result<()> {
+        instructions::protocol::set_curve_graduation_threshold(ctx, new_threshold)
+    }
+
+    /// Commit SOL to a curve's batch-auction opening
+    pub fn commit_opening(ctx: Context<CommitOpening>, sol_amount: u64) -> Result<()> {
+        instructions::opening::commit_opening(ctx, sol_amount)
+    }
+
+    /// Clear a closed batch opening at one uniform curve price (permissionless)
+    pub fn settle_opening(ctx: Context<SettleOpening>) -> Result<()> {
+        instructions::opening::settle_opening(ctx)
+    }
+
+    /// Claim batch-opening tokens pro-rata to the SOL committed
+    pub fn claim_opening(ctx: Context<ClaimOpening>) -> Result<()> {
+        instructions::opening::claim_opening(ctx)
+    }
+
+    /// Graduate a bonding curve when reserve meets its threshold
+    pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
+        instructions::protocol::graduate(ctx)
+    }
+
+    /// Migrate graduated token to Raydium CPMM DEX (permissionless)
+    pub fn graduate_to_dex(ctx: Context<GraduateToDex>) -> Result<()> {
+        instructions::graduation::graduate_to_dex(ctx)
+    }
+}
@@ -1,4 +1,7 @@
 use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::curve::CurvePosition;
+use crate::errors::FyrstError;
 
 /// Escrow vault account storing deployer collateral
 #[account]
@@ -8,26 +11,452 @@ pub struct EscrowVault {
     pub deployer: Pubkey,
     /// Token mint address
     pub token_mint: Pubkey,
+    /// Bonding curve this escrow backs
+    pub bonding_curve: Pubkey,
     /// Collateral amount in lamports
     pub collateral_amount: u64,
     /// Timestamp when escrow was created
     pub created_at: i64,
-    /// Deadline timestamp (created_at + duration_seconds)
+    /// Deadline timestamp (curve's trading_opens_at + duration_seconds)
     pub deadline_timestamp: i64,
     /// Whether the escrow has been released back to deployer
     pub released: bool,
     /// Bump seed for PDA
     pub bump: u8,
+    /// Post-graduation lock snapshotted from `ProtocolConfig` at launch
+    pub collateral_lock: CollateralLock,
+    /// Unlocked collateral already returned via `claim_unlocked_collateral`
+    pub collateral_claimed: u64,
+    /// Locked collateral moved to the holders by `slash_escrow`
+    pub collateral_slashed: u64,
+    /// Refund snapshot posted for this launch (default = burn-to-refund)
+    pub refund_snapshot: Pubkey,
 }
 
 impl EscrowVault {
     pub const LEN: usize = 8  // discriminator
         + 32  // deployer
         + 32  // token_mint
+        + 32  // bonding_curve
         + 8   // collateral_amount
         + 8   // created_at
         + 8   // deadline_timestamp
         + 1   // released
+        + 1   // bump
+        + CollateralLock::LEN // collateral_lock
+        + 8   // collateral_claimed
+        + 8   // collateral_slashed
+        + 32; // refund_snapshot
+}
+
+/// How collateral is returned once the post-graduation safety period ends
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnlockSchedule {
+    /// Unlocks continuously over the unlock duration
+    #[default]
+    Linear,
+    /// Unlocks in `steps` equal tranches spread over the unlock duration
+    Stepped { steps: u8 },
+}
+
+impl UnlockSchedule {
+    pub const LEN: usize = 1  // variant tag
+        + 1;  // steps
+}
+
+/// Post-graduation collateral lock: nothing unlocks during the safety period,
+/// then collateral is returned over the unlock duration. Whatever is still
+/// locked stays in the escrow and remains slashable.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CollateralLock {
+    /// Safety period in seconds after graduation (0 = unlock starts at graduation)
+    pub lock_seconds: i64,
+    /// Seconds after the safety period until all collateral is unlocked
+    /// (0 = everything unlocks when the safety period ends)
+    pub unlock_seconds: i64,
+    /// Unlock shape
+    pub schedule: UnlockSchedule,
+}
+
+impl CollateralLock {
+    pub const LEN: usize = 8  // lock_seconds
+        + 8   // unlock_seconds
+        + UnlockSchedule::LEN; // schedule
+
+    /// Check the lock against the hard-coded ceilings
+    pub fn validate(&self) -> Result<()> {
+        let schedule_valid = match self.schedule {
+            UnlockSchedule::Linear => true,
+            UnlockSchedule::Stepped { steps } => (1..=MAX_UNLOCK_STEPS).contains(&steps),
+        };
+        require!(
+            (0..=MAX_COLLATERAL_LOCK).contains(&self.lock_seconds)
+                && (0..=MAX_COLLATERAL_UNLOCK).contains(&self.unlock_seconds)
+                && schedule_valid,
+            FyrstError::InvalidCollateralLock
+        );
+        Ok(())
+    }
+}
+
+impl Default for CollateralLock {
+    fn default() -> Self {
+        Self {
+            lock_seconds: COLLATERAL_LOCK_SECONDS,
+            unlock_seconds: COLLATERAL_UNLOCK_SECONDS,
+            schedule: UnlockSchedule::Linear,
+        }
+    }
+}
+
+/// Protocol fee schedule. Lives in `ProtocolConfig` and is snapshotted into
+/// each `BondingCurve` at launch, so updates only affect future launches.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeSchedule {
+    /// Trade fee in basis points, charged on every buy/sell
+    pub trade_fee_bps: u64,
+    /// Deployer share of trade volume in basis points (carved out of the trade fee)
+    pub deployer_fee_bps: u64,
+    /// Operations wallet share of protocol fees in basis points
+    pub ops_share_bps: u64,
+    /// Protocol fee in basis points, charged on top of the trade fee
+    pub protocol_fee_bps: u64,
+    /// Deploy fee in lamports
+    pub deploy_fee: u64,
+    /// Minimum escrow collateral in lamports
+    pub min_collateral: u64,
+}
+
+impl FeeSchedule {
+    pub const LEN: usize = 8  // trade_fee_bps
+        + 8   // deployer_fee_bps
+        + 8   // ops_share_bps
+        + 8   // protocol_fee_bps
+        + 8   // deploy_fee
+        + 8;  // min_collateral
+
+    /// Check the schedule, and the sniper tax charged on top of it, against
+    /// the hard-coded safety ceilings
+    pub fn validate(&self, sniper_tax: &SniperTax) -> Result<()> {
+        require!(
+            self.trade_fee_bps <= MAX_TRADE_FEE_BPS
+                && self.deployer_fee_bps <= self.trade_fee_bps
+                && self.ops_share_bps <= BPS_DENOMINATOR
+                && self.protocol_fee_bps <= MAX_PROTOCOL_FEE_BPS
+                && self.trade_fee_bps + self.protocol_fee_bps <= MAX_TOTAL_FEE_BPS
+                && sniper_tax.start_fee_bps + self.protocol_fee_bps <= MAX_SNIPER_FEE_BPS
+                && self.deploy_fee <= MAX_DEPLOY_FEE
+                && self.min_collateral <= MAX_MIN_COLLATERAL,
+            FyrstError::InvalidFeeSchedule
+        );
+        Ok(())
+    }
+}
+
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        Self {
+            trade_fee_bps: TRADE_FEE_BPS,
+            deployer_fee_bps: DEPLOYER_FEE_BPS,
+            ops_share_bps: OPS_SHARE_BPS,
+            protocol_fee_bps: PROTOCOL_FEE_BPS,
+            deploy_fee: DEPLOY_FEE,
+            min_collateral: MIN_COLLATERAL,
+        }
+    }
+}
+
+/// Bonding curve price shape, chosen by the deployer at launch within the
+/// protocol's `CurveBounds`. Prices are lamports per base token unit scaled by
+/// `PRICE_SCALE`; see `crate::curve` for the pricing math.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CurveKind {
+    /// pump.fun-style x*y=k on the virtual reserves
+    #[default]
+    ConstantProduct,
+    /// Price rises linearly from `start_price` to `end_price` over the curve allocation
+    Linear { start_price: u64, end_price: u64 },
+    /// Price grows as start_price * e^(growth * sold / allocation); `growth_wad` in WAD
+    Exponential { start_price: u64, growth_wad: u64 },
+    /// Piecewise-constant price: `count` levels splitting the allocation evenly
+    Step { prices: [u64; 8], count: u8 },
+}
+
+impl CurveKind {
+    pub const LEN: usize = 1  // variant tag
+        + 8 * MAX_CURVE_STEPS // largest variant: step prices
+        + 1;  // step count
+
+    /// Check the shape against the protocol's curve bounds
+    pub fn validate(&self, bounds: &CurveBounds) -> Result<()> {
+        let in_range = |price: u64| (bounds.min_price..=bounds.max_price).contains(&price);
+        let valid = match *self {
+            CurveKind::ConstantProduct => true,
+            CurveKind::Linear { start_price, end_price } => {
+                in_range(start_price) && in_range(end_price) && start_price <= end_price
+            }
+            CurveKind::Exponential { start_price, growth_wad } => {
+                in_range(start_price)
+                    && (MIN_CURVE_GROWTH_WAD..=bounds.max_growth_wad).contains(&growth_wad)
+            }
+            CurveKind::Step { prices, count } => {
+                let count = count as usize;
+                (1..=MAX_CURVE_STEPS).contains(&count)
+                    && prices[..count].iter().all(|p| in_range(*p))
+                    && prices[..count].windows(2).all(|w| w[0] <= w[1])
+                    && prices[count..].iter().all(|p| *p == 0)
+            }
+        };
+        require!(valid, FyrstError::InvalidCurve);
+        Ok(())
+    }
+}
+
+/// How burn-to-refund payouts are split between holders, chosen at launch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RefundMode {
+    /// Pro-rata to tokens burned (any holder, however the tokens were acquired)
+    #[default]
+    TokenShare,
+    /// Pro-rata to net SOL contributed on the curve, recorded in each
+    /// buyer's `Position` (secondary transfers earn nothing)
+    Contribution,
+}
+
+impl RefundMode {
+    pub const LEN: usize = 1; // variant tag
+}
+
+/// Protocol-approved parameter bounds for deployer-chosen curve shapes
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CurveBounds {
+    /// Lowest marginal price a curve may use (PRICE_SCALE units)
+    pub min_price: u64,
+    /// Highest marginal price a curve may use (PRICE_SCALE units)
+    pub max_price: u64,
+    /// Highest exponential growth exponent (WAD)
+    pub max_growth_wad: u64,
+}
+
+impl CurveBounds {
+    pub const LEN: usize = 8  // min_price
+        + 8   // max_price
+        + 8;  // max_growth_wad
+
+    /// Check the bounds against the hard-coded math ceilings
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_price > 0
+                && self.min_price <= self.max_price
+                && self.max_price <= MAX_CURVE_PRICE
+                && self.max_growth_wad >= MIN_CURVE_GROWTH_WAD
+                && self.max_growth_wad <= MAX_CURVE_GROWTH_WAD,
+            FyrstError::InvalidCurve
+        );
+        Ok(())
+    }
+}
+
+impl Default for CurveBounds {
+    fn default() -> Self {
+        Self {
+            min_price: CURVE_MIN_PRICE,
+            max_price: CURVE_MAX_PRICE,
+            max_growth_wad: CURVE_MAX_GROWTH_WAD,
+        }
+    }
+}
+
+/// Protocol bounds for deployer-chosen supply and starting reserves
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LaunchBounds {
+    /// Total supply range in whole tokens
+    pub min_total_supply: u64,
+    pub max_total_supply: u64,
+    /// Token decimals range
+    pub min_decimals: u8,
+    pub max_decimals: u8,
+    /// Initial virtual SOL reserves range (lamports)
+    pub min_virtual_sol: u64,
+    pub max_virtual_sol: u64,
+    /// Curve share of supply range in basis points
+    pub min_curve_token_bps: u64,
+    pub max_curve_token_bps: u64,
+}
+
+impl LaunchBounds {
+    pub const LEN: usize = 8  // min_total_supply
+        + 8   // max_total_supply
+        + 1   // min_decimals
+        + 1   // max_decimals
+        + 8   // min_virtual_sol
+        + 8   // max_virtual_sol
+        + 8   // min_curve_token_bps
+        + 8;  // max_curve_token_bps
+
+    /// Check the bounds are well-formed and within the hard-coded ceilings
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_total_supply > 0
+                && self.min_total_supply <= self.max_total_supply
+                && self.min_decimals <= self.max_decimals
+                && self.max_decimals <= MAX_TOKEN_DECIMALS
+                && self.min_virtual_sol > 0
+                && self.min_virtual_sol <= self.max_virtual_sol
+                && self.min_curve_token_bps > 0
+                && self.min_curve_token_bps <= self.max_curve_token_bps
+                && self.max_curve_token_bps <= BPS_DENOMINATOR,
+            FyrstError::InvalidLaunchParams
+        );
+        Ok(())
+    }
+
+    /// Whether the launch parameters fall within these bounds
+    pub fn contains(&self, total_supply: u64, decimals: u8, virtual_sol: u64, curve_token_bps: u64) -> bool {
+        (self.min_total_supply..=self.max_total_supply).contains(&total_supply)
+            && (self.min_decimals..=self.max_decimals).contains(&decimals)
+            && (self.min_virtual_sol..=self.max_virtual_sol).contains(&virtual_sol)
+            && (self.min_curve_token_bps..=self.max_curve_token_bps).contains(&curve_token_bps)
+    }
+}
+
+impl Default for LaunchBounds {
+    fn default() -> Self {
+        Self {
+            min_total_supply: LAUNCH_MIN_TOTAL_SUPPLY,
+            max_total_supply: LAUNCH_MAX_TOTAL_SUPPLY,
+            min_decimals: LAUNCH_MIN_DECIMALS,
+            max_decimals: LAUNCH_MAX_DECIMALS,
+            min_virtual_sol: LAUNCH_MIN_VIRTUAL_SOL,
+            max_virtual_sol: LAUNCH_MAX_VIRTUAL_SOL,
+            min_curve_token_bps: LAUNCH_MIN_CURVE_TOKEN_BPS,
+            max_curve_token_bps: LAUNCH_MAX_CURVE_TOKEN_BPS,
+        }
+    }
+}
+
+/// Launch-protection window: right after a curve launches, each wallet's
+/// cumulative buys are capped so bots cannot snipe the curve in the first slots
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LaunchProtection {
+    /// Window length in seconds from curve creation (0 = protection disabled)
+    pub window_seconds: i64,
+    /// Gross SOL (fees included) a single wallet may spend on buys during the window
+    pub max_sol_per_wallet: u64,
+}
+
+impl LaunchProtection {
+    pub const LEN: usize = 8  // window_seconds
+        + 8;  // max_sol_per_wallet
+
+    /// Check the window against the hard-coded ceiling
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            (0..=MAX_PROTECTION_WINDOW).contains(&self.window_seconds)
+                && (self.window_seconds == 0 || self.max_sol_per_wallet > 0),
+            FyrstError::InvalidProtection
+        );
+        Ok(())
+    }
+}
+
+impl Default for LaunchProtection {
+    fn default() -> Self {
+        Self {
+            window_seconds: PROTECTION_WINDOW_SECONDS,
+            max_sol_per_wallet: PROTECTION_MAX_SOL_PER_WALLET,
+        }
+    }
+}
+
+/// How the sniper tax falls from its launch value to zero
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TaxDecay {
+    /// Falls linearly to zero over the tax duration
+    #[default]
+    Linear,
+    /// Halves every `half_life_seconds`, cut to zero at the end of the tax duration
+    Exponential { half_life_seconds: i64 },
+}
+
+impl TaxDecay {
+    pub const LEN: usize = 1  // variant tag
+        + 8;  // half_life_seconds
+}
+
+/// Time-decaying extra fee on early buys. The tax is paid into the curve's
+/// holder pool (refunds / DEX liquidity), never to the deployer.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SniperTax {
+    /// Total buy fee at launch in basis points, trade fee included (0 = tax disabled)
+    pub start_fee_bps: u64,
+    /// Seconds after launch until the buy fee is back to the normal trade fee
+    pub duration_seconds: i64,
+    /// Decay shape
+    pub decay: TaxDecay,
+}
+
+impl SniperTax {
+    pub const LEN: usize = 8  // start_fee_bps
+        + 8   // duration_seconds
+        + TaxDecay::LEN; // decay
+
+    /// Check the tax against the hard-coded ceilings
+    pub fn validate(&self) -> Result<()> {
+        if self.start_fee_bps == 0 {
+            return Ok(());
+        }
+        let decay_valid = match self.decay {
+            TaxDecay::Linear => true,
+            TaxDecay::Exponential { half_life_seconds } => half_life_seconds > 0,
+        };
+        require!(
+            self.start_fee_bps <= MAX_SNIPER_FEE_BPS
+                && (1..=MAX_SNIPER_TAX_DURATION).contains(&self.duration_seconds)
+                && decay_valid,
+            FyrstError::InvalidSniperTax
+        );
+        Ok(())
+    }
+}
+
+/// $FYRST holder discount on the deploy fee
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HolderDiscount {
+    /// $FYRST mint (default pubkey = discount disabled)
+    pub fyrst_mint: Pubkey,
+    /// Minimum $FYRST balance (base units) required to qualify
+    pub min_balance: u64,
+    /// Discount off the deploy fee in basis points
+    pub discount_bps: u64,
+}
+
+impl HolderDiscount {
+    pub const LEN: usize = 32  // fyrst_mint
+        + 8   // min_balance
+        + 8;  // discount_bps
+}
+
+/// Authority-granted deploy fee discount for a single deployer
+/// (e.g. from the off-chain reputation score)
+#[account]
+#[derive(Default)]
+pub struct DeployFeeWaiver {
+    /// Deployer the waiver applies to
+    pub deployer: Pubkey,
+    /// Discount off the deploy fee in basis points (10_000 = full exemption)
+    pub discount_bps: u64,
+    /// Unix timestamp after which the waiver lapses (0 = no expiry)
+    pub expires_at: i64,
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl DeployFeeWaiver {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // deployer
+        + 8   // discount_bps
+        + 8   // expires_at
         + 1;  // bump
 }
 
@@ -57,7 +486,7 @@ pub struct BondingCurve {
     pub deployer: Pubkey,
     /// Total SOL ever collected (for pro-rata refund denominator, never decreases)
     pub total_sol_collected: u64,
-    /// High-water mark of reserve_balance (capped at GRADUATION_THRESHOLD)
+    /// High-water mark of reserve_balance (capped at graduation_threshold)
     pub max_reserve_reached: u64,
     /// Total deployer fees accumulated (50% of trade fees)
     pub total_deployer_fees: u64,
@@ -69,6 +498,75 @@ pub struct BondingCurve {
     pub dex_migrated: bool,
     /// Raydium CPMM pool address (set after graduation migration)
     pub raydium_pool: Pubkey,
+    /// Escrow vault holding this launch's deployer collateral
+    pub escrow_vault: Pubkey,
+    /// Collateral locked in the escrow at launch (lamports)
+    pub collateral_amount: u64,
+    /// Fee schedule snapshotted from `ProtocolConfig` at launch
+    pub fee_schedule: FeeSchedule,
+    /// Graduation threshold snapshotted from `ProtocolConfig` at launch (lamports)
+    pub graduation_threshold: u64,
+    /// Ops wallet fees accrued on the curve, pending `sweep_protocol_fees`
+    pub accrued_ops_fees: u64,
+    /// Treasury fees accrued on the curve, pending `sweep_protocol_fees`
+    pub accrued_treasury_fees: u64,
+    /// Price shape chosen at launch
+    pub curve_kind: CurveKind,
+    /// Timestamp when the curve was launched
+    pub created_at: i64,
+    /// Launch protection snapshotted from `ProtocolConfig` at launch
+    pub launch_protection: LaunchProtection,
+    /// Sniper tax snapshotted from `ProtocolConfig` at launch
+    pub sniper_tax: SniperTax,
+    /// Sniper tax collected on the curve for holders: paid out with refunds,
+    /// or added to DEX liquidity at migration
+    pub holder_pool: u64,
+    /// Timestamp when public trading opens (launch time unless scheduled later
+    /// or preceded by a presale)
+    pub trading_opens_at: i64,
+    /// Merkle root of the presale allowlist (all zeros = no presale)
+    pub presale_root: [u8; 32],
+    /// Timestamp when the presale starts; it runs until `trading_opens_at`
+    pub presale_starts_at: i64,
+    /// Timestamp when batch-opening commits start (0 = no batch opening);
+    /// commits run until `trading_opens_at`
+    pub opening_starts_at: i64,
+    /// SOL committed to the batch opening (held on the curve PDA); reduced by
+    /// each claim after settlement
+    pub opening_total_sol: u64,
+    /// Tokens cleared by `settle_opening` and not yet claimed by committers
+    pub opening_tokens: u64,
+    /// Whether the batch opening has been settled
+    pub opening_settled: bool,
+    /// Per-wallet holding cap in basis points of total supply while on the
+    /// curve (0 = no cap; lifted at graduation)
+    pub max_wallet_bps: u64,
+    /// Timestamp when the curve graduated (0 while on the curve)
+    pub graduated_at: i64,
+    /// Tokens the deployer acquired from the curve (buys and opening claims)
+    pub deployer_tokens_bought: u64,
+    /// Tokens the deployer sold back to the curve
+    pub deployer_tokens_sold: u64,
+    /// Dump threshold snapshotted from `ProtocolConfig` at launch (basis points
+    /// of `deployer_tokens_bought`)
+    pub dump_threshold_bps: u64,
+    /// Whether the deployer has sold beyond the dump threshold
+    pub dumped: bool,
+    /// Whether the escrow has been slashed (refunds open to holders)
+    pub slashed: bool,
+    /// Slashed collateral held on the curve PDA, paid out pro-rata with refunds
+    pub slashed_collateral: u64,
+    /// Tokens the deployer burned for refunds (left their wallet, but not sold)
+    pub deployer_tokens_refunded: u64,
+    /// Escrow deadline snapshotted at launch: the curve fails if it has not
+    /// graduated by then
+    pub deadline_timestamp: i64,
+    /// Whether `mark_failed` has recorded the failure
+    pub failed: bool,
+    /// How refunds are split between holders
+    pub refund_mode: RefundMode,
+    /// Sum of every position's `net_sol_contributed` (contribution refund denominator)
+    pub total_net_contributed: u64,
 }
 
 impl BondingCurve {
@@ -89,7 +587,162 @@ impl BondingCurve {
         + 8   // claimed_deployer_fees
         + 1   // bump
         + 1   // dex_migrated
-        + 32; // raydium_pool
+        + 32  // raydium_pool
+        + 32  // escrow_vault
+        + 8   // collateral_amount
+        + FeeSchedule::LEN // fee_schedule
+        + 8   // graduation_threshold
+        + 8   // accrued_ops_fees
+        + 8   // accrued_treasury_fees
+        + CurveKind::LEN // curve_kind
+        + 8   // created_at
+        + LaunchProtection::LEN // launch_protection
+        + SniperTax::LEN // sniper_tax
+        + 8   // holder_pool
+        + 8   // trading_opens_at
+        + 32  // presale_root
+        + 8   // presale_starts_at
+        + 8   // opening_starts_at
+        + 8   // opening_total_sol
+        + 8   // opening_tokens
+        + 1   // opening_settled
+        + 8   // max_wallet_bps
+        + 8   // graduated_at
+        + 8   // deployer_tokens_bought
+        + 8   // deployer_tokens_sold
+        + 8   // dump_threshold_bps
+        + 1   // dumped
+        + 1   // slashed
+        + 8   // slashed_collateral
+        + 8   // deployer_tokens_refunded
+        + 8   // deadline_timestamp
+        + 1   // failed
+        + RefundMode::LEN // refund_mode
+        + 8;  // total_net_contributed
+
+    /// Whether per-wallet buy caps still apply at `now` (window counts from the open time)
+    pub fn protection_active(&self, now: i64) -> bool {
+        self.launch_protection.window_seconds > 0
+            && now < self.trading_opens_at.saturating_add(self.launch_protection.window_seconds)
+    }
+
+    /// Whether allowlisted presale buys are accepted at `now`
+    pub fn presale_active(&self, now: i64) -> bool {
+        self.presale_root != [0u8; 32]
+            && now >= self.presale_starts_at
+            && now < self.trading_opens_at
+    }
+
+    /// Largest token balance a wallet may reach by buying, if capped
+    pub fn max_wallet_tokens(&self) -> Result<Option<u64>> {
+        if self.max_wallet_bps == 0 || self.graduated {
+            return Ok(None);
+        }
+        crate::math::apply_bps(self.token_total_supply, self.max_wallet_bps).map(Some)
+    }
+
+    /// Whether the launch opens with a batch auction
+    pub fn has_batch_opening(&self) -> bool {
+        self.opening_starts_at > 0
+    }
+
+    /// Whether batch-opening commits are accepted at `now`
+    pub fn opening_active(&self, now: i64) -> bool {
+        self.has_batch_opening()
+            && !self.opening_settled
+            && now >= self.opening_starts_at
+            && now < self.trading_opens_at
+    }
+
+    /// Whether the launch has failed: marked, or past its deadline without
+    /// graduating (failure applies from the deadline even before `mark_failed`)
+    pub fn has_failed(&self, now: i64) -> bool {
+        self.failed || (!self.graduated && now >= self.deadline_timestamp)
+    }
+
+    /// Reject trades before the scheduled open time (and, with a batch
+    /// opening, before it has been settled), once the escrow is slashed (new
+    /// buyers would dilute holders' refunds) and once the launch has failed
+    pub fn require_open(&self, now: i64) -> Result<()> {
+        require!(!self.slashed, FyrstError::LaunchSlashed);
+        require!(!self.has_failed(now), FyrstError::LaunchFailed);
+        require!(
+            now >= self.trading_opens_at && (!self.has_batch_opening() || self.opening_settled),
+            FyrstError::TradingNotOpen
+        );
+        Ok(())
+    }
+
+    /// Whether holders can burn-to-refund at `now`: once the launch has
+    /// failed, or any time once the escrow is slashed
+    pub fn refunds_open(&self, now: i64) -> bool {
+        self.slashed || self.has_failed(now)
+    }
+
+    /// Whether refund claims may still be outstanding: tokens in circulation,
+    /// or unrefunded contributions in `Contribution` mode
+    pub fn refunds_outstanding(&self) -> bool {
+        match self.refund_mode {
+            RefundMode::TokenShare => self.current_supply > 0,
+            RefundMode::Contribution => self.total_net_contributed > 0,
+        }
+    }
+
+    /// Credit SOL paid into the curve, and the tokens it bought, to a position
+    /// and the SOL to the curve-wide total
+    pub fn add_contribution(&mut self, position: &mut Position, sol: u64, tokens: u64) -> Result<()> {
+        position.credit(sol, tokens)?;
+        self.total_net_contributed = self
+            .total_net_contributed
+            .checked_add(sol)
+            .ok_or(FyrstError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Debit the contribution backing `tokens` sold back by a position from
+    /// the position and the curve-wide total; returns the amount removed
+    pub fn sell_contribution(&mut self, position: &mut Position, tokens: u64) -> Result<u64> {
+        let removed = position.contribution_for(tokens)?;
+        position.net_sol_contributed -= removed;
+        position.tokens_held -= tokens.min(position.tokens_held);
+        self.total_net_contributed = self
+            .total_net_contributed
+            .checked_sub(removed)
+            .ok_or(FyrstError::MathOverflow)?;
+        Ok(removed)
+    }
+
+    /// Debit a position's whole contribution (paid out by a refund) from the
+    /// position and the curve-wide total; returns the amount removed
+    pub fn clear_contribution(&mut self, position: &mut Position) -> Result<u64> {
+        let removed = position.net_sol_contributed;
+        position.net_sol_contributed = 0;
+        position.tokens_held = 0;
+        self.total_net_contributed = self
+            .total_net_contributed
+            .checked_sub(removed)
+            .ok_or(FyrstError::MathOverflow)?;
+        Ok(removed)
+    }
+
+    /// Whether the deployer's sales are still watched for dumping at `now`:
+    /// on the curve and through the post-graduation safety period
+    pub fn dump_window_active(&self, lock: &CollateralLock, now: i64) -> bool {
+        !self.graduated || now < self.graduated_at.saturating_add(lock.lock_seconds)
+    }
+
+    /// Snapshot of the state the curve's pricing shape quotes against
+    pub fn position(&self) -> Result<CurvePosition> {
+        Ok(CurvePosition {
+            virtual_token_reserves: self.virtual_token_reserves,
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            sold: self.current_supply,
+            allocation: self
+                .current_supply
+                .checked_add(self.real_token_reserves)
+                .ok_or(FyrstError::MathOverflow)?,
+        })
+    }
 }
 
 /// Protocol configuration (singleton PDA)
@@ -102,10 +755,26 @@ pub struct ProtocolConfig {
     pub treasury: Pubkey,
     /// Operations wallet for service revenue
     pub ops_wallet: Pubkey,
-    /// Graduation threshold in lamports
+    /// Graduation threshold in lamports applied to new launches
     pub graduation_threshold: u64,
     /// Bump seed for PDA
     pub bump: u8,
+    /// Fee schedule applied to new launches
+    pub fee_schedule: FeeSchedule,
+    /// $FYRST holder discount on the deploy fee
+    pub holder_discount: HolderDiscount,
+    /// Parameter bounds for deployer-chosen curve shapes
+    pub curve_bounds: CurveBounds,
+    /// Bounds for deployer-chosen supply and starting reserves
+    pub launch_bounds: LaunchBounds,
+    /// Launch protection applied to new launches
+    pub launch_protection: LaunchProtection,
+    /// Sniper tax applied to new launches
+    pub sniper_tax: SniperTax,
+    /// Post-graduation collateral lock applied to new launches
+    pub collateral_lock: CollateralLock,
+    /// Dump threshold applied to new launches (basis points)
+    pub dump_threshold_bps: u64,
 }
 
 impl ProtocolConfig {
@@ -114,6 +783,171 @@ impl ProtocolConfig {
         + 32  // treasury
         + 32  // ops_wallet
         + 8   // graduation_threshold
+        + 1   // bump
+        + FeeSchedule::LEN // fee_schedule
+        + HolderDiscount::LEN // holder_discount
+        + CurveBounds::LEN // curve_bounds
+        + LaunchBounds::LEN // launch_bounds
+        + LaunchProtection::LEN // launch_protection
+        + SniperTax::LEN // sniper_tax
+        + CollateralLock::LEN // collateral_lock
+        + 8;  // dump_threshold_bps
+}
+
+/// A wallet's SOL committed to a curve's batch-auction opening
+/// (PDA per curve + owner, closed when the tokens are claimed)
+#[account]
+#[derive(Default)]
+pub struct OpeningCommit {
+    /// Bonding curve this commit is for
+    pub bonding_curve: Pubkey,
+    /// Committing wallet
+    pub owner: Pubkey,
+    /// Total SOL committed (lamports, fees included)
+    pub sol_committed: u64,
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl OpeningCommit {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // bonding_curve
+        + 32  // owner
+        + 8   // sol_committed
         + 1;  // bump
 }
 
+/// Merkle snapshot of holder refund weights for a failed or slashed launch
+/// (PDA per escrow). Holds the refundable SOL and pays each leaf once.
+#[account]
+#[derive(Default)]
+pub struct RefundSnapshot {
+    /// Escrow vault the snapshot was posted to
+    pub escrow_vault: Pubkey,
+    /// Bonding curve the refunds are for
+    pub bonding_curve: Pubkey,
+    /// Merkle root over indexed_leaf(index, holder, weight)
+    pub root: [u8; 32],
+    /// Number of leaves (bits in `claimed`)
+    pub leaf_count: u32,
+    /// Sum of every leaf's weight (token balance or net SOL contributed)
+    pub total_weight: u64,
+    /// Lamports set aside for refunds when the snapshot was posted
+    pub pool_lamports: u64,
+    /// Lamports paid out so far
+    pub paid_lamports: u64,
+    /// Sum of the weights of leaves paid so far (never above `total_weight`)
+    pub claimed_weight: u64,
+    /// Leaves paid so far
+    pub claimed_count: u32,
+    /// Timestamp when the snapshot was posted
+    pub posted_at: i64,
+    /// Bump seed for PDA
+    pub bump: u8,
+    /// One bit per leaf, set once paid
+    pub claimed: Vec<u8>,
+}
+
+impl RefundSnapshot {
+    /// Account size for a snapshot with `leaf_count` leaves
+    pub fn space(leaf_count: u32) -> usize {
+        8   // discriminator
+        + 32  // escrow_vault
+        + 32  // bonding_curve
+        + 32  // root
+        + 4   // leaf_count
+        + 8   // total_weight
+        + 8   // pool_lamports
+        + 8   // paid_lamports
+        + 8   // claimed_weight
+        + 4   // claimed_count
+        + 8   // posted_at
+        + 1   // bump
+        + 4 + Self::bitmap_len(leaf_count) // claimed
+    }
+
+    /// Bytes needed for one bit per leaf
+    pub fn bitmap_len(leaf_count: u32) -> usize {
+        (leaf_count as usize).div_ceil(8)
+    }
+
+    /// Whether leaf `index` has been paid
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.claimed[index as usize / 8] & (1 << (index % 8)) != 0
+    }
+
+    /// Mark leaf `index` as paid
+    pub fn set_claimed(&mut self, index: u32) {
+        self.claimed[index as usize / 8] |= 1 << (index % 8);
+    }
+}
+
+/// A wallet's position on one bonding curve (PDA per curve + owner)
+#[account]
+#[derive(Default)]
+pub struct Position {
+    /// Bonding curve this position is on
+    pub bonding_curve: Pubkey,
+    /// Wallet that owns the position
+    pub owner: Pubkey,
+    /// Gross SOL spent on buys during the launch-protection window
+    pub window_sol_spent: u64,
+    /// Bump seed for PDA
+    pub bump: u8,
+    /// Gross SOL spent on presale buys (capped by the wallet's allocation)
+    pub presale_sol_spent: u64,
+    /// SOL paid into the curve (buys, fees included, and opening commits),
+    /// reduced pro-rata as the position sells its tokens back
+    pub net_sol_contributed: u64,
+    /// Tokens acquired from the curve (buys and opening claims) and not yet
+    /// sold back; the contribution backs only these
+    pub tokens_held: u64,
+}
+
+impl Position {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // bonding_curve
+        + 32  // owner
+        + 8   // window_sol_spent
+        + 1   // bump
+        + 8   // presale_sol_spent
+        + 8   // net_sol_contributed
+        + 8;  // tokens_held
+
+    /// Open the position on first use (`init_if_needed` leaves it zeroed)
+    pub fn open(&mut self, bonding_curve: Pubkey, owner: Pubkey, bump: u8) {
+        if self.owner == Pubkey::default() {
+            self.bonding_curve = bonding_curve;
+            self.owner = owner;
+            self.bump = bump;
+        }
+    }
+
+    /// Credit SOL paid into the curve and the tokens it acquired
+    pub fn credit(&mut self, sol: u64, tokens: u64) -> Result<()> {
+        self.net_sol_contributed = self
+            .net_sol_contributed
+            .checked_add(sol)
+            .ok_or(FyrstError::MathOverflow)?;
+        self.tokens_held = self
+            .tokens_held
+            .checked_add(tokens)
+            .ok_or(FyrstError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Contribution backing `tokens` of the position's acquired tokens:
+    /// net_sol_contributed × min(tokens, tokens_held) / tokens_held. Tokens
+    /// received by transfer carry no contribution.
+    pub fn contribution_for(&self, tokens: u64) -> Result<u64> {
+        if self.tokens_held == 0 {
+            return Ok(0);
+        }
+        let backed = (self.net_sol_contributed as u128)
+            .checked_mul(tokens.min(self.tokens_held) as u128)
+            .ok_or(FyrstError::MathOverflow)?
+            / self.tokens_held as u128;
+        u64::try_from(backed).map_err(|_| FyrstError::MathOverflow.into())
+    }
+}
+
@@ -35,6 +35,12 @@ const CREATE_POOL_FEE = config.isDevnet
   ? new PublicKey("3oE58BKVt8KuYkGxx8zBojugnymWmBiyafWgMrnb6eYy")
   : new PublicKey("DNXgeM9EiiaAbaWvwjHj9fQQLAX5ZsfHyvmYUNRAdNC8");
 
+// Signer PDA of the self-CPI that carries the program's emit_cpi! events
+const [EVENT_AUTHORITY] = PublicKey.findProgramAddressSync(
+  [Buffer.from("__event_authority")],
+  PROGRAM_ID,
+);
+
 // ---------------------------------------------------------------------------
 // State
 // ---------------------------------------------------------------------------
@@ -284,6 +290,8 @@ export async function executeGraduation(tokenMint: string): Promise<void> {
       observationState,
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      eventAuthority: EVENT_AUTHORITY,
+      program: PROGRAM_ID,
     })
     .instruction();
   tx.add(graduateIx);
@@ -316,7 +324,7 @@ export async function executeGraduation(tokenMint: string): Promise<void> {
   });
 
   logger.info(`Graduation TX confirmed for ${tokenMint}: ${sig}`);
-  // DB update is handled by onchainListener's handleDexMigration() when it detects the log
+  // DB update is handled by onchainListener's handleDexMigration() when it decodes the DexMigrated event
 }
 
 // ---------------------------------------------------------------------------
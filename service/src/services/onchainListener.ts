@@ -1,21 +1,30 @@
 import { Connection, PublicKey, Logs } from "@solana/web3.js";
+import { BorshCoder } from "@coral-xyz/anchor";
+import bs58 from "bs58";
 import { config } from "../config";
 import { logger } from "../utils/logger";
 import { prisma, dbConnected } from "../lib/prisma";
 import { spotPriceFromSupply, calculateProgress, approximateReserves } from "./bondingCurve";
 import { getIo } from "../socketManager";
 import { executeWithRetry } from "./graduationCranker";
+import idl from "../idl/fyrst.json";
 
 // ---------------------------------------------------------------------------
 // On-chain Event Listener (Section 5 — On-chain Data Sync)
 //
 // Listens to FYRST program logs via WebSocket (connection.onLogs) and
-// parses buy/sell events to update the DB. This replaces the client-
-// authoritative POST /api/trade approach.
+// decodes the program's typed Anchor events (TradeEvent, Graduated, ...) to
+// update the DB. This replaces the client-authoritative POST /api/trade
+// approach.
 // ---------------------------------------------------------------------------
 
 const PROGRAM_ID = new PublicKey(config.programId);
 
+const coder = new BorshCoder(idl as any);
+
+/** Instruction tag Anchor prefixes to emit_cpi! event data */
+const EVENT_IX_TAG = Buffer.from("e445a52e51cb9a1d", "hex");
+
 let subscriptionId: number | null = null;
 let rpcConnection: Connection | null = null;
 
@@ -63,97 +72,110 @@ export async function stopOnchainListener(): Promise<void> {
   }
 }
 
+/** A decoded program event: IDL event name plus snake_case fields */
+interface FyrstEvent {
+  name: string;
+  data: any;
+}
+
 /**
- * Parse FYRST program logs and extract trade events.
+ * Decode the events a FYRST transaction emitted.
  *
- * Actual Rust msg!() log formats:
- *   "Buy: buyer=ADDR, sol=AMOUNT, tokens=AMOUNT, new_supply=AMOUNT"
- *   "Sell: seller=ADDR, tokens=AMOUNT, sol=AMOUNT, new_supply=AMOUNT"
- *   "Token auto-graduated: mint=ADDR"
- *   "Escrow created: deployer=ADDR, mint=ADDR, collateral=AMOUNT, deadline=AMOUNT"
- *   "Refund: buyer=ADDR, tokens_burned=AMOUNT, sol_refunded=AMOUNT"
+ * The program emits typed Anchor events with emit_cpi!, which travel as
+ * inner instructions the program sends to itself: EVENT_IX_TAG (8 bytes)
+ * followed by the event discriminator and Borsh-encoded fields. Program logs
+ * carry only the signature, so the transaction is fetched to read them.
  */
-async function processLogs(logs: Logs): Promise<void> {
-  if (logs.err) return; // Skip failed transactions
+async function fetchEvents(signature: string): Promise<FyrstEvent[]> {
+  if (!rpcConnection) return [];
 
-  if (!dbConnected()) return;
+  const tx = await rpcConnection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  if (!tx?.meta?.innerInstructions) return [];
 
-  const signature = logs.signature;
-  const logMessages = logs.logs;
+  const accountKeys = tx.transaction.message.getAccountKeys({
+    accountKeysFromLookups: tx.meta.loadedAddresses,
+  });
 
-  for (const log of logMessages) {
-    // Match buy event: "Buy: buyer=ADDR, sol=AMOUNT, tokens=AMOUNT, new_supply=AMOUNT"
-    const buyMatch = log.match(
-      /Buy: buyer=(\S+), sol=(\d+), tokens=(\d+), new_supply=(\d+)/,
-    );
-    if (buyMatch) {
-      const solLamports = parseInt(buyMatch[2], 10);
-      const tokenAmount = parseInt(buyMatch[3], 10);
-      await recordTradeFromChain(signature, "buy", tokenAmount, solLamports, logMessages);
-      continue;
+  const events: FyrstEvent[] = [];
+  for (const inner of tx.meta.innerInstructions) {
+    for (const ix of inner.instructions) {
+      if (!accountKeys.get(ix.programIdIndex)?.equals(PROGRAM_ID)) continue;
+      const data = Buffer.from(bs58.decode(ix.data));
+      if (!data.subarray(0, 8).equals(EVENT_IX_TAG)) continue;
+      const event = coder.events.decode(data.subarray(8).toString("base64"));
+      if (event) events.push(event);
     }
+  }
+  return events;
+}
 
-    // Match sell event: "Sell: seller=ADDR, tokens=AMOUNT, sol=AMOUNT, new_supply=AMOUNT"
-    const sellMatch = log.match(
-      /Sell: seller=(\S+), tokens=(\d+), sol=(\d+), new_supply=(\d+)/,
-    );
-    if (sellMatch) {
-      const tokenAmount = parseInt(sellMatch[2], 10);
-      const solLamports = parseInt(sellMatch[3], 10);
-      await recordTradeFromChain(signature, "sell", tokenAmount, solLamports, logMessages);
-      continue;
-    }
+/**
+ * Decode the events of a FYRST transaction and apply them to the DB.
+ */
+async function processLogs(logs: Logs): Promise<void> {
+  if (logs.err) return; // Skip failed transactions
 
-    // Match graduation: "Token auto-graduated: mint=ADDR"
-    const gradMatch = log.match(/Token auto-graduated: mint=(\S+)/);
-    if (gradMatch) {
-      await handleGraduation(signature, logMessages);
-      continue;
-    }
+  if (!dbConnected()) return;
 
-    // Match escrow creation: "Escrow created: deployer=ADDR, mint=ADDR, collateral=AMOUNT, deadline=AMOUNT"
-    const escrowMatch = log.match(
-      /Escrow created: deployer=(\S+), mint=(\S+), collateral=(\d+), deadline=(\d+)/,
-    );
-    if (escrowMatch) {
-      logger.info(
-        `On-chain escrow creation detected: deployer=${escrowMatch[1]}, mint=${escrowMatch[2]}, collateral=${escrowMatch[3]}, tx=${signature}`,
-      );
-      continue;
-    }
+  const signature = logs.signature;
 
-    // Match DEX migration: "DEX migration: mint=ADDR, pool=ADDR, sol=AMOUNT, tokens=AMOUNT"
-    const dexMatch = log.match(
-      /DEX migration: mint=(\S+), pool=(\S+), sol=(\d+), tokens=(\d+)/,
-    );
-    if (dexMatch) {
-      await handleDexMigration(dexMatch[1], dexMatch[2], signature);
-      continue;
-    }
+  let events: FyrstEvent[];
+  try {
+    events = await fetchEvents(signature);
+  } catch (err) {
+    logger.error(`Failed to fetch on-chain events: tx=${signature}`, err);
+    return;
+  }
 
-    // Match refund: "Refund: buyer=ADDR, tokens_burned=AMOUNT, sol_refunded=AMOUNT"
-    const refundMatch = log.match(
-      /Refund: buyer=(\S+), tokens_burned=(\d+), sol_refunded=(\d+)/,
-    );
-    if (refundMatch) {
-      logger.info(
-        `On-chain refund detected: buyer=${refundMatch[1]}, tokens_burned=${refundMatch[2]}, sol_refunded=${refundMatch[3]} lamports, tx=${signature}`,
-      );
-      continue;
+  for (const { name, data } of events) {
+    switch (name) {
+      case "TradeEvent":
+        await recordTradeFromChain(
+          signature,
+          data.token_mint.toBase58(),
+          data.trader.toBase58(),
+          data.is_buy ? "buy" : "sell",
+          Number(data.token_amount),
+          Number(data.sol_amount),
+        );
+        break;
+
+      case "Graduated":
+        await handleGraduation(signature, data.token_mint.toBase58());
+        break;
+
+      case "EscrowCreated":
+        logger.info(
+          `On-chain escrow creation detected: deployer=${data.deployer.toBase58()}, mint=${data.token_mint.toBase58()}, collateral=${data.collateral_amount.toString()}, tx=${signature}`,
+        );
+        break;
+
+      case "DexMigrated":
+        await handleDexMigration(data.token_mint.toBase58(), data.pool.toBase58(), signature);
+        break;
+
+      case "RefundProcessed":
+        logger.info(
+          `On-chain refund detected: buyer=${data.buyer.toBase58()}, tokens_burned=${data.tokens_burned.toString()}, sol_refunded=${data.sol_refunded.toString()} lamports, tx=${signature}`,
+        );
+        break;
     }
   }
 }
 
 /**
- * Record a trade detected from on-chain logs into the DB.
- * Extracts the token mint from the transaction accounts.
+ * Record a trade decoded from a TradeEvent into the DB.
  */
 async function recordTradeFromChain(
   signature: string,
+  tokenMint: string,
+  traderAddress: string,
   side: "buy" | "sell",
   tokenAmount: number,
   solLamports: number,
-  _logMessages: string[],
 ): Promise<void> {
   // Avoid duplicate trades
   const existing = await prisma.trade.findFirst({
@@ -161,54 +183,8 @@ async function recordTradeFromChain(
   });
   if (existing) return;
 
-  // Fetch full transaction to get accounts (token mint, trader)
-  if (!rpcConnection) return;
-
   try {
-    const tx = await rpcConnection.getParsedTransaction(signature, {
-      maxSupportedTransactionVersion: 0,
-    });
-    if (!tx?.meta || !tx.transaction) return;
-
-    const accountKeys = tx.transaction.message.accountKeys;
-    // Account 0 = signer (buyer/seller), Account 1 = bonding curve PDA
-    const traderAddress = accountKeys[0].pubkey.toBase58();
-
-    // Find the token mint by looking up which token we have in DB that matches
-    // the bonding curve PDA. The curve PDA is derived from [CURVE_SEED, token_mint]
-    // so we look for the curve account among the transaction accounts.
-    // For simplicity, find a Token record where we have an active trade happening
-    const solAmount = solLamports / 1e9;
-
-    // Try to find the token by checking bonding curve PDA in our known tokens
-    // The token_mint is typically account index 2 or can be found in inner instructions
-    // For now, use a heuristic: find the most recently created non-graduated token
-    // associated with one of the accounts in this transaction
-    const accountAddresses = accountKeys.map((k) => k.pubkey.toBase58());
-
-    // Check if any of the accounts match a known token mint
-    const token = await prisma.token.findFirst({
-      where: { mint: { in: accountAddresses } },
-    });
-
-    if (!token) {
-      // Fallback: try to find by deployer who has a token
-      const tokenByDeployer = await prisma.token.findFirst({
-        where: {
-          deployerAddress: { in: accountAddresses },
-          graduated: false,
-        },
-        orderBy: { createdAt: "desc" },
-      });
-      if (!tokenByDeployer) {
-        logger.warn(`On-chain trade detected but no matching token found: tx=${signature}`);
-        return;
-      }
-      await upsertTradeRecord(tokenByDeployer.mint, traderAddress, side, tokenAmount, solAmount, signature);
-      return;
-    }
-
-    await upsertTradeRecord(token.mint, traderAddress, side, tokenAmount, solAmount, signature);
+    await upsertTradeRecord(tokenMint, traderAddress, side, tokenAmount, solLamports / 1e9, signature);
   } catch (err) {
     logger.error(`Failed to process on-chain trade: tx=${signature}`, err);
   }
@@ -367,20 +343,13 @@ async function handleDexMigration(
   }
 }
 
-async function handleGraduation(signature: string, logMessages: string[]): Promise<void> {
-  logger.info(`On-chain graduation detected: tx=${signature}`);
-
-  // Extract token mint from the graduation log and trigger auto-graduation
-  const mintMatch = logMessages
-    .find((l) => l.includes("Token auto-graduated:"))
-    ?.match(/mint=(\S+)/);
+async function handleGraduation(signature: string, tokenMint: string): Promise<void> {
+  logger.info(`On-chain graduation detected: mint=${tokenMint}, tx=${signature}`);
 
-  if (mintMatch) {
-    // fire-and-forget — cranker handles async, does not block listener
-    executeWithRetry(mintMatch[1]).catch((err) => {
-      logger.error(`Auto-graduation failed for ${mintMatch[1]}`, err);
-    });
-  }
+  // fire-and-forget — cranker handles async, does not block listener
+  executeWithRetry(tokenMint).catch((err) => {
+    logger.error(`Auto-graduation failed for ${tokenMint}`, err);
+  });
 
   try {
     const io = getIo();
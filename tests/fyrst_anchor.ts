@@ -7,13 +7,21 @@ import {
   LAMPORTS_PER_SOL,
   PublicKey,
   SYSVAR_RENT_PUBKEY,
+  Transaction,
 } from "@solana/web3.js";
 import {
   TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
   getAssociatedTokenAddressSync,
   getAccount,
+  getMint,
+  createAssociatedTokenAccount,
+  createMint,
+  createTransferInstruction,
+  mintTo,
+  transfer,
 } from "@solana/spl-token";
+import { keccak_256 } from "@noble/hashes/sha3";
 import { assert } from "chai";
 
 const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
@@ -88,6 +96,31 @@ describe("FYRST CPMM E2E Tests", () => {
     await provider.connection.confirmTransaction(sig2);
   });
 
+  // Events emitted via emit_cpi! ride in self-CPI inner instructions:
+  // the 8-byte EVENT_IX_TAG, then the event discriminator and data.
+  async function cpiEvents(signature: string): Promise<{ name: string; data: any }[]> {
+    await provider.connection.confirmTransaction(signature, "confirmed");
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const keys = tx.transaction.message.getAccountKeys({
+      accountKeysFromLookups: tx.meta.loadedAddresses,
+    });
+    const events = [];
+    for (const inner of tx.meta.innerInstructions ?? []) {
+      for (const ix of inner.instructions) {
+        if (!keys.get(ix.programIdIndex).equals(program.programId)) continue;
+        const data = anchor.utils.bytes.bs58.decode(ix.data);
+        const event = program.coder.events.decode(
+          anchor.utils.bytes.base64.encode(Buffer.from(data.subarray(8)))
+        );
+        if (event) events.push(event);
+      }
+    }
+    return events;
+  }
+
   // ─── 1. Protocol Init ────────────────────────────────────────────
 
   it("1. Initialize protocol config", async () => {
@@ -112,26 +145,168 @@ describe("FYRST CPMM E2E Tests", () => {
       config.graduationThreshold.toNumber(),
       5_000_000_000
     );
+    assert.equal(config.feeSchedule.tradeFeeBps.toNumber(), 100);
+    assert.equal(config.feeSchedule.deployerFeeBps.toNumber(), 50);
+    assert.equal(config.launchProtection.windowSeconds.toNumber(), 60);
+    assert.equal(config.sniperTax.startFeeBps.toNumber(), 0);
+    assert.equal(config.collateralLock.lockSeconds.toNumber(), 86_400);
+    assert.equal(config.collateralLock.unlockSeconds.toNumber(), 604_800);
+    assert.equal(config.dumpThresholdBps.toNumber(), 5000);
 
     console.log("  Protocol initialized: authority + treasury set");
   });
 
-  // ─── 2. Escrow with Deadline ─────────────────────────────────────
+  it("1b. update_fee_schedule applies within the ceilings, authority only", async () => {
+    const defaults = (
+      await (program.account as any).protocolConfig.fetch(protocolConfigPda)
+    ).feeSchedule;
+    const updateFeeSchedule = (schedule: Record<string, any>, authority?: Keypair) =>
+      (program.methods as any)
+        .updateFeeSchedule(schedule)
+        .accounts({
+          authority: (authority ?? deployer).publicKey,
+          protocolConfig: protocolConfigPda,
+        })
+        .signers(authority ? [authority] : [])
+        .rpc();
 
-  it("2. Create escrow with 0.1 SOL + 1h deadline", async () => {
-    const collateral = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
-    const duration = new anchor.BN(3600); // 1 hour
+    const raised = {
+      ...defaults,
+      tradeFeeBps: new anchor.BN(200),
+      protocolFeeBps: new anchor.BN(100),
+      deployFee: new anchor.BN(0.05 * LAMPORTS_PER_SOL),
+    };
+    await updateFeeSchedule(raised);
+    const config = await (program.account as any).protocolConfig.fetch(protocolConfigPda);
+    assert.equal(config.feeSchedule.tradeFeeBps.toNumber(), 200);
+    assert.equal(config.feeSchedule.protocolFeeBps.toNumber(), 100);
+    assert.equal(config.feeSchedule.deployFee.toNumber(), 0.05 * LAMPORTS_PER_SOL);
+
+    // Each ceiling on its own, then trade + protocol fees each within theirs
+    // but over the combined 6%
+    const overCeilings: Record<string, any>[] = [
+      { tradeFeeBps: new anchor.BN(501) },
+      { deployerFeeBps: new anchor.BN(101), tradeFeeBps: new anchor.BN(100) },
+      { opsShareBps: new anchor.BN(10_001) },
+      { protocolFeeBps: new anchor.BN(201) },
+      { tradeFeeBps: new anchor.BN(500), protocolFeeBps: new anchor.BN(200) },
+      { deployFee: new anchor.BN(1_000_000_001) },
+      { minCollateral: new anchor.BN(10_000_000_001) },
+    ];
+    for (const over of overCeilings) {
+      try {
+        await updateFeeSchedule({ ...defaults, ...over });
+        assert.fail("Should have thrown InvalidFeeSchedule");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidFeeSchedule");
+      }
+    }
 
-    await (program.methods as any)
-      .createEscrow(collateral, duration)
-      .accounts({
-        deployer: deployer.publicKey,
-        tokenMint: tokenMint.publicKey,
-        escrowVault: escrowPda,
-        systemProgram: SystemProgram.programId,
-      })
+    try {
+      await updateFeeSchedule(defaults, buyer);
+      assert.fail("Should have thrown Unauthorized");
+    } catch (err: any) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+
+    await updateFeeSchedule(defaults);
+    console.log(`  Fee schedule updated, ${overCeilings.length} over-ceiling schedules rejected`);
+  });
+
+  // ─── 2. Atomic Launch (mint + metadata + curve + escrow) ─────────
+
+  function launchArgs(collateral: number, duration: number) {
+    return {
+      name: "TestToken",
+      symbol: "TEST",
+      uri: "https://example.com/meta.json",
+      collateralAmount: new anchor.BN(collateral),
+      durationSeconds: new anchor.BN(duration),
+      initialBuySol: new anchor.BN(0),
+      minTokensOut: new anchor.BN(0),
+      curveKind: { constantProduct: {} },
+      totalSupply: new anchor.BN(1_000_000_000),
+      decimals: 6,
+      virtualSol: new anchor.BN(30_000_000_000),
+      curveTokenBps: new anchor.BN(7931),
+      tradingOpensAt: new anchor.BN(0),
+      presaleRoot: Array(32).fill(0),
+      presaleSeconds: new anchor.BN(0),
+      openingSeconds: new anchor.BN(0),
+      maxWalletBps: new anchor.BN(0),
+      refundMode: { tokenShare: {} },
+    };
+  }
+
+  function getPositionPDA(curve: PublicKey, owner: PublicKey): PublicKey {
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("position"), curve.toBuffer(), owner.toBuffer()],
+      program.programId
+    );
+    return pda;
+  }
+
+  function launchAccounts(mint: PublicKey) {
+    const [curve] = PublicKey.findProgramAddressSync(
+      [Buffer.from("curve"), mint.toBuffer()],
+      program.programId
+    );
+    const [escrow] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), deployer.publicKey.toBuffer(), mint.toBuffer()],
+      program.programId
+    );
+    return {
+      deployer: deployer.publicKey,
+      tokenMint: mint,
+      bondingCurve: curve,
+      curveTokenAccount: getAssociatedTokenAddressSync(mint, curve, true),
+      escrowVault: escrow,
+      deployerTokenAccount: getAssociatedTokenAddressSync(
+        mint,
+        deployer.publicKey
+      ),
+      deployerPosition: getPositionPDA(curve, deployer.publicKey),
+      metadataAccount: getMetadataPDA(mint),
+      metadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      protocolConfig: protocolConfigPda,
+      treasury: treasury.publicKey,
+      opsWallet: treasury.publicKey,
+      deployerFyrstAccount: null,
+      feeWaiver: null,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+    };
+  }
+
+  async function fundedWallet(sol: number): Promise<Keypair> {
+    const wallet = Keypair.generate();
+    const sig = await provider.connection.requestAirdrop(
+      wallet.publicKey,
+      sol * LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(sig);
+    return wallet;
+  }
+
+  it("2. Launch token with 0.1 SOL collateral + 1h deadline", async () => {
+    const treasuryBalBefore = await provider.connection.getBalance(
+      treasury.publicKey
+    );
+
+    const sig = await (program.methods as any)
+      .launchToken(launchArgs(0.1 * LAMPORTS_PER_SOL, 3600))
+      .accounts(launchAccounts(tokenMint.publicKey))
+      .signers([tokenMint])
       .rpc();
 
+    // Deploy fee (0.02 SOL) split between treasury and ops (same wallet here)
+    const treasuryBalAfter = await provider.connection.getBalance(
+      treasury.publicKey
+    );
+    assert.equal(treasuryBalAfter - treasuryBalBefore, 0.02 * LAMPORTS_PER_SOL);
+
     const escrow = await (program.account as any).escrowVault.fetch(escrowPda);
     assert.equal(escrow.deployer.toBase58(), deployer.publicKey.toBase58());
     assert.equal(
@@ -144,69 +319,77 @@ describe("FYRST CPMM E2E Tests", () => {
       escrow.createdAt.toNumber() + 3600
     );
 
+    const curve = await (program.account as any).bondingCurve.fetch(curvePda);
+    assert.equal(
+      curve.tokenMint.toBase58(),
+      tokenMint.publicKey.toBase58()
+    );
+    assert.equal(curve.currentSupply.toNumber(), 0);
+    assert.equal(curve.reserveBalance.toNumber(), 0);
+    assert.equal(curve.totalSolCollected.toNumber(), 0);
+
+    // Verify CPMM reserves
+    assert.equal(curve.virtualTokenReserves.toString(), "1073000000000000");
+    assert.equal(curve.virtualSolReserves.toString(), "30000000000");
+    assert.equal(curve.realTokenReserves.toString(), "793100000000000");
+    assert.equal(curve.realSolReserves.toNumber(), 0);
+    assert.equal(curve.tokenTotalSupply.toString(), "1000000000000000");
+    assert.equal(curve.graduated, false);
+
+    // Curve and escrow reference each other
+    assert.equal(curve.escrowVault.toBase58(), escrowPda.toBase58());
+    assert.equal(curve.collateralAmount.toNumber(), 0.1 * LAMPORTS_PER_SOL);
+    assert.equal(escrow.bondingCurve.toBase58(), curvePda.toBase58());
+
+    // Verify all tokens minted to curve ATA
+    const ataInfo = await getAccount(provider.connection, curveTokenAccount);
+    assert.equal(ataInfo.amount.toString(), "1000000000000000");
+
+    // CurveInitialized carries the launch parameters
+    const [init] = (await cpiEvents(sig)).filter((e) => e.name === "curveInitialized");
+    assert.ok(init, "CurveInitialized emitted");
+    assert.equal(init.data.tokenMint.toBase58(), tokenMint.publicKey.toBase58());
+    assert.equal(init.data.bondingCurve.toBase58(), curvePda.toBase58());
+    assert.equal(init.data.deployer.toBase58(), deployer.publicKey.toBase58());
+    assert.equal(init.data.symbol, "TEST");
+    assert.equal(init.data.tokenTotalSupply.toString(), "1000000000000000");
+    assert.equal(init.data.virtualTokenReserves.toString(), "1073000000000000");
+    assert.equal(init.data.virtualSolReserves.toString(), "30000000000");
+    assert.equal(init.data.realTokenReserves.toString(), "793100000000000");
+
     console.log(
-      `  Escrow created: 0.1 SOL, deadline=${escrow.deadlineTimestamp.toNumber()}`
+      `  Launched: escrow 0.1 SOL, deadline=${escrow.deadlineTimestamp.toNumber()}, 1B tokens minted to curve ATA`
     );
   });
 
   // ─── 3. Reject Insufficient Collateral ───────────────────────────
 
-  it("3. Reject escrow with insufficient collateral (< 0.1 SOL)", async () => {
+  it("3. Reject launch with insufficient collateral", async () => {
     const badMint = Keypair.generate();
-    const [badEscrow] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("escrow"),
-        deployer.publicKey.toBuffer(),
-        badMint.publicKey.toBuffer(),
-      ],
-      program.programId
-    );
 
     try {
       await (program.methods as any)
-        .createEscrow(
-          new anchor.BN(0.05 * LAMPORTS_PER_SOL),
-          new anchor.BN(3600)
-        )
-        .accounts({
-          deployer: deployer.publicKey,
-          tokenMint: badMint.publicKey,
-          escrowVault: badEscrow,
-          systemProgram: SystemProgram.programId,
-        })
+        .launchToken(launchArgs(0.005 * LAMPORTS_PER_SOL, 3600))
+        .accounts(launchAccounts(badMint.publicKey))
+        .signers([badMint])
         .rpc();
       assert.fail("Should have thrown InsufficientCollateral");
     } catch (err: any) {
       assert.include(err.toString(), "InsufficientCollateral");
-      console.log("  Correctly rejected: 0.05 SOL < 0.1 SOL minimum");
+      console.log("  Correctly rejected: collateral below minimum, nothing created");
     }
   });
 
   // ─── 4. Reject Invalid Duration ──────────────────────────────────
 
-  it("4. Reject escrow with invalid duration (< 1min)", async () => {
+  it("4. Reject launch with invalid duration (< 1min)", async () => {
     const badMint = Keypair.generate();
-    const [badEscrow] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("escrow"),
-        deployer.publicKey.toBuffer(),
-        badMint.publicKey.toBuffer(),
-      ],
-      program.programId
-    );
 
     try {
       await (program.methods as any)
-        .createEscrow(
-          new anchor.BN(0.1 * LAMPORTS_PER_SOL),
-          new anchor.BN(30) // 30 seconds — too short
-        )
-        .accounts({
-          deployer: deployer.publicKey,
-          tokenMint: badMint.publicKey,
-          escrowVault: badEscrow,
-          systemProgram: SystemProgram.programId,
-        })
+        .launchToken(launchArgs(0.1 * LAMPORTS_PER_SOL, 30))
+        .accounts(launchAccounts(badMint.publicKey))
+        .signers([badMint])
         .rpc();
       assert.fail("Should have thrown InvalidDuration");
     } catch (err: any) {
@@ -215,159 +398,652 @@ describe("FYRST CPMM E2E Tests", () => {
     }
   });
 
-  // ─── 5. Init Bonding Curve (Constant Product AMM) ────────────────
+  // ─── 4b. Custom Supply and Protocol Launch Bounds ───────────────
+
+  it("4b. Launch with custom supply, decimals and starting reserves", async () => {
+    const totalSupply = new anchor.BN(5_000_000_000);
+    const decimals = 9;
+    const virtualSol = new anchor.BN(50 * LAMPORTS_PER_SOL);
+    const curveTokenBps = new anchor.BN(9_000);
+    const launch = await launchOpen({ totalSupply, decimals, virtualSol, curveTokenBps });
+
+    const baseSupply = totalSupply.mul(new anchor.BN(10).pow(new anchor.BN(decimals)));
+    const mint = await getMint(provider.connection, launch.mint);
+    assert.equal(mint.decimals, decimals);
+    assert.equal(mint.supply.toString(), baseSupply.toString());
+    const curveAta = await getAccount(provider.connection, launch.curveTokenAccount);
+    assert.equal(curveAta.amount.toString(), baseSupply.toString());
+
+    // Virtual token reserves keep the reference 1,073 : 793.1 virtual:real ratio
+    const curve = await (program.account as any).bondingCurve.fetch(launch.bondingCurve);
+    const real = baseSupply.mul(curveTokenBps).divn(10_000);
+    assert.equal(curve.tokenTotalSupply.toString(), baseSupply.toString());
+    assert.equal(curve.realTokenReserves.toString(), real.toString());
+    assert.equal(curve.virtualSolReserves.toString(), virtualSol.toString());
+    assert.equal(
+      curve.virtualTokenReserves.toString(),
+      real.mul(new anchor.BN("1073000000000000")).div(new anchor.BN("793100000000000")).toString()
+    );
 
-  it("5. Initialize bonding curve with CPMM + SPL mint + metadata", async () => {
-    const metadataAccount = getMetadataPDA(tokenMint.publicKey);
+    console.log(`  Launched ${baseSupply.toString()} base units at ${decimals} decimals, 90% on the curve`);
+  });
 
-    await (program.methods as any)
-      .initBondingCurve(
-        "TestToken",
-        "TEST",
-        "https://example.com/meta.json"
-      )
-      .accounts({
-        deployer: deployer.publicKey,
-        tokenMint: tokenMint.publicKey,
-        bondingCurve: curvePda,
-        curveTokenAccount,
-        metadataAccount,
-        metadataProgram: TOKEN_METADATA_PROGRAM_ID,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-        rent: SYSVAR_RENT_PUBKEY,
-      })
-      .signers([tokenMint])
-      .rpc();
+  it("4c. update_launch_bounds gates launch parameters and rejects bad bounds", async () => {
+    const defaults = (await (program.account as any).protocolConfig.fetch(protocolConfigPda))
+      .launchBounds;
+    const setBounds = (bounds: Record<string, any>, authority: Keypair | null = null) =>
+      (program.methods as any)
+        .updateLaunchBounds(bounds)
+        .accounts({
+          authority: (authority ?? deployer).publicKey,
+          protocolConfig: protocolConfigPda,
+        })
+        .signers(authority ? [authority] : [])
+        .rpc();
+    const expectInvalid = async (label: string, attempt: Promise<unknown>) => {
+      try {
+        await attempt;
+        assert.fail(`Should have rejected ${label}`);
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidLaunchParams", label);
+      }
+    };
+
+    const outOfBounds: [string, Record<string, any>][] = [
+      ["supply below min", { totalSupply: defaults.minTotalSupply.subn(1) }],
+      ["supply above max", { totalSupply: defaults.maxTotalSupply.addn(1) }],
+      ["decimals above max", { decimals: defaults.maxDecimals + 1 }],
+      ["virtual SOL below min", { virtualSol: defaults.minVirtualSol.subn(1) }],
+      ["virtual SOL above max", { virtualSol: defaults.maxVirtualSol.addn(1) }],
+      ["curve share below min", { curveTokenBps: defaults.minCurveTokenBps.subn(1) }],
+      ["curve share above max", { curveTokenBps: defaults.maxCurveTokenBps.addn(1) }],
+    ];
+    for (const [label, overrides] of outOfBounds) {
+      await expectInvalid(label, launchOpen(overrides));
+    }
 
-    const curve = await (program.account as any).bondingCurve.fetch(curvePda);
+    // Widening the curve share admits a launch the defaults rejected
+    const wider = { ...defaults, maxCurveTokenBps: defaults.maxCurveTokenBps.addn(100) };
+    await setBounds(wider);
+    const config = await (program.account as any).protocolConfig.fetch(protocolConfigPda);
     assert.equal(
-      curve.tokenMint.toBase58(),
-      tokenMint.publicKey.toBase58()
+      config.launchBounds.maxCurveTokenBps.toString(),
+      wider.maxCurveTokenBps.toString()
     );
-    assert.equal(curve.currentSupply.toNumber(), 0);
-    assert.equal(curve.reserveBalance.toNumber(), 0);
-    assert.equal(curve.totalSolCollected.toNumber(), 0);
-
-    // Verify CPMM reserves
-    assert.equal(curve.virtualTokenReserves.toString(), "1073000000000000");
-    assert.equal(curve.virtualSolReserves.toString(), "30000000000");
-    assert.equal(curve.realTokenReserves.toString(), "793100000000000");
-    assert.equal(curve.realSolReserves.toNumber(), 0);
-    assert.equal(curve.tokenTotalSupply.toString(), "1000000000000000");
-    assert.equal(curve.graduated, false);
+    await launchOpen({ curveTokenBps: wider.maxCurveTokenBps });
+
+    const rejected: [string, Record<string, any>][] = [
+      ["zero min supply", { ...defaults, minTotalSupply: new anchor.BN(0) }],
+      ["min supply above max", { ...defaults, minTotalSupply: defaults.maxTotalSupply.addn(1) }],
+      ["min decimals above max", { ...defaults, minDecimals: defaults.maxDecimals + 1 }],
+      ["decimals ceiling", { ...defaults, maxDecimals: 10 }],
+      ["zero min virtual SOL", { ...defaults, minVirtualSol: new anchor.BN(0) }],
+      ["zero min curve share", { ...defaults, minCurveTokenBps: new anchor.BN(0) }],
+      ["curve share above 100%", { ...defaults, maxCurveTokenBps: new anchor.BN(10_001) }],
+    ];
+    for (const [label, bounds] of rejected) {
+      await expectInvalid(label, setBounds(bounds));
+    }
 
-    // Verify all tokens minted to curve ATA
-    const ataInfo = await getAccount(provider.connection, curveTokenAccount);
-    assert.equal(ataInfo.amount.toString(), "1000000000000000");
+    try {
+      await setBounds(defaults, await fundedWallet(1));
+      assert.fail("Should have thrown Unauthorized");
+    } catch (err: any) {
+      assert.include(err.toString(), "Unauthorized");
+    }
 
-    console.log("  Curve initialized with CPMM reserves + 1B tokens minted to curve ATA");
+    await setBounds(defaults);
+    console.log("  Out-of-bounds launches rejected, bounds widened, ceilings enforced");
   });
 
-  // ─── 6. Buy Tokens (CPMM + fee split + max_reserve tracking) ────
-
-  it("6. Buy tokens — verify CPMM transfer + fee split + max_reserve_reached", async () => {
-    const buyAmount = new anchor.BN(0.2 * LAMPORTS_PER_SOL);
-    const buyerAta = getAssociatedTokenAddressSync(
-      tokenMint.publicKey,
-      buyer.publicKey
-    );
+  // ─── 5. Launch with Initial Deployer Buy ─────────────────────────
 
-    const treasuryBalBefore = await provider.connection.getBalance(
-      treasury.publicKey
-    );
+  it("5. Launch with initial deployer buy in the same instruction", async () => {
+    const mint = Keypair.generate();
+    const accounts = launchAccounts(mint.publicKey);
+    const args = {
+      ...launchArgs(0.1 * LAMPORTS_PER_SOL, 3600),
+      initialBuySol: new anchor.BN(0.1 * LAMPORTS_PER_SOL),
+    };
 
     await (program.methods as any)
-      .buyTokens(buyAmount, new anchor.BN(0))
-      .accounts({
-        buyer: buyer.publicKey,
-        bondingCurve: curvePda,
-        tokenMint: tokenMint.publicKey,
-        curveTokenAccount,
-        buyerTokenAccount: buyerAta,
-        protocolConfig: protocolConfigPda,
-        treasury: treasury.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-      })
-      .signers([buyer])
+      .launchToken(args)
+      .accounts(accounts)
+      .signers([mint])
       .rpc();
 
-    const curve = await (program.account as any).bondingCurve.fetch(curvePda);
+    const curve = await (program.account as any).bondingCurve.fetch(
+      accounts.bondingCurve
+    );
+    const ataInfo = await getAccount(
+      provider.connection,
+      accounts.deployerTokenAccount
+    );
+    const position = await (program.account as any).position.fetch(
+      accounts.deployerPosition
+    );
     assert.isAbove(curve.currentSupply.toNumber(), 0);
-    assert.isAbove(curve.reserveBalance.toNumber(), 0);
-    assert.isAbove(curve.totalSolCollected.toNumber(), 0);
-    assert.isAbove(curve.totalDeployerFees.toNumber(), 0);
-    assert.isAbove(curve.maxReserveReached.toNumber(), 0);
-    assert.equal(curve.claimedDeployerFees.toNumber(), 0);
+    assert.equal(ataInfo.amount.toString(), curve.currentSupply.toString());
+    assert.equal(position.windowSolSpent.toString(), args.initialBuySol.toString());
 
-    // Virtual reserves should have changed
-    assert.isBelow(Number(curve.virtualTokenReserves.toString()), 1073000000000000);
-    assert.isAbove(Number(curve.virtualSolReserves.toString()), 30000000000);
+    console.log(`  Deployer bought ${ataInfo.amount.toString()} tokens at launch`);
+  });
 
-    // Real reserves
-    assert.isBelow(Number(curve.realTokenReserves.toString()), 793100000000000);
-    assert.isAbove(curve.realSolReserves.toNumber(), 0);
+  it("5b. Reject initial buy above the launch-protection wallet cap", async () => {
+    const mint = Keypair.generate();
+    const args = {
+      ...launchArgs(0.1 * LAMPORTS_PER_SOL, 3600),
+      initialBuySol: new anchor.BN(1.5 * LAMPORTS_PER_SOL),
+    };
 
-    // max_reserve_reached should equal reserve_balance after first buy
-    assert.equal(
-      curve.maxReserveReached.toNumber(),
-      curve.reserveBalance.toNumber()
-    );
+    try {
+      await (program.methods as any)
+        .launchToken(args)
+        .accounts(launchAccounts(mint.publicKey))
+        .signers([mint])
+        .rpc();
+      assert.fail("Should have thrown WalletCapExceeded");
+    } catch (err: any) {
+      assert.include(err.toString(), "WalletCapExceeded");
+      console.log("  Correctly rejected: initial buy above the 1 SOL window cap");
+    }
+  });
 
-    // Treasury should have received trade fee share
-    const treasuryBalAfter = await provider.connection.getBalance(
-      treasury.publicKey
-    );
-    assert.isAbove(treasuryBalAfter, treasuryBalBefore);
+  it("5c. Reject buys before a scheduled trading open time", async () => {
+    const mint = Keypair.generate();
+    const accounts = launchAccounts(mint.publicKey);
+    const opensAt = Math.floor(Date.now() / 1000) + 3600;
+    const args = {
+      ...launchArgs(0.1 * LAMPORTS_PER_SOL, 3600),
+      tradingOpensAt: new anchor.BN(opensAt),
+    };
 
-    // Verify SPL tokens transferred to buyer ATA
-    const ataInfo = await getAccount(provider.connection, buyerAta);
-    assert.isAbove(Number(ataInfo.amount), 0);
+    await (program.methods as any)
+      .launchToken(args)
+      .accounts(accounts)
+      .signers([mint])
+      .rpc();
 
-    console.log(
-      `  Bought: supply=${curve.currentSupply.toNumber()}, reserve=${curve.reserveBalance.toNumber()}, deployer_fees=${curve.totalDeployerFees.toNumber()}, max_reserve=${curve.maxReserveReached.toNumber()}`
+    const curve = await (program.account as any).bondingCurve.fetch(
+      accounts.bondingCurve
     );
-    console.log(
-      `  CPMM: vt=${curve.virtualTokenReserves.toString()}, vs=${curve.virtualSolReserves.toString()}, rt=${curve.realTokenReserves.toString()}, rs=${curve.realSolReserves.toNumber()}`
+    const escrow = await (program.account as any).escrowVault.fetch(
+      accounts.escrowVault
     );
-  });
+    assert.equal(curve.tradingOpensAt.toNumber(), opensAt);
+    assert.equal(escrow.deadlineTimestamp.toNumber(), opensAt + 3600);
 
-  // ─── 7. Sell Tokens (CPMM + transfer back) ──────────────────────
+    try {
+      await (program.methods as any)
+        .buyTokens(new anchor.BN(0.01 * LAMPORTS_PER_SOL), new anchor.BN(0))
+        .accounts({
+          buyer: buyer.publicKey,
+          bondingCurve: accounts.bondingCurve,
+          tokenMint: mint.publicKey,
+          curveTokenAccount: accounts.curveTokenAccount,
+          buyerTokenAccount: getAssociatedTokenAddressSync(
+            mint.publicKey,
+            buyer.publicKey
+          ),
+          position: getPositionPDA(accounts.bondingCurve, buyer.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+      assert.fail("Should have thrown TradingNotOpen");
+    } catch (err: any) {
+      assert.include(err.toString(), "TradingNotOpen");
+      console.log("  Correctly rejected: buy before the scheduled open time");
+    }
+  });
 
-  it("7. Sell tokens — transfers back to curve ATA + fee split", async () => {
-    const curveBefore = await (program.account as any).bondingCurve.fetch(
-      curvePda
+  it("5d. Presale accepts only allowlisted buyers until public trading opens", async () => {
+    // Single-leaf allowlist: the root is the leaf and the proof is empty
+    const allowlisted = await fundedWallet(1);
+    const allocation = new anchor.BN(0.05 * LAMPORTS_PER_SOL);
+    const presaleRoot = Array.from(
+      keccak_256(
+        Buffer.concat([allowlisted.publicKey.toBuffer(), allocation.toArrayLike(Buffer, "le", 8)])
+      )
     );
-    const sellAmount = new anchor.BN(
-      Math.floor(curveBefore.currentSupply.toNumber() / 2)
+
+    const mint = Keypair.generate();
+    const accounts = launchAccounts(mint.publicKey);
+    const args = {
+      ...launchArgs(0.1 * LAMPORTS_PER_SOL, 3600),
+      presaleRoot,
+      presaleSeconds: new anchor.BN(600),
+    };
+
+    await (program.methods as any)
+      .launchToken(args)
+      .accounts(accounts)
+      .signers([mint])
+      .rpc();
+
+    const curve = await (program.account as any).bondingCurve.fetch(
+      accounts.bondingCurve
     );
-    const sellerAta = getAssociatedTokenAddressSync(
-      tokenMint.publicKey,
-      buyer.publicKey
+    assert.equal(
+      curve.tradingOpensAt.toNumber(),
+      curve.presaleStartsAt.toNumber() + 600
     );
-    const buyerBalBefore = await provider.connection.getBalance(
-      buyer.publicKey
+
+    const presaleAccounts = (wallet: PublicKey) => ({
+      buyer: wallet,
+      bondingCurve: accounts.bondingCurve,
+      tokenMint: mint.publicKey,
+      curveTokenAccount: accounts.curveTokenAccount,
+      buyerTokenAccount: getAssociatedTokenAddressSync(mint.publicKey, wallet),
+      position: getPositionPDA(accounts.bondingCurve, wallet),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+    const solAmount = new anchor.BN(0.01 * LAMPORTS_PER_SOL);
+
+    try {
+      await (program.methods as any)
+        .presaleBuy(solAmount, new anchor.BN(0), allocation, [])
+        .accounts(presaleAccounts(buyer.publicKey))
+        .signers([buyer])
+        .rpc();
+      assert.fail("Should have thrown InvalidProof");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidProof");
+    }
+
+    try {
+      await (program.methods as any)
+        .buyTokens(solAmount, new anchor.BN(0))
+        .accounts(presaleAccounts(buyer.publicKey))
+        .signers([buyer])
+        .rpc();
+      assert.fail("Should have thrown TradingNotOpen");
+    } catch (err: any) {
+      assert.include(err.toString(), "TradingNotOpen");
+    }
+
+    // The allowlisted wallet buys within its allocation, untaxed, at the quoted amount
+    const quote = await (program.methods as any)
+      .quoteBuy(solAmount)
+      .accounts({ bondingCurve: accounts.bondingCurve })
+      .view();
+    await (program.methods as any)
+      .presaleBuy(solAmount, quote.tokens, allocation, [])
+      .accounts(presaleAccounts(allowlisted.publicKey))
+      .signers([allowlisted])
+      .rpc();
+    const { amount } = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(mint.publicKey, allowlisted.publicKey)
     );
+    assert.equal(amount.toString(), quote.tokens.toString());
+
+    try {
+      await (program.methods as any)
+        .presaleBuy(allocation, new anchor.BN(0), allocation, [])
+        .accounts(presaleAccounts(allowlisted.publicKey))
+        .signers([allowlisted])
+        .rpc();
+      assert.fail("Should have thrown PresaleAllocationExceeded");
+    } catch (err: any) {
+      assert.include(err.toString(), "PresaleAllocationExceeded");
+    }
+
+    console.log(`  Allowlisted presale buy got ${amount} tokens; others rejected`);
+  });
+
+  it("5e. Batch opening clears commits at one price, then committers claim", async () => {
+    const mint = Keypair.generate();
+    const accounts = launchAccounts(mint.publicKey);
+    const args = {
+      ...launchArgs(0.1 * LAMPORTS_PER_SOL, 3600),
+      openingSeconds: new anchor.BN(3),
+    };
 
     await (program.methods as any)
-      .sellTokens(sellAmount, new anchor.BN(0))
+      .launchToken(args)
+      .accounts(accounts)
+      .signers([mint])
+      .rpc();
+
+    const [commit] = PublicKey.findProgramAddressSync(
+      [Buffer.from("commit"), accounts.bondingCurve.toBuffer(), buyer.publicKey.toBuffer()],
+      program.programId
+    );
+    const commitAmount = new anchor.BN(0.05 * LAMPORTS_PER_SOL);
+    await (program.methods as any)
+      .commitOpening(commitAmount)
       .accounts({
-        seller: buyer.publicKey,
-        bondingCurve: curvePda,
-        tokenMint: tokenMint.publicKey,
-        curveTokenAccount,
-        sellerTokenAccount: sellerAta,
-        protocolConfig: protocolConfigPda,
-        treasury: treasury.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        committer: buyer.publicKey,
+        bondingCurve: accounts.bondingCurve,
+        commit,
         systemProgram: SystemProgram.programId,
       })
       .signers([buyer])
       .rpc();
 
+    // Commit window closes 3s after launch
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+
+    await (program.methods as any)
+      .settleOpening()
+      .accounts({ bondingCurve: accounts.bondingCurve })
+      .rpc();
+
+    const buyerAta = getAssociatedTokenAddressSync(mint.publicKey, buyer.publicKey);
+    await (program.methods as any)
+      .claimOpening()
+      .accounts({
+        committer: buyer.publicKey,
+        bondingCurve: accounts.bondingCurve,
+        tokenMint: mint.publicKey,
+        curveTokenAccount: accounts.curveTokenAccount,
+        committerTokenAccount: buyerAta,
+        commit,
+        position: getPositionPDA(accounts.bondingCurve, buyer.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buyer])
+      .rpc();
+
+    const curve = await (program.account as any).bondingCurve.fetch(
+      accounts.bondingCurve
+    );
+    const ataInfo = await getAccount(provider.connection, buyerAta);
+    assert.isTrue(curve.openingSettled);
+    assert.equal(curve.openingTokens.toNumber(), 0);
+    assert.equal(ataInfo.amount.toString(), curve.currentSupply.toString());
+    assert.isNull(await provider.connection.getAccountInfo(commit));
+
+    console.log(`  Sole committer claimed ${ataInfo.amount.toString()} tokens at the clearing price`);
+  });
+
+  it("5f. Reject buys that push a wallet past the holding cap", async () => {
+    const mint = Keypair.generate();
+    const accounts = launchAccounts(mint.publicKey);
+    const args = {
+      ...launchArgs(0.1 * LAMPORTS_PER_SOL, 3600),
+      maxWalletBps: new anchor.BN(50), // 0.5% of supply = 5M tokens
+    };
+
+    await (program.methods as any)
+      .launchToken(args)
+      .accounts(accounts)
+      .signers([mint])
+      .rpc();
+
+    try {
+      // 0.2 SOL buys ~7M tokens at the opening price
+      await (program.methods as any)
+        .buyTokens(new anchor.BN(0.2 * LAMPORTS_PER_SOL), new anchor.BN(0))
+        .accounts({
+          buyer: buyer.publicKey,
+          bondingCurve: accounts.bondingCurve,
+          tokenMint: mint.publicKey,
+          curveTokenAccount: accounts.curveTokenAccount,
+          buyerTokenAccount: getAssociatedTokenAddressSync(
+            mint.publicKey,
+            buyer.publicKey
+          ),
+          position: getPositionPDA(accounts.bondingCurve, buyer.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+      assert.fail("Should have thrown MaxWalletExceeded");
+    } catch (err: any) {
+      assert.include(err.toString(), "MaxWalletExceeded");
+      console.log("  Correctly rejected: buy above the 0.5% holding cap");
+    }
+  });
+
+  it("5g. Holding cap still applies to the buy that graduates the curve", async () => {
+    const mint = Keypair.generate();
+    const accounts = launchAccounts(mint.publicKey);
+    const args = {
+      ...launchArgs(0.1 * LAMPORTS_PER_SOL, 3600),
+      maxWalletBps: new anchor.BN(50), // 0.5% of supply = 5M tokens
+    };
+
+    await (program.methods as any)
+      .launchToken(args)
+      .accounts(accounts)
+      .signers([mint])
+      .rpc();
+
+    // A 0.1 SOL threshold makes the next 0.2 SOL buy the graduating one
+    await (program.methods as any)
+      .setCurveGraduationThreshold(new anchor.BN(0.1 * LAMPORTS_PER_SOL))
+      .accounts({
+        authority: deployer.publicKey,
+        protocolConfig: protocolConfigPda,
+        bondingCurve: accounts.bondingCurve,
+      })
+      .rpc();
+
+    try {
+      await (program.methods as any)
+        .buyTokens(new anchor.BN(0.2 * LAMPORTS_PER_SOL), new anchor.BN(0))
+        .accounts({
+          buyer: buyer.publicKey,
+          bondingCurve: accounts.bondingCurve,
+          tokenMint: mint.publicKey,
+          curveTokenAccount: accounts.curveTokenAccount,
+          buyerTokenAccount: getAssociatedTokenAddressSync(
+            mint.publicKey,
+            buyer.publicKey
+          ),
+          position: getPositionPDA(accounts.bondingCurve, buyer.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+      assert.fail("Should have thrown MaxWalletExceeded");
+    } catch (err: any) {
+      assert.include(err.toString(), "MaxWalletExceeded");
+    }
+
+    const curve = await (program.account as any).bondingCurve.fetch(
+      accounts.bondingCurve
+    );
+    assert.equal(curve.graduated, false);
+    console.log("  Correctly rejected: graduating buy above the holding cap");
+  });
+
+  // ─── 6. Buy Tokens (CPMM + fee split + max_reserve tracking) ────
+
+  it("6. Buy tokens — verify CPMM transfer + fee split + max_reserve_reached", async () => {
+    const buyAmount = new anchor.BN(0.2 * LAMPORTS_PER_SOL);
+    const buyerAta = getAssociatedTokenAddressSync(
+      tokenMint.publicKey,
+      buyer.publicKey
+    );
+
+    const treasuryBalBefore = await provider.connection.getBalance(
+      treasury.publicKey
+    );
+
+    const sig = await (program.methods as any)
+      .buyTokens(buyAmount, new anchor.BN(0))
+      .accounts({
+        buyer: buyer.publicKey,
+        bondingCurve: curvePda,
+        tokenMint: tokenMint.publicKey,
+        curveTokenAccount,
+        buyerTokenAccount: buyerAta,
+        position: getPositionPDA(curvePda, buyer.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buyer])
+      .rpc();
+
+    const curve = await (program.account as any).bondingCurve.fetch(curvePda);
+    assert.isAbove(curve.currentSupply.toNumber(), 0);
+    assert.isAbove(curve.reserveBalance.toNumber(), 0);
+    assert.isAbove(curve.totalSolCollected.toNumber(), 0);
+    assert.isAbove(curve.totalDeployerFees.toNumber(), 0);
+    assert.isAbove(curve.maxReserveReached.toNumber(), 0);
+    assert.equal(curve.claimedDeployerFees.toNumber(), 0);
+
+    // Virtual reserves should have changed
+    assert.isBelow(Number(curve.virtualTokenReserves.toString()), 1073000000000000);
+    assert.isAbove(Number(curve.virtualSolReserves.toString()), 30000000000);
+
+    // Real reserves
+    assert.isBelow(Number(curve.realTokenReserves.toString()), 793100000000000);
+    assert.isAbove(curve.realSolReserves.toNumber(), 0);
+
+    // max_reserve_reached should equal reserve_balance after first buy
+    assert.equal(
+      curve.maxReserveReached.toNumber(),
+      curve.reserveBalance.toNumber()
+    );
+
+    // Protocol fees accrue on the curve — treasury is not touched by trades
+    const treasuryBalAfter = await provider.connection.getBalance(
+      treasury.publicKey
+    );
+    assert.equal(treasuryBalAfter, treasuryBalBefore);
+    assert.isAbove(curve.accruedTreasuryFees.toNumber(), 0);
+
+    // Permissionless sweep moves accrued fees to the fee wallets. A separate
+    // ops wallet for the sweep lets each share be checked exactly.
+    const opsWallet = await fundedWallet(0.01);
+    const setOpsWallet = (wallet: PublicKey) =>
+      (program.methods as any)
+        .updateOpsWallet(wallet)
+        .accounts({ authority: deployer.publicKey, protocolConfig: protocolConfigPda })
+        .rpc();
+    await setOpsWallet(opsWallet.publicKey);
+    const opsBalBefore = await provider.connection.getBalance(opsWallet.publicKey);
+    const sweep = (curves: { pubkey: PublicKey; isWritable: boolean; isSigner: boolean }[]) =>
+      (program.methods as any)
+        .sweepProtocolFees()
+        .accounts({
+          protocolConfig: protocolConfigPda,
+          treasury: treasury.publicKey,
+          opsWallet: opsWallet.publicKey,
+        })
+        .remainingAccounts(curves)
+        .rpc();
+
+    // Only writable curve PDAs are accepted
+    try {
+      await sweep([{ pubkey: escrowPda, isWritable: true, isSigner: false }]);
+      assert.fail("Should have rejected a non-curve account");
+    } catch (err: any) {
+      assert.include(err.toString(), "AccountDiscriminatorMismatch");
+    }
+    try {
+      await sweep([{ pubkey: curvePda, isWritable: false, isSigner: false }]);
+      assert.fail("Should have thrown InvalidAccount");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidAccount");
+    }
+
+    await sweep([{ pubkey: curvePda, isWritable: true, isSigner: false }]);
+    await setOpsWallet(treasury.publicKey);
+
+    const swept = await (program.account as any).bondingCurve.fetch(curvePda);
+    assert.equal(swept.accruedTreasuryFees.toNumber(), 0);
+    assert.equal(swept.accruedOpsFees.toNumber(), 0);
+    const treasuryBalSwept = await provider.connection.getBalance(
+      treasury.publicKey
+    );
+    const opsBalSwept = await provider.connection.getBalance(opsWallet.publicKey);
+    assert.isAbove(curve.accruedOpsFees.toNumber(), 0);
+    assert.equal(
+      treasuryBalSwept - treasuryBalBefore,
+      curve.accruedTreasuryFees.toNumber()
+    );
+    assert.equal(opsBalSwept - opsBalBefore, curve.accruedOpsFees.toNumber());
+
+    // Verify SPL tokens transferred to buyer ATA
+    const ataInfo = await getAccount(provider.connection, buyerAta);
+    assert.isAbove(Number(ataInfo.amount), 0);
+
+    // TradeEvent reports the trade and the post-trade curve state
+    const [trade] = (await cpiEvents(sig)).filter((e) => e.name === "tradeEvent");
+    assert.ok(trade, "TradeEvent emitted");
+    assert.equal(trade.data.tokenMint.toBase58(), tokenMint.publicKey.toBase58());
+    assert.equal(trade.data.trader.toBase58(), buyer.publicKey.toBase58());
+    assert.isTrue(trade.data.isBuy);
+    assert.equal(trade.data.solAmount.toString(), buyAmount.toString());
+    assert.equal(trade.data.tokenAmount.toString(), ataInfo.amount.toString());
+    assert.equal(trade.data.virtualTokenReserves.toString(), curve.virtualTokenReserves.toString());
+    assert.equal(trade.data.virtualSolReserves.toString(), curve.virtualSolReserves.toString());
+    assert.equal(trade.data.reserveBalance.toString(), curve.reserveBalance.toString());
+    assert.equal(trade.data.currentSupply.toString(), curve.currentSupply.toString());
+
+    console.log(
+      `  Bought: supply=${curve.currentSupply.toNumber()}, reserve=${curve.reserveBalance.toNumber()}, deployer_fees=${curve.totalDeployerFees.toNumber()}, max_reserve=${curve.maxReserveReached.toNumber()}`
+    );
+    console.log(
+      `  CPMM: vt=${curve.virtualTokenReserves.toString()}, vs=${curve.virtualSolReserves.toString()}, rt=${curve.realTokenReserves.toString()}, rs=${curve.realSolReserves.toNumber()}`
+    );
+  });
+
+  // ─── 7. Sell Tokens (CPMM + transfer back) ──────────────────────
+
+  it("7. Sell tokens — transfers back to curve ATA + fee split", async () => {
+    const curveBefore = await (program.account as any).bondingCurve.fetch(
+      curvePda
+    );
+    const sellAmount = new anchor.BN(
+      Math.floor(curveBefore.currentSupply.toNumber() / 2)
+    );
+    const sellerAta = getAssociatedTokenAddressSync(
+      tokenMint.publicKey,
+      buyer.publicKey
+    );
+    const buyerBalBefore = await provider.connection.getBalance(
+      buyer.publicKey
+    );
+    const position = getPositionPDA(curvePda, buyer.publicKey);
+    const positionBefore = await (program.account as any).position.fetch(
+      position
+    );
+
+    await (program.methods as any)
+      .sellTokens(sellAmount, new anchor.BN(0))
+      .accounts({
+        seller: buyer.publicKey,
+        bondingCurve: curvePda,
+        tokenMint: tokenMint.publicKey,
+        curveTokenAccount,
+        sellerTokenAccount: sellerAta,
+        position,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buyer])
+      .rpc();
+
+    // Net SOL contributed drops pro-rata to the acquired tokens sold
+    const positionAfter = await (program.account as any).position.fetch(
+      position
+    );
+    assert.isBelow(
+      positionAfter.netSolContributed.toNumber(),
+      positionBefore.netSolContributed.toNumber()
+    );
+    assert.equal(
+      positionAfter.tokensHeld.toString(),
+      positionBefore.tokensHeld.sub(sellAmount).toString()
+    );
+
     const curveAfter = await (program.account as any).bondingCurve.fetch(
       curvePda
     );
@@ -402,6 +1078,119 @@ describe("FYRST CPMM E2E Tests", () => {
     );
   });
 
+  // ─── 7b. Curve Shapes and Protocol Curve Bounds ──────────────────
+
+  const shapes: Record<string, any> = {
+    linear: { linear: { startPrice: new anchor.BN(1_000_000), endPrice: new anchor.BN(100_000_000) } },
+    exponential: {
+      exponential: {
+        startPrice: new anchor.BN(1_000_000),
+        growthWad: new anchor.BN("5000000000000000000"),
+      },
+    },
+    step: {
+      step: {
+        prices: [1_000_000, 2_000_000, 5_000_000, 10_000_000, 0, 0, 0, 0].map(
+          (p) => new anchor.BN(p)
+        ),
+        count: 4,
+      },
+    },
+  };
+
+  for (const [name, curveKind] of Object.entries(shapes)) {
+    it(`7b. ${name} curve: launch → buy → sell round trip`, async () => {
+      const holder = await fundedWallet(1);
+      const launch = await launchOpen({ curveKind });
+      const curveBefore = await (program.account as any).bondingCurve.fetch(launch.bondingCurve);
+      assert.property(curveBefore.curveKind, name);
+
+      const solIn = 0.1 * LAMPORTS_PER_SOL;
+      await (program.methods as any)
+        .buyTokens(new anchor.BN(solIn), new anchor.BN(1))
+        .accounts(buyAccounts(launch.mint, launch.bondingCurve, holder.publicKey))
+        .signers([holder])
+        .rpc();
+      const tokens = await tokenBalance(launch.mint, holder.publicKey);
+      assert.isTrue(tokens > 0n);
+
+      const holderBefore = await provider.connection.getBalance(holder.publicKey);
+      await (program.methods as any)
+        .sellTokens(new anchor.BN(tokens.toString()), new anchor.BN(1))
+        .accounts(sellAccounts(launch.mint, launch.bondingCurve, holder.publicKey))
+        .signers([holder])
+        .rpc();
+      const solOut = (await provider.connection.getBalance(holder.publicKey)) - holderBefore;
+
+      const curveAfter = await (program.account as any).bondingCurve.fetch(launch.bondingCurve);
+      assert.equal(curveAfter.currentSupply.toNumber(), 0);
+      assert.equal(
+        curveAfter.realTokenReserves.toString(),
+        curveBefore.realTokenReserves.toString()
+      );
+      assert.isAbove(solOut, 0);
+      assert.isBelow(solOut, solIn);
+
+      console.log(`  ${name}: ${tokens} tokens for ${solIn} lamports, sold back for ${solOut}`);
+    });
+  }
+
+  it("7c. update_curve_bounds gates launch shapes and rejects bad bounds", async () => {
+    const defaults = (await (program.account as any).protocolConfig.fetch(protocolConfigPda))
+      .curveBounds;
+    const setBounds = (bounds: Record<string, any>, authority: Keypair | null = null) =>
+      (program.methods as any)
+        .updateCurveBounds(bounds)
+        .accounts({
+          authority: (authority ?? deployer).publicKey,
+          protocolConfig: protocolConfigPda,
+        })
+        .signers(authority ? [authority] : [])
+        .rpc();
+    const steep = {
+      linear: { startPrice: new anchor.BN(1_000_000), endPrice: new anchor.BN(2_000_000_000_000) },
+    };
+
+    try {
+      await launchOpen({ curveKind: steep });
+      assert.fail("Should have thrown InvalidCurve");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidCurve");
+    }
+
+    const widened = { ...defaults, maxPrice: new anchor.BN(2_000_000_000_000) };
+    await setBounds(widened);
+    const config = await (program.account as any).protocolConfig.fetch(protocolConfigPda);
+    assert.equal(config.curveBounds.maxPrice.toString(), widened.maxPrice.toString());
+    await launchOpen({ curveKind: steep });
+
+    const rejected: [string, Record<string, any>][] = [
+      ["zero min price", { ...defaults, minPrice: new anchor.BN(0) }],
+      ["min above max", { ...defaults, minPrice: defaults.maxPrice.addn(1) }],
+      ["max price ceiling", { ...defaults, maxPrice: new anchor.BN("1000000000000001") }],
+      ["growth floor", { ...defaults, maxGrowthWad: new anchor.BN("999999999999999") }],
+      ["growth ceiling", { ...defaults, maxGrowthWad: new anchor.BN("10000000000000000001") }],
+    ];
+    for (const [label, bounds] of rejected) {
+      try {
+        await setBounds(bounds);
+        assert.fail(`Should have rejected ${label}`);
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidCurve", label);
+      }
+    }
+
+    try {
+      await setBounds(defaults, await fundedWallet(1));
+      assert.fail("Should have thrown Unauthorized");
+    } catch (err: any) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+
+    await setBounds(defaults);
+    console.log("  Curve bounds widened, ceilings enforced, authority required");
+  });
+
   // ─── 8. Claim Fees (progressive unlock) ──────────────────────────
 
   it("8. Claim deployer fees (progressive unlock)", async () => {
@@ -446,6 +1235,48 @@ describe("FYRST CPMM E2E Tests", () => {
     );
   });
 
+  it("8b. Curve threshold override is bounded and never underflows claimable fees", async () => {
+    const setThreshold = (lamports: anchor.BN) =>
+      (program.methods as any)
+        .setCurveGraduationThreshold(lamports)
+        .accounts({
+          authority: deployer.publicKey,
+          protocolConfig: protocolConfigPda,
+          bondingCurve: curvePda,
+        })
+        .rpc();
+
+    // Far above what selling out the whole curve would raise
+    try {
+      await setThreshold(new anchor.BN("1000000000000000000"));
+      assert.fail("Should have thrown InvalidThreshold");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidThreshold");
+    }
+
+    // Raising after a claim leaves unlocked below claimed: nothing to claim, no error
+    await setThreshold(new anchor.BN(50 * LAMPORTS_PER_SOL));
+    const claimable = await (program.methods as any)
+      .quoteClaimableFees()
+      .accounts({ bondingCurve: curvePda })
+      .view();
+    const curve = await (program.account as any).bondingCurve.fetch(curvePda);
+    const unlocked = curve.totalDeployerFees
+      .mul(curve.maxReserveReached)
+      .div(curve.graduationThreshold);
+    if (unlocked.lt(curve.claimedDeployerFees)) {
+      assert.equal(claimable.toNumber(), 0);
+    } else {
+      assert.equal(
+        claimable.toNumber(),
+        unlocked.sub(curve.claimedDeployerFees).toNumber()
+      );
+    }
+
+    await setThreshold(new anchor.BN(5_000_000_000));
+    console.log(`  Override bounded; claimable at 50 SOL threshold = ${claimable.toNumber()}`);
+  });
+
   // ─── 9. Release Escrow Blocked (not graduated) ──────────────────
 
   it("9. Release escrow blocked — token not graduated", async () => {
@@ -466,6 +1297,58 @@ describe("FYRST CPMM E2E Tests", () => {
     }
   });
 
+  it("9b. Claim unlocked collateral blocked — token not graduated", async () => {
+    try {
+      await (program.methods as any)
+        .claimUnlockedCollateral()
+        .accounts({
+          deployer: deployer.publicKey,
+          escrowVault: escrowPda,
+          bondingCurve: curvePda,
+        })
+        .rpc();
+      assert.fail("Should have thrown NotGraduated");
+    } catch (err: any) {
+      assert.include(err.toString(), "NotGraduated");
+      console.log("  Correctly blocked: collateral stays locked before graduation");
+    }
+  });
+
+  it("9c. Slash escrow blocked — deployer has not dumped", async () => {
+    try {
+      await (program.methods as any)
+        .slashEscrow()
+        .accounts({
+          slasher: buyer.publicKey,
+          escrowVault: escrowPda,
+          bondingCurve: curvePda,
+          deployerTokenAccount: getAssociatedTokenAddressSync(
+            tokenMint.publicKey,
+            deployer.publicKey
+          ),
+        })
+        .signers([buyer])
+        .rpc();
+      assert.fail("Should have thrown DeployerNotDumped");
+    } catch (err: any) {
+      assert.include(err.toString(), "DeployerNotDumped");
+      console.log("  Correctly blocked: deployer still holds their tokens");
+    }
+  });
+
+  it("9d. Mark failed blocked — deadline not reached", async () => {
+    try {
+      await (program.methods as any)
+        .markFailed()
+        .accounts({ bondingCurve: curvePda })
+        .rpc();
+      assert.fail("Should have thrown DeadlineNotReached");
+    } catch (err: any) {
+      assert.include(err.toString(), "DeadlineNotReached");
+      console.log("  Correctly blocked: launch still live");
+    }
+  });
+
   // ─── 10. Refund blocked — deadline not reached ─────────────────
 
   it("10. Refund blocked — deadline not reached yet (burn-to-refund)", async () => {
@@ -483,6 +1366,7 @@ describe("FYRST CPMM E2E Tests", () => {
           bondingCurve: curvePda,
           tokenMint: tokenMint.publicKey,
           buyerTokenAccount: buyerAta,
+          position: getPositionPDA(curvePda, buyer.publicKey),
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -494,4 +1378,1155 @@ describe("FYRST CPMM E2E Tests", () => {
       console.log("  Correctly blocked: deadline (1h) has not passed yet");
     }
   });
+
+  it("10b. Refund snapshot blocked — deadline not reached yet", async () => {
+    const [snapshotPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("refund_snapshot"), escrowPda.toBuffer()],
+      program.programId
+    );
+
+    try {
+      await (program.methods as any)
+        .postRefundSnapshot(Array(32).fill(1), 1, new anchor.BN(1))
+        .accounts({
+          authority: deployer.publicKey,
+          protocolConfig: protocolConfigPda,
+          escrowVault: escrowPda,
+          bondingCurve: curvePda,
+          refundSnapshot: snapshotPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      assert.fail("Should have thrown DeadlineNotReached");
+    } catch (err: any) {
+      assert.include(err.toString(), "DeadlineNotReached");
+      console.log("  Correctly blocked: launch has not failed");
+    }
+  });
+
+  // ─── 11. Failed Launch Refunds ──────────────────────────────────
+
+  function refundAccounts(mint: PublicKey, holder: PublicKey) {
+    const accounts = launchAccounts(mint);
+    return {
+      buyer: holder,
+      escrowVault: accounts.escrowVault,
+      bondingCurve: accounts.bondingCurve,
+      tokenMint: mint,
+      buyerTokenAccount: getAssociatedTokenAddressSync(mint, holder),
+      position: getPositionPDA(accounts.bondingCurve, holder),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+  }
+
+  /** Launch with the minimum 60s deadline, let each holder buy, run
+   *  `beforeDeadline`, then wait until the launch has failed */
+  async function launchAndFail(
+    holders: [Keypair, number][],
+    overrides: Record<string, any> = {},
+    beforeDeadline?: (mint: PublicKey, bondingCurve: PublicKey) => Promise<void>
+  ) {
+    const mint = Keypair.generate();
+    const accounts = launchAccounts(mint.publicKey);
+    await (program.methods as any)
+      .launchToken({ ...launchArgs(0.1 * LAMPORTS_PER_SOL, 60), ...overrides })
+      .accounts(accounts)
+      .signers([mint])
+      .rpc();
+
+    for (const [holder, lamports] of holders) {
+      await (program.methods as any)
+        .buyTokens(new anchor.BN(lamports), new anchor.BN(0))
+        .accounts(buyAccounts(mint.publicKey, accounts.bondingCurve, holder.publicKey))
+        .signers([holder])
+        .rpc();
+    }
+
+    if (beforeDeadline) await beforeDeadline(mint.publicKey, accounts.bondingCurve);
+    await waitPastDeadline(accounts.bondingCurve);
+    return { mint: mint.publicKey, ...accounts };
+  }
+
+  async function waitPastDeadline(bondingCurve: PublicKey) {
+    const curve = await (program.account as any).bondingCurve.fetch(bondingCurve);
+    const deadline = curve.deadlineTimestamp.toNumber();
+    for (;;) {
+      const now = await provider.connection.getBlockTime(
+        await provider.connection.getSlot()
+      );
+      if (now !== null && now > deadline) break;
+      await new Promise((resolve) => setTimeout(resolve, 2_000));
+    }
+  }
+
+  it("11. Failed launch refund pays the escrow and the curve reserve", async () => {
+    const holder = await fundedWallet(1);
+    const launch = await launchAndFail([[holder, 0.2 * LAMPORTS_PER_SOL]]);
+
+    const curveBefore = await (program.account as any).bondingCurve.fetch(
+      launch.bondingCurve
+    );
+    const escrowInfo = await provider.connection.getAccountInfo(launch.escrowVault);
+    const escrowRent = await provider.connection.getMinimumBalanceForRentExemption(
+      escrowInfo!.data.length
+    );
+    const holderBefore = await provider.connection.getBalance(holder.publicKey);
+    assert.isAbove(curveBefore.reserveBalance.toNumber(), 0);
+
+    const quote = await (program.methods as any)
+      .quoteRefund(new anchor.BN((await tokenBalance(launch.mint, holder.publicKey)).toString()))
+      .accounts({
+        bondingCurve: launch.bondingCurve,
+        escrowVault: launch.escrowVault,
+        position: null,
+      })
+      .view();
+    assert.isTrue(quote.available);
+
+    await (program.methods as any)
+      .processRefund()
+      .accounts(refundAccounts(launch.mint, holder.publicKey))
+      .signers([holder])
+      .rpc();
+
+    // Sole holder: the whole reserve and the escrow above rent
+    const curveAfter = await (program.account as any).bondingCurve.fetch(
+      launch.bondingCurve
+    );
+    const holderAfter = await provider.connection.getBalance(holder.publicKey);
+    const escrowAfter = await provider.connection.getBalance(launch.escrowVault);
+    assert.equal(curveAfter.reserveBalance.toNumber(), 0);
+    assert.equal(escrowAfter, escrowRent);
+    const expected =
+      escrowInfo!.lamports -
+      escrowRent +
+      curveBefore.reserveBalance.toNumber() +
+      curveBefore.holderPool.toNumber();
+    assert.equal(quote.solRefunded.toNumber(), expected);
+    // Less the transaction fee
+    assert.isAbove(holderAfter - holderBefore, expected - 10_000);
+
+    console.log(
+      `  Refunded ${holderAfter - holderBefore} lamports (reserve ${curveBefore.reserveBalance.toNumber()})`
+    );
+  });
+
+  it("11b. A late batch-opening settlement cannot graduate a failed launch", async () => {
+    const committer = await fundedWallet(1);
+    const mint = Keypair.generate();
+    const accounts = launchAccounts(mint.publicKey);
+    await (program.methods as any)
+      .launchToken({
+        ...launchArgs(0.1 * LAMPORTS_PER_SOL, 60),
+        openingSeconds: new anchor.BN(3),
+      })
+      .accounts(accounts)
+      .signers([mint])
+      .rpc();
+
+    const [commit] = PublicKey.findProgramAddressSync(
+      [Buffer.from("commit"), accounts.bondingCurve.toBuffer(), committer.publicKey.toBuffer()],
+      program.programId
+    );
+    await (program.methods as any)
+      .commitOpening(new anchor.BN(0.05 * LAMPORTS_PER_SOL))
+      .accounts({
+        committer: committer.publicKey,
+        bondingCurve: accounts.bondingCurve,
+        commit,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([committer])
+      .rpc();
+
+    // The committed SOL alone would meet this threshold
+    await (program.methods as any)
+      .setCurveGraduationThreshold(new anchor.BN(0.01 * LAMPORTS_PER_SOL))
+      .accounts({
+        authority: deployer.publicKey,
+        protocolConfig: protocolConfigPda,
+        bondingCurve: accounts.bondingCurve,
+      })
+      .rpc();
+
+    await waitPastDeadline(accounts.bondingCurve);
+    await (program.methods as any)
+      .settleOpening()
+      .accounts({ bondingCurve: accounts.bondingCurve })
+      .rpc();
+
+    const curve = await (program.account as any).bondingCurve.fetch(
+      accounts.bondingCurve
+    );
+    assert.isTrue(curve.openingSettled);
+    assert.isFalse(curve.graduated);
+
+    await (program.methods as any)
+      .markFailed()
+      .accounts({ bondingCurve: accounts.bondingCurve })
+      .rpc();
+    try {
+      await (program.methods as any)
+        .markFailed()
+        .accounts({ bondingCurve: accounts.bondingCurve })
+        .rpc();
+      assert.fail("Should have thrown AlreadyMarkedFailed");
+    } catch (err: any) {
+      assert.include(err.toString(), "AlreadyMarkedFailed");
+    }
+
+    console.log("  Settled after the deadline without graduating; failure recorded once");
+  });
+
+  it("11c. Contribution refunds pay buyers, not tokens passed on and sold elsewhere", async () => {
+    const honest = await fundedWallet(1);
+    const launderer = await fundedWallet(1);
+    const seller = await fundedWallet(0.1);
+
+    // The launderer hands its tokens to a fresh wallet, which sells them
+    const launch = await launchAndFail(
+      [
+        [launderer, 0.2 * LAMPORTS_PER_SOL],
+        [honest, 0.1 * LAMPORTS_PER_SOL],
+      ],
+      { refundMode: { contribution: {} } },
+      async (mint, bondingCurve) => {
+        const from = getAssociatedTokenAddressSync(mint, launderer.publicKey);
+        const to = await createAssociatedTokenAccount(
+          provider.connection,
+          seller,
+          mint,
+          seller.publicKey
+        );
+        const { amount } = await getAccount(provider.connection, from);
+        await transfer(provider.connection, launderer, from, to, launderer, amount);
+        await (program.methods as any)
+          .sellTokens(new anchor.BN(amount.toString()), new anchor.BN(0))
+          .accounts({
+            seller: seller.publicKey,
+            bondingCurve,
+            tokenMint: mint,
+            curveTokenAccount: getAssociatedTokenAddressSync(mint, bondingCurve, true),
+            sellerTokenAccount: to,
+            position: getPositionPDA(bondingCurve, seller.publicKey),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([seller])
+          .rpc();
+      }
+    );
+
+    const launderBefore = await provider.connection.getBalance(launderer.publicKey);
+    await (program.methods as any)
+      .processRefund()
+      .accounts(refundAccounts(launch.mint, launderer.publicKey))
+      .signers([launderer])
+      .rpc();
+    const launderAfter = await provider.connection.getBalance(launderer.publicKey);
+    // Nothing back but the contribution is cleared
+    assert.isAtMost(launderAfter, launderBefore);
+
+    const honestBefore = await provider.connection.getBalance(honest.publicKey);
+    await (program.methods as any)
+      .processRefund()
+      .accounts(refundAccounts(launch.mint, honest.publicKey))
+      .signers([honest])
+      .rpc();
+    const honestAfter = await provider.connection.getBalance(honest.publicKey);
+    assert.isAbove(honestAfter - honestBefore, 0.05 * LAMPORTS_PER_SOL);
+
+    const curve = await (program.account as any).bondingCurve.fetch(
+      launch.bondingCurve
+    );
+    assert.equal(curve.totalNetContributed.toNumber(), 0);
+    console.log(`  Honest buyer refunded ${honestAfter - honestBefore} lamports; launderer 0`);
+  });
+
+  /** Snapshot leaf: keccak(index_le || wallet || weight_le) */
+  function snapshotLeaf(index: number, wallet: PublicKey, weight: anchor.BN): Buffer {
+    const indexBytes = Buffer.alloc(4);
+    indexBytes.writeUInt32LE(index);
+    return Buffer.from(
+      keccak_256(
+        Buffer.concat([indexBytes, wallet.toBuffer(), weight.toArrayLike(Buffer, "le", 8)])
+      )
+    );
+  }
+
+  /** Sorted-pair keccak Merkle layers, leaves first; an odd node is carried up */
+  function merkleLayers(leaves: Buffer[]): Buffer[][] {
+    const layers = [leaves];
+    while (layers[layers.length - 1].length > 1) {
+      const level = layers[layers.length - 1];
+      const next: Buffer[] = [];
+      for (let i = 0; i < level.length; i += 2) {
+        if (i + 1 === level.length) {
+          next.push(level[i]);
+          continue;
+        }
+        const [a, b] = Buffer.compare(level[i], level[i + 1]) <= 0
+          ? [level[i], level[i + 1]]
+          : [level[i + 1], level[i]];
+        next.push(Buffer.from(keccak_256(Buffer.concat([a, b]))));
+      }
+      layers.push(next);
+    }
+    return layers;
+  }
+
+  function merkleProof(layers: Buffer[][], index: number): number[][] {
+    const proof: number[][] = [];
+    for (const level of layers.slice(0, -1)) {
+      const sibling = index ^ 1;
+      if (sibling < level.length) proof.push([...level[sibling]]);
+      index >>= 1;
+    }
+    return proof;
+  }
+
+  it("11d. Snapshot refunds pay by claim and by push, then the snapshot closes", async () => {
+    const first = await fundedWallet(1);
+    const second = await fundedWallet(1);
+    const empty = Keypair.generate();
+    const launch = await launchAndFail([
+      [first, 0.2 * LAMPORTS_PER_SOL],
+      [second, 0.1 * LAMPORTS_PER_SOL],
+    ]);
+
+    // Token balances as weights; the empty wallet's dust share is below rent
+    const weights: [PublicKey, anchor.BN][] = [];
+    for (const holder of [first, second]) {
+      const { amount } = await getAccount(
+        provider.connection,
+        getAssociatedTokenAddressSync(launch.mint, holder.publicKey)
+      );
+      weights.push([holder.publicKey, new anchor.BN(amount.toString())]);
+    }
+    // A ~0.01% share of a ~0.4 SOL pool: non-zero but below rent
+    weights.push([empty.publicKey, weights[0][1].add(weights[1][1]).divn(10_000)]);
+    const totalWeight = weights.reduce((sum, [, w]) => sum.add(w), new anchor.BN(0));
+    const layers = merkleLayers(weights.map(([wallet, w], i) => snapshotLeaf(i, wallet, w)));
+    const claim = (index: number) => ({
+      index,
+      weight: weights[index][1],
+      proof: merkleProof(layers, index),
+    });
+
+    const [refundSnapshot] = PublicKey.findProgramAddressSync(
+      [Buffer.from("refund_snapshot"), launch.escrowVault.toBuffer()],
+      program.programId
+    );
+    await (program.methods as any)
+      .postRefundSnapshot([...layers[layers.length - 1][0]], weights.length, totalWeight)
+      .accounts({
+        authority: deployer.publicKey,
+        protocolConfig: protocolConfigPda,
+        escrowVault: launch.escrowVault,
+        bondingCurve: launch.bondingCurve,
+        refundSnapshot,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    const snapshot = await (program.account as any).refundSnapshot.fetch(refundSnapshot);
+    const share = (index: number) =>
+      weights[index][1].mul(snapshot.poolLamports).div(totalWeight).toNumber();
+
+    // First holder claims by proof; the fee payer is the provider wallet
+    const firstBefore = await provider.connection.getBalance(first.publicKey);
+    await (program.methods as any)
+      .claimSnapshotRefund(claim(0))
+      .accounts({ holder: first.publicKey, refundSnapshot, bondingCurve: launch.bondingCurve })
+      .rpc();
+    const firstAfter = await provider.connection.getBalance(first.publicKey);
+    assert.equal(firstAfter - firstBefore, share(0));
+
+    // The crank pays the second holder and skips the empty wallet's dust
+    const pushed = [
+      { pubkey: second.publicKey, isSigner: false, isWritable: true },
+      { pubkey: empty.publicKey, isSigner: false, isWritable: true },
+    ];
+    const secondBefore = await provider.connection.getBalance(second.publicKey);
+    await (program.methods as any)
+      .pushSnapshotRefunds([claim(1), claim(2)])
+      .accounts({
+        cranker: deployer.publicKey,
+        refundSnapshot,
+        bondingCurve: launch.bondingCurve,
+      })
+      .remainingAccounts(pushed)
+      .rpc();
+    const secondAfter = await provider.connection.getBalance(second.publicKey);
+    assert.equal(secondAfter - secondBefore, share(1));
+    assert.equal(await provider.connection.getBalance(empty.publicKey), 0);
+
+    const closeAccounts = {
+      deployer: deployer.publicKey,
+      escrowVault: launch.escrowVault,
+      bondingCurve: launch.bondingCurve,
+      refundSnapshot,
+      protocolConfig: protocolConfigPda,
+      treasury: treasury.publicKey,
+    };
+    try {
+      await (program.methods as any).closeRefundSnapshot().accounts(closeAccounts).rpc();
+      assert.fail("Should have thrown SnapshotClaimsOpen");
+    } catch (err: any) {
+      assert.include(err.toString(), "SnapshotClaimsOpen");
+    }
+
+    // Once funded, the third wallet is paid on the next push
+    const sig = await provider.connection.requestAirdrop(empty.publicKey, 0.01 * LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+    await (program.methods as any)
+      .pushSnapshotRefunds([claim(2)])
+      .accounts({
+        cranker: deployer.publicKey,
+        refundSnapshot,
+        bondingCurve: launch.bondingCurve,
+      })
+      .remainingAccounts(pushed.slice(1))
+      .rpc();
+    const paid = await (program.account as any).refundSnapshot.fetch(refundSnapshot);
+    assert.equal(paid.claimedCount, 3);
+    assert.isAbove(share(2), 0);
+
+    const treasuryBefore = await provider.connection.getBalance(treasury.publicKey);
+    const snapshotLamports = await provider.connection.getBalance(refundSnapshot);
+    await (program.methods as any).closeRefundSnapshot().accounts(closeAccounts).rpc();
+    const treasuryAfter = await provider.connection.getBalance(treasury.publicKey);
+    assert.equal(treasuryAfter - treasuryBefore, snapshotLamports);
+    assert.isNull(await provider.connection.getAccountInfo(refundSnapshot));
+    assert.isNull(await provider.connection.getAccountInfo(launch.escrowVault));
+
+    console.log(
+      `  Claimed ${share(0)}, pushed ${share(1)} lamports; snapshot and escrow closed`
+    );
+  });
+
+  function snapshotPda(escrowVault: PublicKey): PublicKey {
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("refund_snapshot"), escrowVault.toBuffer()],
+      program.programId
+    );
+    return pda;
+  }
+
+  function postSnapshot(
+    launch: { escrowVault: PublicKey; bondingCurve: PublicKey },
+    root: Buffer,
+    leafCount: number,
+    totalWeight: anchor.BN
+  ) {
+    return (program.methods as any)
+      .postRefundSnapshot([...root], leafCount, totalWeight)
+      .accounts({
+        authority: deployer.publicKey,
+        protocolConfig: protocolConfigPda,
+        escrowVault: launch.escrowVault,
+        bondingCurve: launch.bondingCurve,
+        refundSnapshot: snapshotPda(launch.escrowVault),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  it("11e. Snapshot refunds pay only system wallets and never past the total weight", async () => {
+    const holder = await fundedWallet(1);
+    const other = await fundedWallet(1);
+    const launch = await launchAndFail([[holder, 0.1 * LAMPORTS_PER_SOL]]);
+
+    // Leaf 1 names the holder's token account, owned by the token program.
+    // The root over-sums: three leaves of `weight` against 1.5 × weight.
+    const holderAta = getAssociatedTokenAddressSync(launch.mint, holder.publicKey);
+    const weight = new anchor.BN((await tokenBalance(launch.mint, holder.publicKey)).toString());
+    const wallets = [holder.publicKey, holderAta, other.publicKey];
+    const layers = merkleLayers(wallets.map((wallet, i) => snapshotLeaf(i, wallet, weight)));
+    const claim = (index: number) => ({ index, weight, proof: merkleProof(layers, index) });
+    const totalWeight = weight.muln(3).divn(2);
+    await postSnapshot(launch, layers[layers.length - 1][0], wallets.length, totalWeight);
+    const refundSnapshot = snapshotPda(launch.escrowVault);
+
+    // A program-owned holder can be neither pushed to nor claimed for
+    try {
+      await (program.methods as any)
+        .pushSnapshotRefunds([claim(1)])
+        .accounts({
+          cranker: deployer.publicKey,
+          refundSnapshot,
+          bondingCurve: launch.bondingCurve,
+        })
+        .remainingAccounts([{ pubkey: holderAta, isSigner: false, isWritable: true }])
+        .rpc();
+      assert.fail("Should have thrown HolderAccountsMismatch");
+    } catch (err: any) {
+      assert.include(err.toString(), "HolderAccountsMismatch");
+    }
+    try {
+      await (program.methods as any)
+        .claimSnapshotRefund(claim(1))
+        .accounts({ holder: holderAta, refundSnapshot, bondingCurve: launch.bondingCurve })
+        .rpc();
+      assert.fail("Should have thrown AccountNotSystemOwned");
+    } catch (err: any) {
+      assert.include(err.toString(), "AccountNotSystemOwned");
+    }
+
+    // The first system-wallet claim fits the total weight; the second would not
+    await (program.methods as any)
+      .claimSnapshotRefund(claim(0))
+      .accounts({ holder: holder.publicKey, refundSnapshot, bondingCurve: launch.bondingCurve })
+      .rpc();
+    try {
+      await (program.methods as any)
+        .claimSnapshotRefund(claim(2))
+        .accounts({ holder: other.publicKey, refundSnapshot, bondingCurve: launch.bondingCurve })
+        .rpc();
+      assert.fail("Should have thrown InvalidSnapshot");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidSnapshot");
+    }
+
+    const snapshot = await (program.account as any).refundSnapshot.fetch(refundSnapshot);
+    assert.equal(snapshot.claimedCount, 1);
+    assert.equal(snapshot.claimedWeight.toString(), weight.toString());
+    assert.isAtMost(snapshot.paidLamports.toNumber(), snapshot.poolLamports.toNumber());
+
+    console.log("  Token-account leaf unpayable; over-summed root capped at its total weight");
+  });
+
+  it("11f. Snapshot waits for the batch opening to settle and be claimed", async () => {
+    const committer = await fundedWallet(1);
+    const mint = Keypair.generate();
+    const accounts = launchAccounts(mint.publicKey);
+    await (program.methods as any)
+      .launchToken({
+        ...launchArgs(0.1 * LAMPORTS_PER_SOL, 60),
+        openingSeconds: new anchor.BN(3),
+      })
+      .accounts(accounts)
+      .signers([mint])
+      .rpc();
+
+    const [commit] = PublicKey.findProgramAddressSync(
+      [Buffer.from("commit"), accounts.bondingCurve.toBuffer(), committer.publicKey.toBuffer()],
+      program.programId
+    );
+    await (program.methods as any)
+      .commitOpening(new anchor.BN(0.05 * LAMPORTS_PER_SOL))
+      .accounts({
+        committer: committer.publicKey,
+        bondingCurve: accounts.bondingCurve,
+        commit,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([committer])
+      .rpc();
+    await waitPastDeadline(accounts.bondingCurve);
+
+    const root = snapshotLeaf(0, committer.publicKey, new anchor.BN(1));
+    const post = () => postSnapshot(accounts, root, 1, new anchor.BN(1));
+    try {
+      await post();
+      assert.fail("Should have thrown OpeningNotSettled");
+    } catch (err: any) {
+      assert.include(err.toString(), "OpeningNotSettled");
+    }
+
+    await (program.methods as any)
+      .settleOpening()
+      .accounts({ bondingCurve: accounts.bondingCurve })
+      .rpc();
+    try {
+      await post();
+      assert.fail("Should have thrown OpeningTokensUnclaimed");
+    } catch (err: any) {
+      assert.include(err.toString(), "OpeningTokensUnclaimed");
+    }
+
+    await (program.methods as any)
+      .claimOpening()
+      .accounts({
+        committer: committer.publicKey,
+        bondingCurve: accounts.bondingCurve,
+        tokenMint: mint.publicKey,
+        curveTokenAccount: accounts.curveTokenAccount,
+        committerTokenAccount: getAssociatedTokenAddressSync(mint.publicKey, committer.publicKey),
+        commit,
+        position: getPositionPDA(accounts.bondingCurve, committer.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([committer])
+      .rpc();
+    await post();
+
+    const snapshot = await (program.account as any).refundSnapshot.fetch(
+      snapshotPda(accounts.escrowVault)
+    );
+    assert.isAbove(snapshot.poolLamports.toNumber(), 0);
+
+    console.log("  Snapshot posted only once the opening was settled and claimed");
+  });
+
+  // ─── 12. Exact-Output Trades and Quotes ─────────────────────────
+
+  async function launchOpen(overrides: Record<string, any> = {}) {
+    const mint = Keypair.generate();
+    const accounts = launchAccounts(mint.publicKey);
+    await (program.methods as any)
+      .launchToken({ ...launchArgs(0.1 * LAMPORTS_PER_SOL, 3600), ...overrides })
+      .accounts(accounts)
+      .signers([mint])
+      .rpc();
+    return { mint: mint.publicKey, ...accounts };
+  }
+
+  function buyAccounts(mint: PublicKey, curve: PublicKey, holder: PublicKey) {
+    return {
+      buyer: holder,
+      bondingCurve: curve,
+      tokenMint: mint,
+      curveTokenAccount: getAssociatedTokenAddressSync(mint, curve, true),
+      buyerTokenAccount: getAssociatedTokenAddressSync(mint, holder),
+      position: getPositionPDA(curve, holder),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+  }
+
+  async function tokenBalance(mint: PublicKey, owner: PublicKey): Promise<bigint> {
+    const { amount } = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(mint, owner)
+    );
+    return amount;
+  }
+
+  it("12. Exact-output buy delivers the requested tokens within max_sol_in", async () => {
+    const holder = await fundedWallet(1);
+    const launch = await launchOpen();
+    const accounts = buyAccounts(launch.mint, launch.bondingCurve, holder.publicKey);
+    const tokenAmount = new anchor.BN(5_000_000_000);
+
+    try {
+      await (program.methods as any)
+        .buyExactTokens(tokenAmount, new anchor.BN(1))
+        .accounts(accounts)
+        .signers([holder])
+        .rpc();
+      assert.fail("Should have thrown SlippageExceeded");
+    } catch (err: any) {
+      assert.include(err.toString(), "SlippageExceeded");
+    }
+
+    const maxSolIn = 0.5 * LAMPORTS_PER_SOL;
+    const holderBefore = await provider.connection.getBalance(holder.publicKey);
+    await (program.methods as any)
+      .buyExactTokens(tokenAmount, new anchor.BN(maxSolIn))
+      .accounts(accounts)
+      .signers([holder])
+      .rpc();
+    const holderAfter = await provider.connection.getBalance(holder.publicKey);
+
+    assert.equal(
+      (await tokenBalance(launch.mint, holder.publicKey)).toString(),
+      tokenAmount.toString()
+    );
+    // The holder also paid rent for its token account and position
+    const rent =
+      (await provider.connection.getBalance(accounts.buyerTokenAccount)) +
+      (await provider.connection.getBalance(accounts.position));
+    const solIn = holderBefore - holderAfter - rent;
+    assert.isAbove(solIn, 0);
+    assert.isAtMost(solIn, maxSolIn);
+
+    console.log(`  Bought exactly ${tokenAmount.toString()} tokens for ${solIn} lamports`);
+  });
+
+  function sellAccounts(mint: PublicKey, curve: PublicKey, holder: PublicKey) {
+    return {
+      seller: holder,
+      bondingCurve: curve,
+      tokenMint: mint,
+      curveTokenAccount: getAssociatedTokenAddressSync(mint, curve, true),
+      sellerTokenAccount: getAssociatedTokenAddressSync(mint, holder),
+      position: getPositionPDA(curve, holder),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+  }
+
+  it("12b. Exact-output sell pays the requested SOL within max_tokens_in", async () => {
+    const holder = await fundedWallet(1);
+    const launch = await launchOpen();
+    await (program.methods as any)
+      .buyTokens(new anchor.BN(0.3 * LAMPORTS_PER_SOL), new anchor.BN(0))
+      .accounts(buyAccounts(launch.mint, launch.bondingCurve, holder.publicKey))
+      .signers([holder])
+      .rpc();
+    const accounts = sellAccounts(launch.mint, launch.bondingCurve, holder.publicKey);
+    const solOut = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+
+    try {
+      await (program.methods as any)
+        .sellForExactSol(solOut, new anchor.BN(1))
+        .accounts(accounts)
+        .signers([holder])
+        .rpc();
+      assert.fail("Should have thrown SlippageExceeded");
+    } catch (err: any) {
+      assert.include(err.toString(), "SlippageExceeded");
+    }
+
+    const tokensBefore = await tokenBalance(launch.mint, holder.publicKey);
+    const holderBefore = await provider.connection.getBalance(holder.publicKey);
+    await (program.methods as any)
+      .sellForExactSol(solOut, new anchor.BN(tokensBefore.toString()))
+      .accounts(accounts)
+      .signers([holder])
+      .rpc();
+    const holderAfter = await provider.connection.getBalance(holder.publicKey);
+    const tokensIn = tokensBefore - (await tokenBalance(launch.mint, holder.publicKey));
+
+    // The provider pays the transaction fee, so the balance moves by the payout alone
+    assert.equal(holderAfter - holderBefore, solOut.toNumber());
+    assert.isTrue(tokensIn > 0n && tokensIn < tokensBefore);
+
+    console.log(`  Sold ${tokensIn} tokens for exactly ${solOut.toNumber()} lamports`);
+  });
+
+  it("12c. Buy and sell quotes match the executed trades", async () => {
+    const holder = await fundedWallet(1);
+    const launch = await launchOpen();
+    const solAmount = new anchor.BN(0.2 * LAMPORTS_PER_SOL);
+
+    const buyQuote = await (program.methods as any)
+      .quoteBuy(solAmount)
+      .accounts({ bondingCurve: launch.bondingCurve })
+      .view();
+    // Slippage floor exactly at the quote: passes only if the buy matches it
+    await (program.methods as any)
+      .buyTokens(solAmount, buyQuote.tokens)
+      .accounts(buyAccounts(launch.mint, launch.bondingCurve, holder.publicKey))
+      .signers([holder])
+      .rpc();
+    const bought = await tokenBalance(launch.mint, holder.publicKey);
+    assert.equal(bought.toString(), buyQuote.tokens.toString());
+
+    const sellAmount = new anchor.BN((bought / 2n).toString());
+    const sellQuote = await (program.methods as any)
+      .quoteSell(sellAmount)
+      .accounts({ bondingCurve: launch.bondingCurve })
+      .view();
+    const holderBefore = await provider.connection.getBalance(holder.publicKey);
+    await (program.methods as any)
+      .sellTokens(sellAmount, sellQuote.netSol)
+      .accounts(sellAccounts(launch.mint, launch.bondingCurve, holder.publicKey))
+      .signers([holder])
+      .rpc();
+    const holderAfter = await provider.connection.getBalance(holder.publicKey);
+    assert.equal(holderAfter - holderBefore, sellQuote.netSol.toNumber());
+    assert.equal(
+      sellQuote.grossSol.sub(sellQuote.netSol).toNumber(),
+      sellQuote.fees.tradeFee.add(sellQuote.fees.protocolFee).toNumber()
+    );
+
+    console.log(
+      `  Quoted and received ${buyQuote.tokens.toString()} tokens, then ${sellQuote.netSol.toNumber()} lamports`
+    );
+  });
+
+  // ─── 13. Deploy Fee Discounts ───────────────────────────────────
+
+  /** Launch and return what the treasury (also the ops wallet here) received */
+  async function deployFeePaid(accountOverrides: Record<string, any>): Promise<number> {
+    const mint = Keypair.generate();
+    const before = await provider.connection.getBalance(treasury.publicKey);
+    await (program.methods as any)
+      .launchToken(launchArgs(0.1 * LAMPORTS_PER_SOL, 3600))
+      .accounts({ ...launchAccounts(mint.publicKey), ...accountOverrides })
+      .signers([mint])
+      .rpc();
+    return (await provider.connection.getBalance(treasury.publicKey)) - before;
+  }
+
+  it("13. Deploy fee is charged in full, less a holder discount, and waived", async () => {
+    const config = await (program.account as any).protocolConfig.fetch(protocolConfigPda);
+    const deployFee = config.feeSchedule.deployFee.toNumber();
+    assert.isAbove(deployFee, 0);
+    assert.equal(await deployFeePaid({}), deployFee);
+
+    // $FYRST holder discount: 25% off above a 1,000-token balance
+    const issuer = await fundedWallet(1);
+    const fyrstMint = await createMint(provider.connection, issuer, issuer.publicKey, null, 6);
+    const deployerFyrstAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      issuer,
+      fyrstMint,
+      deployer.publicKey
+    );
+    await mintTo(provider.connection, issuer, fyrstMint, deployerFyrstAccount, issuer, 1_000_000_000);
+    const setHolderDiscount = (discount: Record<string, any>) =>
+      (program.methods as any)
+        .updateHolderDiscount(discount)
+        .accounts({ authority: deployer.publicKey, protocolConfig: protocolConfigPda })
+        .rpc();
+    await setHolderDiscount({
+      fyrstMint,
+      minBalance: new anchor.BN(1_000_000_000),
+      discountBps: new anchor.BN(2_500),
+    });
+    assert.equal(
+      await deployFeePaid({ deployerFyrstAccount }),
+      Math.floor((deployFee * 7_500) / 10_000)
+    );
+
+    // An authority waiver beats the holder discount when it is larger
+    const [feeWaiver] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_waiver"), deployer.publicKey.toBuffer()],
+      program.programId
+    );
+    const setWaiver = (discountBps: number) =>
+      (program.methods as any)
+        .setDeployFeeWaiver(deployer.publicKey, new anchor.BN(discountBps), new anchor.BN(0))
+        .accounts({
+          authority: deployer.publicKey,
+          protocolConfig: protocolConfigPda,
+          feeWaiver,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    await setWaiver(10_000);
+    assert.equal(await deployFeePaid({ deployerFyrstAccount, feeWaiver }), 0);
+
+    await setWaiver(0);
+    await setHolderDiscount({
+      fyrstMint: PublicKey.default,
+      minBalance: new anchor.BN(0),
+      discountBps: new anchor.BN(0),
+    });
+    console.log(`  Deploy fee ${deployFee}, 25% holder discount, full waiver`);
+  });
+
+  // ─── 14. Sniper Tax ─────────────────────────────────────────────
+
+  async function chainTime(): Promise<number> {
+    for (;;) {
+      const now = await provider.connection.getBlockTime(
+        await provider.connection.getSlot()
+      );
+      if (now !== null) return now;
+    }
+  }
+
+  async function waitUntil(timestamp: number) {
+    while ((await chainTime()) < timestamp) {
+      await new Promise((resolve) => setTimeout(resolve, 1_000));
+    }
+  }
+
+  it("14. Sniper tax starts at the launch fee, decays, and funds the holder pool", async () => {
+    const setSniperTax = (tax: Record<string, any>) =>
+      (program.methods as any)
+        .updateSniperTax(tax)
+        .accounts({ authority: deployer.publicKey, protocolConfig: protocolConfigPda })
+        .rpc();
+    // 11% launch fee = 10% tax over the 1% trade fee, gone after 20s
+    await setSniperTax({
+      startFeeBps: new anchor.BN(1_100),
+      durationSeconds: new anchor.BN(20),
+      decay: { linear: {} },
+    });
+    const launch = await launchOpen();
+    await setSniperTax({
+      startFeeBps: new anchor.BN(0),
+      durationSeconds: new anchor.BN(0),
+      decay: { linear: {} },
+    });
+
+    const curve = await (program.account as any).bondingCurve.fetch(launch.bondingCurve);
+    assert.equal(curve.sniperTax.startFeeBps.toNumber(), 1_100);
+    const opensAt = curve.tradingOpensAt.toNumber();
+    const solAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+    const quoteTax = async () =>
+      (
+        await (program.methods as any)
+          .quoteBuy(solAmount)
+          .accounts({ bondingCurve: launch.bondingCurve })
+          .view()
+      ).fees.sniperTax.toNumber();
+
+    // Linear from 10% of the buy at open: 10% × (20 - elapsed) / 20
+    const elapsed = (await chainTime()) - opensAt;
+    const early = await quoteTax();
+    const maxTax = solAmount.toNumber() / 10;
+    assert.isAbove(early, 0);
+    assert.isAtMost(early, maxTax);
+    assert.isAtMost(early, Math.ceil((maxTax * (20 - elapsed)) / 20) + 1);
+
+    const holder = await fundedWallet(1);
+    await (program.methods as any)
+      .buyTokens(solAmount, new anchor.BN(0))
+      .accounts(buyAccounts(launch.mint, launch.bondingCurve, holder.publicKey))
+      .signers([holder])
+      .rpc();
+    const bought = await (program.account as any).bondingCurve.fetch(launch.bondingCurve);
+    const paid = bought.holderPool.sub(curve.holderPool).toNumber();
+    assert.isAbove(paid, 0);
+    assert.isAtMost(paid, early);
+
+    await waitUntil(opensAt + 10);
+    const later = await quoteTax();
+    assert.isAtMost(later, paid);
+    assert.isAtMost(later, maxTax / 2);
+
+    await waitUntil(opensAt + 20);
+    assert.equal(await quoteTax(), 0);
+
+    console.log(`  Sniper tax ${early} → ${later} → 0 lamports on a 0.1 SOL buy`);
+  });
+
+  it("14b. Sniper tax and protocol fee stay under one combined ceiling", async () => {
+    const defaults = (
+      await (program.account as any).protocolConfig.fetch(protocolConfigPda)
+    ).feeSchedule;
+    const updateFeeSchedule = (schedule: Record<string, any>) =>
+      (program.methods as any)
+        .updateFeeSchedule(schedule)
+        .accounts({ authority: deployer.publicKey, protocolConfig: protocolConfigPda })
+        .rpc();
+    const setSniperTax = (startFeeBps: number) =>
+      (program.methods as any)
+        .updateSniperTax({
+          startFeeBps: new anchor.BN(startFeeBps),
+          durationSeconds: new anchor.BN(startFeeBps > 0 ? 20 : 0),
+          decay: { linear: {} },
+        })
+        .accounts({ authority: deployer.publicKey, protocolConfig: protocolConfigPda })
+        .rpc();
+
+    // A 50% launch fee leaves no room for a protocol fee on top
+    await setSniperTax(5_000);
+    try {
+      await updateFeeSchedule({ ...defaults, protocolFeeBps: new anchor.BN(100) });
+      assert.fail("Should have thrown InvalidFeeSchedule");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidFeeSchedule");
+    }
+
+    // With a 1% protocol fee the launch fee tops out at 49%
+    await setSniperTax(0);
+    await updateFeeSchedule({ ...defaults, protocolFeeBps: new anchor.BN(100) });
+    await setSniperTax(4_900);
+    try {
+      await setSniperTax(4_901);
+      assert.fail("Should have thrown InvalidFeeSchedule");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidFeeSchedule");
+    }
+
+    await setSniperTax(0);
+    await updateFeeSchedule(defaults);
+    console.log("  Launch fee plus protocol fee capped at 50%");
+  });
+
+  // ─── 15. Post-Graduation Collateral Release ─────────────────────
+
+  it("15. Deployer claims collateral as it unlocks after graduation", async () => {
+    const setCollateralLock = (lock: Record<string, any>) =>
+      (program.methods as any)
+        .updateCollateralLock(lock)
+        .accounts({ authority: deployer.publicKey, protocolConfig: protocolConfigPda })
+        .rpc();
+    // No safety period, then a linear release over 10s
+    await setCollateralLock({
+      lockSeconds: new anchor.BN(0),
+      unlockSeconds: new anchor.BN(10),
+      schedule: { linear: {} },
+    });
+    const launch = await launchOpen();
+    await setCollateralLock({
+      lockSeconds: new anchor.BN(86_400),
+      unlockSeconds: new anchor.BN(604_800),
+      schedule: { linear: {} },
+    });
+
+    // Graduate with one buy over a lowered threshold
+    await (program.methods as any)
+      .setCurveGraduationThreshold(new anchor.BN(0.05 * LAMPORTS_PER_SOL))
+      .accounts({
+        authority: deployer.publicKey,
+        protocolConfig: protocolConfigPda,
+        bondingCurve: launch.bondingCurve,
+      })
+      .rpc();
+    const holder = await fundedWallet(1);
+    await (program.methods as any)
+      .buyTokens(new anchor.BN(0.1 * LAMPORTS_PER_SOL), new anchor.BN(0))
+      .accounts(buyAccounts(launch.mint, launch.bondingCurve, holder.publicKey))
+      .signers([holder])
+      .rpc();
+    const curve = await (program.account as any).bondingCurve.fetch(launch.bondingCurve);
+    assert.isTrue(curve.graduated);
+    const graduatedAt = curve.graduatedAt.toNumber();
+
+    const claim = async () => {
+      const before = await provider.connection.getBalance(launch.escrowVault);
+      await (program.methods as any)
+        .claimUnlockedCollateral()
+        .accounts({
+          deployer: deployer.publicKey,
+          escrowVault: launch.escrowVault,
+          bondingCurve: launch.bondingCurve,
+        })
+        .rpc();
+      return before - (await provider.connection.getBalance(launch.escrowVault));
+    };
+    const collateral = 0.1 * LAMPORTS_PER_SOL;
+
+    // Part-way through the release only part of the collateral is claimable
+    await waitUntil(graduatedAt + 4);
+    const partial = await claim();
+    assert.isAbove(partial, 0);
+    assert.isBelow(partial, collateral);
+
+    await waitUntil(graduatedAt + 10);
+    const rest = await claim();
+    assert.equal(partial + rest, collateral);
+    const escrow = await (program.account as any).escrowVault.fetch(launch.escrowVault);
+    assert.equal(escrow.collateralClaimed.toNumber(), collateral);
+
+    try {
+      await claim();
+      assert.fail("Should have thrown NoCollateralToClaim");
+    } catch (err: any) {
+      assert.include(err.toString(), "NoCollateralToClaim");
+    }
+
+    console.log(`  Claimed ${partial} then ${rest} lamports of collateral`);
+  });
+
+  // ─── 16. Escrow Slashing ────────────────────────────────────────
+
+  it("16. Slashed collateral funds holder refunds, the deployer wallet is excluded", async () => {
+    const launch = await launchOpen();
+    await (program.methods as any)
+      .setCurveGraduationThreshold(new anchor.BN(0.05 * LAMPORTS_PER_SOL))
+      .accounts({
+        authority: deployer.publicKey,
+        protocolConfig: protocolConfigPda,
+        bondingCurve: launch.bondingCurve,
+      })
+      .rpc();
+
+    // A holder buys, then the deployer's buy graduates the curve
+    const holder = await fundedWallet(1);
+    await (program.methods as any)
+      .buyTokens(new anchor.BN(0.02 * LAMPORTS_PER_SOL), new anchor.BN(0))
+      .accounts(buyAccounts(launch.mint, launch.bondingCurve, holder.publicKey))
+      .signers([holder])
+      .rpc();
+    await (program.methods as any)
+      .buyTokens(new anchor.BN(0.1 * LAMPORTS_PER_SOL), new anchor.BN(0))
+      .accounts(buyAccounts(launch.mint, launch.bondingCurve, deployer.publicKey))
+      .rpc();
+    const graduated = await (program.account as any).bondingCurve.fetch(launch.bondingCurve);
+    assert.isTrue(graduated.graduated);
+
+    // Inside the safety period the deployer moves 60% of their tokens to a
+    // fresh wallet: missing from the deployer's wallet, so counted as sold
+    const bought = await tokenBalance(launch.mint, deployer.publicKey);
+    const moved = (bought * 6n) / 10n;
+    const fresh = await fundedWallet(1);
+    const freshAta = await createAssociatedTokenAccount(
+      provider.connection,
+      fresh,
+      launch.mint,
+      fresh.publicKey
+    );
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createTransferInstruction(
+          getAssociatedTokenAddressSync(launch.mint, deployer.publicKey),
+          freshAta,
+          deployer.publicKey,
+          moved
+        )
+      )
+    );
+
+    const escrowBefore = await provider.connection.getBalance(launch.escrowVault);
+    const curveBefore = await provider.connection.getBalance(launch.bondingCurve);
+    await (program.methods as any)
+      .slashEscrow()
+      .accounts({
+        slasher: holder.publicKey,
+        escrowVault: launch.escrowVault,
+        bondingCurve: launch.bondingCurve,
+        deployerTokenAccount: getAssociatedTokenAddressSync(launch.mint, deployer.publicKey),
+      })
+      .signers([holder])
+      .rpc();
+
+    // All locked collateral moves into the curve's slashed pool
+    const collateral = 0.1 * LAMPORTS_PER_SOL;
+    const slashed = await (program.account as any).bondingCurve.fetch(launch.bondingCurve);
+    assert.isTrue(slashed.slashed);
+    assert.equal(slashed.slashedCollateral.toNumber(), collateral);
+    assert.equal(escrowBefore - (await provider.connection.getBalance(launch.escrowVault)), collateral);
+    assert.equal((await provider.connection.getBalance(launch.bondingCurve)) - curveBefore, collateral);
+
+    const refund = (wallet: PublicKey, signers: Keypair[]) =>
+      (program.methods as any)
+        .processRefund()
+        .accounts({
+          buyer: wallet,
+          escrowVault: launch.escrowVault,
+          bondingCurve: launch.bondingCurve,
+          tokenMint: launch.mint,
+          buyerTokenAccount: getAssociatedTokenAddressSync(launch.mint, wallet),
+          // Token-share refunds need no position; the fresh wallet has none
+          position: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(signers)
+        .rpc();
+
+    try {
+      await refund(deployer.publicKey, []);
+      assert.fail("Should have thrown Unauthorized");
+    } catch (err: any) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+
+    // Holders draw on the slashed pool pro-rata
+    const refundShare = async (wallet: Keypair) => {
+      const curve = await (program.account as any).bondingCurve.fetch(launch.bondingCurve);
+      const tokens = await tokenBalance(launch.mint, wallet.publicKey);
+      const expected =
+        (tokens * BigInt(curve.slashedCollateral.toString())) /
+        BigInt(curve.currentSupply.toString());
+      const before = await provider.connection.getBalance(wallet.publicKey);
+      await refund(wallet.publicKey, [wallet]);
+      const received = (await provider.connection.getBalance(wallet.publicKey)) - before;
+      // The refund transaction's fee comes out of the holder's balance
+      assert.approximately(received, Number(expected), 10_000);
+      assert.equal(await tokenBalance(launch.mint, wallet.publicKey), 0n);
+      return received;
+    };
+    const holderShare = await refundShare(holder);
+    assert.isAbove(holderShare, 0);
+
+    // The exclusion keys on the deployer wallet: tokens moved out before the
+    // slash are refunded like any holder's
+    const freshShare = await refundShare(fresh);
+    assert.isAbove(freshShare, 0);
+
+    console.log(`  Slashed ${collateral} lamports; holder got ${holderShare}, moved tokens got ${freshShare}`);
+  });
 });
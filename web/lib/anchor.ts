@@ -23,6 +23,9 @@ export const PROGRAM_ID = new PublicKey(
 const ESCROW_SEED = Buffer.from("escrow");
 const CURVE_SEED = Buffer.from("curve");
 const PROTOCOL_SEED = Buffer.from("protocol");
+const EVENT_AUTHORITY_SEED = Buffer.from("__event_authority");
+const POSITION_SEED = Buffer.from("position");
+const REFUND_SNAPSHOT_SEED = Buffer.from("refund_snapshot");
 
 const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
   "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s",
@@ -93,6 +96,23 @@ export function getCurvePDA(tokenMint: PublicKey): [PublicKey, number] {
   );
 }
 
+export function getPositionPDA(
+  bondingCurve: PublicKey,
+  owner: PublicKey,
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [POSITION_SEED, bondingCurve.toBuffer(), owner.toBuffer()],
+    PROGRAM_ID,
+  );
+}
+
+export function getRefundSnapshotPDA(escrowVault: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [REFUND_SNAPSHOT_SEED, escrowVault.toBuffer()],
+    PROGRAM_ID,
+  );
+}
+
 export function getProtocolConfigPDA(): [PublicKey, number] {
   return PublicKey.findProgramAddressSync(
     [PROTOCOL_SEED],
@@ -100,6 +120,11 @@ export function getProtocolConfigPDA(): [PublicKey, number] {
   );
 }
 
+/** Signer PDA of the self-CPI that carries `emit_cpi!` events */
+export function getEventAuthorityPDA(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([EVENT_AUTHORITY_SEED], PROGRAM_ID);
+}
+
 export function getMetadataPDA(tokenMint: PublicKey): PublicKey {
   const [pda] = PublicKey.findProgramAddressSync(
     [
@@ -154,7 +179,7 @@ export interface LaunchResult {
   txSig: string;
 }
 
-/** Launch a new token: create_escrow + init_bonding_curve batched in 1 TX (1 wallet approval) */
+/** Launch a new token: mint + metadata + curve + escrow in one atomic launch_token IX */
 export async function launchToken(
   program: FyrstProgram,
   deployer: PublicKey,
@@ -164,54 +189,20 @@ export async function launchToken(
   uri: string,
   durationSeconds: BN = new BN(86_400),
 ): Promise<LaunchResult> {
-  const provider = program.provider as AnchorProvider;
-  const tokenMintKeypair = Keypair.generate();
-  const tokenMint = tokenMintKeypair.publicKey;
-
-  const [escrowVault] = getEscrowPDA(deployer, tokenMint);
-  const [bondingCurve] = getCurvePDA(tokenMint);
-  const metadataAccount = getMetadataPDA(tokenMint);
-  const curveTokenAccount = getAssociatedTokenAddressSync(tokenMint, bondingCurve, true);
-
-  const methods = program.methods as any; // eslint-disable-line @typescript-eslint/no-explicit-any
-
-  const escrowIx = await methods
-    .createEscrow(collateralLamports, durationSeconds)
-    .accounts({
-      deployer,
-      tokenMint,
-      escrowVault,
-      systemProgram: SystemProgram.programId,
-    })
-    .instruction();
-
-  const curveIx = await methods
-    .initBondingCurve(name, symbol, uri)
-    .accounts({
-      deployer,
-      tokenMint,
-      bondingCurve,
-      curveTokenAccount,
-      metadataAccount,
-      metadataProgram: TOKEN_METADATA_PROGRAM_ID,
-      tokenProgram: TOKEN_PROGRAM_ID,
-      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-      systemProgram: SystemProgram.programId,
-      rent: SYSVAR_RENT_PUBKEY,
-    })
-    .instruction();
-
-  // Single TX: escrow + bonding curve (1 wallet signature)
-  const tx = new Transaction()
-    .add(ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }))
-    .add(await getPriorityFeeIx(provider.connection))
-    .add(escrowIx, curveIx);
-  const txSig = await provider.sendAndConfirm(tx, [tokenMintKeypair]);
-
-  return { tokenMintKeypair, txSig };
+  return sendLaunchToken(
+    program,
+    deployer,
+    collateralLamports,
+    name,
+    symbol,
+    uri,
+    durationSeconds,
+    new BN(0),
+    new BN(0),
+  );
 }
 
-/** Launch + initial buy: escrow + curve + buy in 1 TX (1 wallet approval) */
+/** Launch + initial buy: launch_token with initial_buy_sol set (1 wallet approval) */
 export async function launchAndBuy(
   program: FyrstProgram,
   deployer: PublicKey,
@@ -222,6 +213,40 @@ export async function launchAndBuy(
   buyAmountLamports: BN,
   durationSeconds: BN = new BN(86_400),
   slippageBps: number = DEFAULT_SLIPPAGE_BPS,
+): Promise<LaunchResult> {
+  // Calculate expected tokens using known initial reserves
+  const tradeFee = buyAmountLamports.mul(new BN(100)).div(new BN(10_000));
+  const netSol = buyAmountLamports.sub(tradeFee);
+  const expectedTokens = estimateBuyTokens(
+    INITIAL_VIRTUAL_TOKEN_RESERVES,
+    INITIAL_VIRTUAL_SOL_RESERVES,
+    netSol,
+  );
+  const minTokensOut = expectedTokens.muln(10_000 - slippageBps).divn(10_000);
+
+  return sendLaunchToken(
+    program,
+    deployer,
+    collateralLamports,
+    name,
+    symbol,
+    uri,
+    durationSeconds,
+    buyAmountLamports,
+    minTokensOut,
+  );
+}
+
+async function sendLaunchToken(
+  program: FyrstProgram,
+  deployer: PublicKey,
+  collateralLamports: BN,
+  name: string,
+  symbol: string,
+  uri: string,
+  durationSeconds: BN,
+  initialBuySol: BN,
+  minTokensOut: BN,
 ): Promise<LaunchResult> {
   const provider = program.provider as AnchorProvider;
   const tokenMintKeypair = Keypair.generate();
@@ -232,7 +257,8 @@ export async function launchAndBuy(
   const metadataAccount = getMetadataPDA(tokenMint);
   const [protocolConfig] = getProtocolConfigPDA();
   const curveTokenAccount = getAssociatedTokenAddressSync(tokenMint, bondingCurve, true);
-  const buyerTokenAccount = getAssociatedTokenAddressSync(tokenMint, deployer);
+  const deployerTokenAccount = getAssociatedTokenAddressSync(tokenMint, deployer);
+  const [deployerPosition] = getPositionPDA(bondingCurve, deployer);
 
   // Fetch protocol config for treasury + ops_wallet addresses
   const configAccount = await (program.account as any).protocolConfig.fetch(protocolConfig); // eslint-disable-line @typescript-eslint/no-explicit-any
@@ -241,74 +267,62 @@ export async function launchAndBuy(
 
   const methods = program.methods as any; // eslint-disable-line @typescript-eslint/no-explicit-any
 
-  // IX 1: Create escrow
-  const escrowIx = await methods
-    .createEscrow(collateralLamports, durationSeconds)
-    .accounts({
-      deployer,
-      tokenMint,
-      escrowVault,
-      systemProgram: SystemProgram.programId,
+  const launchIx = await methods
+    .launchToken({
+      name,
+      symbol,
+      uri,
+      collateralAmount: collateralLamports,
+      durationSeconds,
+      initialBuySol,
+      minTokensOut,
+      curveKind: { constantProduct: {} },
+      totalSupply: new BN(1_000_000_000),
+      decimals: TOKEN_DECIMALS,
+      virtualSol: INITIAL_VIRTUAL_SOL_RESERVES,
+      curveTokenBps: new BN(7931),
+      tradingOpensAt: new BN(0),
+      presaleRoot: Array(32).fill(0),
+      presaleSeconds: new BN(0),
+      openingSeconds: new BN(0),
+      maxWalletBps: new BN(0),
+      refundMode: { tokenShare: {} },
     })
-    .instruction();
-
-  // IX 2: Init bonding curve (constant product AMM — no base_price/slope)
-  const curveIx = await methods
-    .initBondingCurve(name, symbol, uri)
     .accounts({
       deployer,
       tokenMint,
       bondingCurve,
       curveTokenAccount,
+      escrowVault,
+      deployerTokenAccount,
+      deployerPosition,
       metadataAccount,
       metadataProgram: TOKEN_METADATA_PROGRAM_ID,
-      tokenProgram: TOKEN_PROGRAM_ID,
-      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-      systemProgram: SystemProgram.programId,
-      rent: SYSVAR_RENT_PUBKEY,
-    })
-    .instruction();
-
-  // Calculate expected tokens using known initial reserves
-  const tradeFee = buyAmountLamports.mul(new BN(100)).div(new BN(10_000));
-  const netSol = buyAmountLamports.sub(tradeFee);
-  const expectedTokens = estimateBuyTokens(
-    INITIAL_VIRTUAL_TOKEN_RESERVES,
-    INITIAL_VIRTUAL_SOL_RESERVES,
-    netSol,
-  );
-  const minTokensOut = expectedTokens.muln(10_000 - slippageBps).divn(10_000);
-
-  // IX 3: Buy tokens
-  const buyIx = await methods
-    .buyTokens(buyAmountLamports, minTokensOut)
-    .accounts({
-      buyer: deployer,
-      bondingCurve,
-      tokenMint,
-      curveTokenAccount,
-      buyerTokenAccount,
       protocolConfig,
       treasury,
       opsWallet,
+      deployerFyrstAccount: null,
+      feeWaiver: null,
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
     })
     .instruction();
 
-  // Single TX: escrow + curve + buy (1 wallet signature)
   const tx = new Transaction()
     .add(ComputeBudgetProgram.setComputeUnitLimit({ units: 600_000 }))
     .add(await getPriorityFeeIx(provider.connection))
-    .add(escrowIx, curveIx, buyIx);
+    .add(launchIx);
   const txSig = await provider.sendAndConfirm(tx, [tokenMintKeypair]);
 
   return { tokenMintKeypair, txSig };
 }
 
 /** Estimate tokens received for a buy using constant product AMM.
- *  tokens_out = virtualToken - k / (virtualSol + netSol) */
+ *  tokens_out = virtualToken - ceil(k / (virtualSol + netSol)) */
 export function estimateBuyTokens(
   virtualTokenReserves: BN,
   virtualSolReserves: BN,
@@ -317,12 +331,12 @@ export function estimateBuyTokens(
   if (netSolLamports.isZero()) return new BN(0);
   const k = virtualTokenReserves.mul(virtualSolReserves);
   const newVs = virtualSolReserves.add(netSolLamports);
-  const newVt = k.div(newVs);
+  const newVt = k.add(newVs).subn(1).div(newVs);
   return virtualTokenReserves.sub(newVt);
 }
 
 /** Estimate SOL received for a sell using constant product AMM.
- *  sol_out = virtualSol - k / (virtualToken + tokenAmount) */
+ *  sol_out = virtualSol - ceil(k / (virtualToken + tokenAmount)) */
 export function estimateSellSol(
   virtualTokenReserves: BN,
   virtualSolReserves: BN,
@@ -331,10 +345,66 @@ export function estimateSellSol(
   if (tokenAmount.isZero()) return new BN(0);
   const k = virtualTokenReserves.mul(virtualSolReserves);
   const newVt = virtualTokenReserves.add(tokenAmount);
-  const newVs = k.div(newVt);
+  const newVs = k.add(newVt).subn(1).div(newVt);
   return virtualSolReserves.sub(newVs);
 }
 
+/** Estimate gross SOL (1% fee included) needed to buy an exact token amount.
+ *  Mirrors on-chain inverse: net = ceil(k / (vt - tokens)) - vs, gross = ceil(net / 0.99) */
+export function estimateBuyCost(
+  virtualTokenReserves: BN,
+  virtualSolReserves: BN,
+  tokenAmount: BN,
+): BN {
+  if (tokenAmount.isZero()) return new BN(0);
+  const k = virtualTokenReserves.mul(virtualSolReserves);
+  const newVt = virtualTokenReserves.sub(tokenAmount);
+  const newVs = k.add(newVt).subn(1).div(newVt);
+  const netSol = newVs.sub(virtualSolReserves);
+  return netSol.muln(10_000).addn(9_900 - 1).divn(9_900);
+}
+
+/** Estimate tokens needed to receive an exact net SOL amount (1% fee included).
+ *  Mirrors on-chain inverse: gross = ceil(net / 0.99), tokens = ceil(k / (vs - gross)) - vt */
+export function estimateSellTokensForSol(
+  virtualTokenReserves: BN,
+  virtualSolReserves: BN,
+  solOut: BN,
+): BN {
+  if (solOut.isZero()) return new BN(0);
+  const gross = solOut.muln(10_000).addn(9_900 - 1).divn(9_900);
+  const k = virtualTokenReserves.mul(virtualSolReserves);
+  const newVs = virtualSolReserves.sub(gross);
+  const newVt = k.add(newVs).subn(1).div(newVs);
+  return newVt.sub(virtualTokenReserves);
+}
+
+/** Authoritative buy quote: simulates the program's read-only `quote_buy` */
+export async function quoteBuy(
+  program: FyrstProgram,
+  tokenMint: PublicKey,
+  solAmountLamports: BN,
+): Promise<{ solAmount: BN; netSol: BN; tokens: BN }> {
+  const [bondingCurve] = getCurvePDA(tokenMint);
+  return await (program.methods as any) // eslint-disable-line @typescript-eslint/no-explicit-any
+    .quoteBuy(solAmountLamports)
+    .accounts({ bondingCurve })
+    .view();
+}
+
+/** Authoritative sell quote: simulates the program's read-only `quote_sell` */
+export async function quoteSell(
+  program: FyrstProgram,
+  tokenMint: PublicKey,
+  tokenAmount: BN,
+): Promise<{ tokens: BN; grossSol: BN; netSol: BN }> {
+  const [bondingCurve] = getCurvePDA(tokenMint);
+  return await (program.methods as any) // eslint-disable-line @typescript-eslint/no-explicit-any
+    .quoteSell(tokenAmount)
+    .accounts({ bondingCurve })
+    .view();
+}
+
 /** Default slippage tolerance in basis points (1% = 100 bps) */
 export const DEFAULT_SLIPPAGE_BPS = 100;
 
@@ -348,42 +418,125 @@ export async function buyTokens(
 ): Promise<string> {
   const provider = program.provider as AnchorProvider;
   const [bondingCurve] = getCurvePDA(tokenMint);
-  const [protocolConfig] = getProtocolConfigPDA();
 
-  // Fetch protocol config for treasury + ops_wallet addresses
-  const configAccount = await (program.account as any).protocolConfig.fetch(protocolConfig); // eslint-disable-line @typescript-eslint/no-explicit-any
-  const treasury = configAccount.treasury as PublicKey;
-  const opsWallet = configAccount.opsWallet as PublicKey;
+  // Quote on-chain so curve shape and any early-buy sniper tax are priced in
+  const { tokens: expectedTokens } = await quoteBuy(program, tokenMint, solAmountLamports);
+  const minTokensOut = expectedTokens.muln(10_000 - slippageBps).divn(10_000);
 
-  // Fetch current curve state
-  const curveAccount = await (program.account as any).bondingCurve.fetch(bondingCurve); // eslint-disable-line @typescript-eslint/no-explicit-any
-  const ca = curveAccount as BondingCurveData;
+  const curveTokenAccount = getAssociatedTokenAddressSync(tokenMint, bondingCurve, true);
+  const buyerTokenAccount = getAssociatedTokenAddressSync(tokenMint, buyer);
+  const [position] = getPositionPDA(bondingCurve, buyer);
 
-  // Calculate fees and net SOL (1% total fee)
-  const tradeFee = solAmountLamports.mul(new BN(100)).div(new BN(10_000));
-  const netSol = solAmountLamports.sub(tradeFee);
+  const buyIx = await (program.methods as any) // eslint-disable-line @typescript-eslint/no-explicit-any
+    .buyTokens(solAmountLamports, minTokensOut)
+    .accounts({
+      buyer,
+      bondingCurve,
+      tokenMint,
+      curveTokenAccount,
+      buyerTokenAccount,
+      position,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
+    })
+    .instruction();
+
+  const tx = new Transaction()
+    .add(ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }))
+    .add(await getPriorityFeeIx(provider.connection))
+    .add(buyIx);
+  return await provider.sendAndConfirm(tx, []);
+}
+
+/** Presale buy for an allowlisted wallet. `allocation` and `proof` come from
+ *  the launch's off-chain allowlist tree (leaf = keccak256(wallet || allocation_le)). */
+export async function presaleBuy(
+  program: FyrstProgram,
+  buyer: PublicKey,
+  tokenMint: PublicKey,
+  solAmountLamports: BN,
+  allocation: BN,
+  proof: number[][],
+  slippageBps: number = DEFAULT_SLIPPAGE_BPS,
+): Promise<string> {
+  const provider = program.provider as AnchorProvider;
+  const [bondingCurve] = getCurvePDA(tokenMint);
 
-  // Estimate tokens using constant product AMM
+  // Presale buys pay no sniper tax: price with the plain curve estimate
+  const curveAccount = await (program.account as any).bondingCurve.fetch(bondingCurve); // eslint-disable-line @typescript-eslint/no-explicit-any
+  const ca = curveAccount as BondingCurveData;
+  const netSol = solAmountLamports.sub(solAmountLamports.muln(100).divn(10_000));
   const expectedTokens = estimateBuyTokens(ca.virtualTokenReserves, ca.virtualSolReserves, netSol);
   const minTokensOut = expectedTokens.muln(10_000 - slippageBps).divn(10_000);
 
   const curveTokenAccount = getAssociatedTokenAddressSync(tokenMint, bondingCurve, true);
   const buyerTokenAccount = getAssociatedTokenAddressSync(tokenMint, buyer);
+  const [position] = getPositionPDA(bondingCurve, buyer);
 
   const buyIx = await (program.methods as any) // eslint-disable-line @typescript-eslint/no-explicit-any
-    .buyTokens(solAmountLamports, minTokensOut)
+    .presaleBuy(solAmountLamports, minTokensOut, allocation, proof)
     .accounts({
       buyer,
       bondingCurve,
       tokenMint,
       curveTokenAccount,
       buyerTokenAccount,
-      protocolConfig,
-      treasury,
-      opsWallet,
+      position,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
+    })
+    .instruction();
+
+  const tx = new Transaction()
+    .add(ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }))
+    .add(await getPriorityFeeIx(provider.connection))
+    .add(buyIx);
+  return await provider.sendAndConfirm(tx, []);
+}
+
+/** Buy an exact token amount (clamped on-chain to the curve's remaining tokens) */
+export async function buyExactTokens(
+  program: FyrstProgram,
+  buyer: PublicKey,
+  tokenMint: PublicKey,
+  tokenAmount: BN,
+  slippageBps: number = DEFAULT_SLIPPAGE_BPS,
+): Promise<string> {
+  const provider = program.provider as AnchorProvider;
+  const [bondingCurve] = getCurvePDA(tokenMint);
+
+  // Fetch current curve state
+  const curveAccount = await (program.account as any).bondingCurve.fetch(bondingCurve); // eslint-disable-line @typescript-eslint/no-explicit-any
+  const ca = curveAccount as BondingCurveData;
+
+  const tokens = BN.min(tokenAmount, ca.realTokenReserves);
+  const expectedCost = estimateBuyCost(ca.virtualTokenReserves, ca.virtualSolReserves, tokens);
+  const maxSolIn = expectedCost.muln(10_000 + slippageBps).divn(10_000);
+
+  const curveTokenAccount = getAssociatedTokenAddressSync(tokenMint, bondingCurve, true);
+  const buyerTokenAccount = getAssociatedTokenAddressSync(tokenMint, buyer);
+  const [position] = getPositionPDA(bondingCurve, buyer);
+
+  const buyIx = await (program.methods as any) // eslint-disable-line @typescript-eslint/no-explicit-any
+    .buyExactTokens(tokenAmount, maxSolIn)
+    .accounts({
+      buyer,
+      bondingCurve,
+      tokenMint,
+      curveTokenAccount,
+      buyerTokenAccount,
+      position,
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
     })
     .instruction();
 
@@ -404,25 +557,19 @@ export async function sellTokens(
 ): Promise<string> {
   const provider = program.provider as AnchorProvider;
   const [bondingCurve] = getCurvePDA(tokenMint);
-  const [protocolConfig] = getProtocolConfigPDA();
   const sellerTokenAccount = getAssociatedTokenAddressSync(tokenMint, seller);
   const curveTokenAccount = getAssociatedTokenAddressSync(tokenMint, bondingCurve, true);
 
-  // Fetch protocol config for treasury + ops_wallet addresses
-  const configAccount = await (program.account as any).protocolConfig.fetch(protocolConfig); // eslint-disable-line @typescript-eslint/no-explicit-any
-  const treasury = configAccount.treasury as PublicKey;
-  const opsWallet = configAccount.opsWallet as PublicKey;
-
   // Fetch curve state for expected SOL calculation
   const curveAccount = await (program.account as any).bondingCurve.fetch(bondingCurve); // eslint-disable-line @typescript-eslint/no-explicit-any
   const ca = curveAccount as BondingCurveData;
 
   // Estimate SOL received using constant product AMM (1% total fee)
-  let expectedGross = estimateSellSol(ca.virtualTokenReserves, ca.virtualSolReserves, tokenAmount);
+  const expectedGross = estimateSellSol(ca.virtualTokenReserves, ca.virtualSolReserves, tokenAmount);
 
-  // Cap gross at reserve balance (matches on-chain cap)
-  if (expectedGross.gt(ca.reserveBalance)) {
-    expectedGross = ca.reserveBalance;
+  // On-chain sell errors instead of capping when the reserve can't cover it
+  if (expectedGross.gt(BN.min(ca.realSolReserves, ca.reserveBalance))) {
+    throw new Error("Curve SOL reserve cannot cover this sale");
   }
 
   const tradeFee = expectedGross.muln(100).divn(10_000); // 1%
@@ -438,11 +585,11 @@ export async function sellTokens(
       tokenMint,
       curveTokenAccount,
       sellerTokenAccount,
-      protocolConfig,
-      treasury,
-      opsWallet,
+      position: getPositionPDA(bondingCurve, seller)[0],
       tokenProgram: TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
     })
     .instruction();
 
@@ -453,7 +600,50 @@ export async function sellTokens(
   return await provider.sendAndConfirm(tx, []);
 }
 
-/** Process burn-to-refund: burns buyer's tokens and returns pro-rata SOL from escrow */
+/** Sell for an exact net SOL amount — never gives up more tokens than needed */
+export async function sellForExactSol(
+  program: FyrstProgram,
+  seller: PublicKey,
+  tokenMint: PublicKey,
+  solOutLamports: BN,
+  slippageBps: number = DEFAULT_SLIPPAGE_BPS,
+): Promise<string> {
+  const provider = program.provider as AnchorProvider;
+  const [bondingCurve] = getCurvePDA(tokenMint);
+  const sellerTokenAccount = getAssociatedTokenAddressSync(tokenMint, seller);
+  const curveTokenAccount = getAssociatedTokenAddressSync(tokenMint, bondingCurve, true);
+
+  // Fetch curve state for expected token calculation
+  const curveAccount = await (program.account as any).bondingCurve.fetch(bondingCurve); // eslint-disable-line @typescript-eslint/no-explicit-any
+  const ca = curveAccount as BondingCurveData;
+
+  const expectedTokens = estimateSellTokensForSol(ca.virtualTokenReserves, ca.virtualSolReserves, solOutLamports);
+  const maxTokensIn = expectedTokens.muln(10_000 + slippageBps).divn(10_000);
+
+  const sellIx = await (program.methods as any) // eslint-disable-line @typescript-eslint/no-explicit-any
+    .sellForExactSol(solOutLamports, maxTokensIn)
+    .accounts({
+      seller,
+      bondingCurve,
+      tokenMint,
+      curveTokenAccount,
+      sellerTokenAccount,
+      position: getPositionPDA(bondingCurve, seller)[0],
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
+    })
+    .instruction();
+
+  const tx = new Transaction()
+    .add(ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 }))
+    .add(await getPriorityFeeIx(provider.connection))
+    .add(sellIx);
+  return await provider.sendAndConfirm(tx, []);
+}
+
+/** Process burn-to-refund: burns buyer's tokens and returns pro-rata SOL from escrow and the curve reserve */
 export async function processRefund(
   program: FyrstProgram,
   buyer: PublicKey,
@@ -464,6 +654,10 @@ export async function processRefund(
   const [bondingCurve] = getCurvePDA(tokenMint);
   const buyerTokenAccount = getAssociatedTokenAddressSync(tokenMint, buyer);
 
+  // Position is required for contribution refunds; holders without one pass null
+  const [positionPda] = getPositionPDA(bondingCurve, buyer);
+  const position = (await program.provider.connection.getAccountInfo(positionPda)) ? positionPda : null;
+
   return await (program.methods as any) // eslint-disable-line @typescript-eslint/no-explicit-any
     .processRefund()
     .accounts({
@@ -472,8 +666,88 @@ export async function processRefund(
       bondingCurve,
       tokenMint,
       buyerTokenAccount,
+      position,
       tokenProgram: TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
+    })
+    .rpc();
+}
+
+/** One holder's leaf in a refund snapshot: keccak(index_le || wallet || weight_le) */
+export interface SnapshotClaim {
+  holder: PublicKey;
+  index: number;
+  weight: BN;
+  proof: number[][];
+}
+
+/** Pay one holder's snapshot refund by Merkle proof (permissionless, no token burn) */
+export async function claimSnapshotRefund(
+  program: FyrstProgram,
+  deployer: PublicKey,
+  tokenMint: PublicKey,
+  claim: SnapshotClaim,
+): Promise<string> {
+  const [escrowVault] = getEscrowPDA(deployer, tokenMint);
+  const [bondingCurve] = getCurvePDA(tokenMint);
+  const [refundSnapshot] = getRefundSnapshotPDA(escrowVault);
+
+  return await (program.methods as any) // eslint-disable-line @typescript-eslint/no-explicit-any
+    .claimSnapshotRefund({ index: claim.index, weight: claim.weight, proof: claim.proof })
+    .accounts({ holder: claim.holder, refundSnapshot, bondingCurve })
+    .rpc();
+}
+
+/** Push snapshot refunds to a batch of holder wallets (permissionless crank; already-paid leaves are skipped) */
+export async function pushSnapshotRefunds(
+  program: FyrstProgram,
+  cranker: PublicKey,
+  deployer: PublicKey,
+  tokenMint: PublicKey,
+  claims: SnapshotClaim[],
+): Promise<string> {
+  const [escrowVault] = getEscrowPDA(deployer, tokenMint);
+  const [bondingCurve] = getCurvePDA(tokenMint);
+  const [refundSnapshot] = getRefundSnapshotPDA(escrowVault);
+
+  return await (program.methods as any) // eslint-disable-line @typescript-eslint/no-explicit-any
+    .pushSnapshotRefunds(
+      claims.map(({ index, weight, proof }) => ({ index, weight, proof })),
+    )
+    .accounts({ cranker, refundSnapshot, bondingCurve })
+    .remainingAccounts(
+      claims.map(({ holder }) => ({ pubkey: holder, isSigner: false, isWritable: true })),
+    )
+    .rpc();
+}
+
+/** Close a refund snapshot after its claim period (permissionless). Unclaimed refunds go to treasury. */
+export async function closeRefundSnapshot(
+  program: FyrstProgram,
+  deployer: PublicKey,
+  tokenMint: PublicKey,
+): Promise<string> {
+  const [escrowVault] = getEscrowPDA(deployer, tokenMint);
+  const [bondingCurve] = getCurvePDA(tokenMint);
+  const [refundSnapshot] = getRefundSnapshotPDA(escrowVault);
+  const [protocolConfig] = getProtocolConfigPDA();
+
+  const configAccount = await (program.account as any).protocolConfig.fetch(protocolConfig); // eslint-disable-line @typescript-eslint/no-explicit-any
+  const treasury = configAccount.treasury as PublicKey;
+
+  return await (program.methods as any) // eslint-disable-line @typescript-eslint/no-explicit-any
+    .closeRefundSnapshot()
+    .accounts({
+      deployer,
+      escrowVault,
+      bondingCurve,
+      refundSnapshot,
+      protocolConfig,
+      treasury,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
     })
     .rpc();
 }
@@ -491,11 +765,57 @@ export async function claimFees(
     .accounts({
       deployer,
       bondingCurve,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
+    })
+    .rpc();
+}
+
+/** Claim collateral unlocked since graduation (the locked rest stays in escrow) */
+export async function claimUnlockedCollateral(
+  program: FyrstProgram,
+  deployer: PublicKey,
+  tokenMint: PublicKey,
+): Promise<string> {
+  const [escrowVault] = getEscrowPDA(deployer, tokenMint);
+  const [bondingCurve] = getCurvePDA(tokenMint);
+
+  return await (program.methods as any) // eslint-disable-line @typescript-eslint/no-explicit-any
+    .claimUnlockedCollateral()
+    .accounts({
+      deployer,
+      escrowVault,
+      bondingCurve,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
     })
     .rpc();
 }
 
-/** Release escrow back to deployer (requires token graduation) */
+/** Slash a dumping deployer's locked collateral to the holders (permissionless) */
+export async function slashEscrow(
+  program: FyrstProgram,
+  slasher: PublicKey,
+  deployer: PublicKey,
+  tokenMint: PublicKey,
+): Promise<string> {
+  const [escrowVault] = getEscrowPDA(deployer, tokenMint);
+  const [bondingCurve] = getCurvePDA(tokenMint);
+
+  return await (program.methods as any) // eslint-disable-line @typescript-eslint/no-explicit-any
+    .slashEscrow()
+    .accounts({
+      slasher,
+      escrowVault,
+      bondingCurve,
+      deployerTokenAccount: getAssociatedTokenAddressSync(tokenMint, deployer),
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
+    })
+    .rpc();
+}
+
+/** Release escrow back to deployer (requires graduation and a fully unlocked collateral lock) */
 export async function releaseEscrow(
   program: FyrstProgram,
   deployer: PublicKey,
@@ -511,10 +831,25 @@ export async function releaseEscrow(
       escrowVault,
       bondingCurve,
       systemProgram: SystemProgram.programId,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
     })
     .rpc();
 }
 
+/** Mark a launch that missed its deadline as failed (permissionless) */
+export async function markFailed(
+  program: FyrstProgram,
+  tokenMint: PublicKey,
+): Promise<string> {
+  const [bondingCurve] = getCurvePDA(tokenMint);
+
+  return await (program.methods as any) // eslint-disable-line @typescript-eslint/no-explicit-any
+    .markFailed()
+    .accounts({ bondingCurve })
+    .rpc();
+}
+
 /** Expire escrow after deadline (permissionless). 50% deployer refund, 50% treasury buyback+burn. */
 export async function expireEscrow(
   program: FyrstProgram,
@@ -540,6 +875,8 @@ export async function expireEscrow(
       treasury,
       opsWallet,
       systemProgram: SystemProgram.programId,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
     })
     .rpc();
 }
@@ -559,6 +896,8 @@ export async function initProtocol(
       authority,
       protocolConfig,
       systemProgram: SystemProgram.programId,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
     })
     .rpc();
 }
@@ -675,6 +1014,8 @@ export async function graduateToDex(
       observationState,
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      eventAuthority: getEventAuthorityPDA()[0],
+      program: PROGRAM_ID,
     })
     .instruction();
   tx.add(graduateIx);